@@ -0,0 +1,256 @@
+// src-tauri/src/commands/artifacts.rs
+// Detects files written by a script run and registers them as project nodes
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use uuid::Uuid;
+use super::{
+    Node,
+    file_types::{detect_file_type, file_type_config},
+    search_index::update_search_index,
+    sync::{build_file_node, build_folder_node, find_project_root_id},
+    utils::*,
+};
+
+/// Relative path -> (size, modified nanos) for every visible file in a project.
+pub(crate) type ProjectSnapshot = HashMap<String, (u64, u128)>;
+
+// Walks the project the same way sync does: hidden and "__" entries are ignored
+pub(crate) fn snapshot_project(project_dir: &Path) -> ProjectSnapshot {
+    fn walk(dir: &Path, base_dir: &Path, snapshot: &mut ProjectSnapshot) {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') || name.starts_with("__") {
+                continue;
+            }
+
+            if path.is_dir() {
+                walk(&path, base_dir, snapshot);
+            } else if let Ok(metadata) = entry.metadata() {
+                let relative_path = match path.strip_prefix(base_dir) {
+                    Ok(relative) => relative.to_string_lossy().replace('\\', "/"),
+                    Err(_) => continue,
+                };
+                let modified = metadata.modified().ok()
+                    .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|duration| duration.as_nanos())
+                    .unwrap_or(0);
+                snapshot.insert(relative_path, (metadata.len(), modified));
+            }
+        }
+    }
+
+    let mut snapshot = HashMap::new();
+    walk(project_dir, project_dir, &mut snapshot);
+    snapshot
+}
+
+/// Script runs in progress, per project. A snapshot diff only shows which run
+/// wrote a file when nothing else was writing to the project at the same time.
+#[derive(Default)]
+pub struct ProjectRuns {
+    state: Mutex<ProjectRunState>,
+}
+
+#[derive(Default)]
+struct ProjectRunState {
+    active: HashMap<String, HashSet<String>>, // Project id -> run ids
+    overlapped: HashSet<String>, // Runs that shared their project with another writer
+}
+
+impl ProjectRuns {
+    pub(crate) fn start(&self, project_id: &str, run_id: &str) {
+        let mut state = self.state.lock().unwrap();
+        let ProjectRunState { active, overlapped } = &mut *state;
+        let runs = active.entry(project_id.to_string()).or_default();
+        if !runs.is_empty() {
+            overlapped.extend(runs.iter().cloned());
+            overlapped.insert(run_id.to_string());
+        }
+        runs.insert(run_id.to_string());
+    }
+
+    /// Marks every run in the project as overlapped, for writes made outside them.
+    pub(crate) fn touch(&self, project_id: &str) {
+        let mut state = self.state.lock().unwrap();
+        let ProjectRunState { active, overlapped } = &mut *state;
+        if let Some(runs) = active.get(project_id) {
+            overlapped.extend(runs.iter().cloned());
+        }
+    }
+
+    /// Ends a run. Returns whether it had the project to itself, so the files
+    /// its snapshots saw change can be credited to it.
+    pub(crate) fn finish(&self, project_id: &str, run_id: &str) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if let Some(runs) = state.active.get_mut(project_id) {
+            runs.remove(run_id);
+            if runs.is_empty() {
+                state.active.remove(project_id);
+            }
+        }
+        !state.overlapped.remove(run_id)
+    }
+}
+
+/// Returns the (created, modified) files between two snapshots, sorted by path.
+pub(crate) fn diff_snapshots(
+    before: &ProjectSnapshot,
+    after: &ProjectSnapshot,
+) -> (Vec<String>, Vec<String>) {
+    let mut created = Vec::new();
+    let mut modified = Vec::new();
+
+    for (path, state) in after {
+        match before.get(path) {
+            None => created.push(path.clone()),
+            Some(previous) if previous != state => modified.push(path.clone()),
+            _ => {}
+        }
+    }
+
+    created.sort();
+    modified.sort();
+    (created, modified)
+}
+
+/// Adds nodes (and any missing parent folders) for created files, and refreshes
+/// the metadata of tracked files that were modified. When the files come from a
+/// run, every touched node is linked to it through `produced_by`. Returns the
+/// newly created nodes.
+pub(crate) fn register_project_files(
+    app: &AppHandle,
+    project_id: &str,
+    created: &[String],
+    modified: &[String],
+    produced_by: Option<&str>,
+) -> Result<Vec<Node>, String> {
+    if created.is_empty() && modified.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Files written outside a run can't be told apart from what running scripts wrote
+    if produced_by.is_none() {
+        app.state::<ProjectRuns>().touch(project_id);
+    }
+
+    let project_dir = get_project_dir(app, project_id)?;
+    let file_types = file_type_config(app);
+    let guard = lock_project_data(app);
+    let mut data = load_project_data(app)?;
+    let root_id = find_project_root_id(&data, project_id)
+        .ok_or_else(|| "Project root not found".to_string())?;
+
+    // Tracked paths for this project (normalize to forward slashes)
+    let mut tracked: HashMap<String, String> = data.nodes.iter()
+        .filter(|node| node.project_id == project_id)
+        .filter_map(|node| node.file_path.as_ref().map(|path| (path.replace('\\', "/"), node.id.clone())))
+        .collect();
+
+    let mut new_nodes: Vec<Node> = Vec::new();
+    for path in created {
+        let components: Vec<&str> = path.split('/').collect();
+        let mut parent_id = root_id.clone();
+
+        for depth in 0..components.len() {
+            let relative_path = components[..=depth].join("/");
+            if let Some(id) = tracked.get(&relative_path) {
+                parent_id = id.clone();
+                continue;
+            }
+
+            let node_id = Uuid::new_v4().to_string();
+            let mut node = if depth + 1 == components.len() {
+                build_file_node(
+                    node_id.clone(),
+                    &project_dir.join(&relative_path),
+                    components[depth],
+                    &parent_id,
+                    project_id,
+                    &relative_path,
+                    &file_types,
+                )?
+            } else {
+                build_folder_node(node_id.clone(), components[depth], &parent_id, project_id, &relative_path)
+            };
+            node.produced_by = produced_by.map(str::to_string);
+
+            tracked.insert(relative_path, node_id.clone());
+            new_nodes.push(node);
+            parent_id = node_id;
+        }
+    }
+
+    for path in modified {
+        let full_path = project_dir.join(path);
+        let node = data.nodes.iter_mut().find(|node| {
+            node.project_id == project_id &&
+            node.file_path.as_ref().map(|p| p.replace('\\', "/")) == Some(path.clone())
+        });
+
+        if let (Some(node), Ok(metadata)) = (node, fs::metadata(&full_path)) {
+            // A rewrite can change what the file is, e.g. a script replacing a CSV with Parquet
+            let file_type = detect_file_type(&full_path, &file_types);
+            node.is_binary = Some(file_type.is_binary);
+            node.mime_type = Some(file_type.mime);
+            node.category = Some(file_type.category);
+            node.size = Some(metadata.len());
+            node.modified = metadata.modified().ok()
+                .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|duration| duration.as_secs() as i64);
+            if let Some(run_id) = produced_by {
+                node.produced_by = Some(run_id.to_string());
+            }
+        }
+    }
+
+    data.nodes.extend(new_nodes.iter().cloned());
+    save_project_data(app, &data)?;
+    drop(guard);
+    update_search_index(app, project_id, &[created, modified].concat());
+
+    println!("📦 ARTIFACTS: {} created {} and modified {} files ({} new nodes)",
+             produced_by.map_or("Import".to_string(), |run_id| format!("Run {}", run_id)),
+             created.len(), modified.len(), new_nodes.len());
+
+    Ok(new_nodes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_runs_alone_in_their_project_are_attributable() {
+        let runs = ProjectRuns::default();
+        runs.start("p1", "solo");
+        assert!(runs.finish("p1", "solo"));
+
+        // Overlapping runs taint each other, even after the first one ends
+        runs.start("p1", "a");
+        runs.start("p1", "b");
+        runs.start("p2", "other-project");
+        assert!(!runs.finish("p1", "a"));
+        runs.start("p1", "c");
+        assert!(!runs.finish("p1", "b"));
+        assert!(runs.finish("p2", "other-project"));
+
+        assert!(!runs.finish("p1", "c"));
+
+        // So does a download or edit registered while a run is going
+        runs.start("p1", "d");
+        runs.touch("p1");
+        assert!(!runs.finish("p1", "d"));
+        runs.start("p1", "e");
+        assert!(runs.finish("p1", "e"));
+    }
+}
@@ -1,12 +1,39 @@
 // src-tauri/src/commands/content_management.rs
 // Fixed version that handles proper file paths for reading/writing
 
+use std::collections::HashMap;
 use std::fs;
-use tauri::AppHandle;
-use super::{FileStats, ProjectData, utils::*};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use encoding_rs::{Encoding, UTF_16LE, UTF_8};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use super::{
+    FileStats, ProjectData,
+    artifacts::{register_project_files, ProjectRuns},
+    file_finder::record_node_access,
+    file_types::detect_app_file_type,
+    search_index::update_search_index,
+    text_encoding::{decode_text, encode_text, read_text_format, TextFormat},
+    undo_journal::sha256_hex,
+    utils::*,
+};
+
+// Files above this are only readable through read_file_range
+const MAX_FULL_READ_BYTES: u64 = 64 * 1024 * 1024;
+const MAX_RANGE_BYTES: u64 = 4 * 1024 * 1024;
+const DEFAULT_RANGE_LINES: u64 = 1000;
+const LINE_CHECKPOINT_INTERVAL: u64 = 10_000;
+const EXACT_LINE_COUNT_BYTES: u64 = 16 * 1024 * 1024;
+const READ_BLOCK_BYTES: usize = 64 * 1024;
+const MAX_BYTES_READ: u64 = 16 * 1024 * 1024;
+const MAX_LINE_INDEXES: usize = 64; // Files whose line checkpoints are kept
 
 // Helper function to resolve the actual file path on disk
-fn resolve_file_path(
+pub(crate) fn resolve_file_path(
     app: &AppHandle,
     node_id: &str,
     file_path: &str,
@@ -14,9 +41,9 @@ fn resolve_file_path(
 ) -> Result<std::path::PathBuf, String> {
     let project_dir = get_project_dir(app, project_id)?;
     
-    // If file_path is provided and not empty, use it directly
+    // If file_path is provided and not empty, use it, as long as it stays in the project
     if !file_path.is_empty() && file_path != "__PROJECT_ROOT__" {
-        return Ok(project_dir.join(file_path));
+        return resolve_project_path(&project_dir, file_path);
     }
     
     // Otherwise, try to reconstruct the path from the node hierarchy
@@ -60,13 +87,94 @@ fn resolve_file_path(
         }
     }
     
-    // Construct the full path
-    let mut full_path = project_dir;
-    for component in path_components {
-        full_path = full_path.join(component);
+    // Construct the full path; node names like ".." must not lead outside the project
+    resolve_project_path(&project_dir, &path_components.join("/"))
+}
+
+/// What a file on disk looked like when it was read. Saves pass it back so
+/// changes made by other programs in between are not overwritten.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileVersion {
+    pub modified: i64, // Milliseconds since the epoch
+    pub size: u64,
+    pub hash: String, // SHA-256 of the bytes on disk
+}
+
+impl FileVersion {
+    fn of(metadata: &fs::Metadata, bytes: &[u8]) -> Self {
+        let modified = metadata.modified().ok()
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|duration| duration.as_millis() as i64)
+            .unwrap_or(0);
+        FileVersion { modified, size: bytes.len() as u64, hash: sha256_hex(bytes) }
     }
-    
-    Ok(full_path)
+
+    // A file touched without being changed, e.g. by a git checkout, still matches
+    fn matches(&self, other: &FileVersion) -> bool {
+        self.size == other.size && self.hash == other.hash
+    }
+}
+
+/// Text of a file with the version it was read at.
+#[derive(Debug, Serialize)]
+pub struct FileContent {
+    pub content: String,
+    pub version: Option<FileVersion>, // None when the file does not exist yet
+    pub is_binary: bool,
+}
+
+/// Returned, serialized as JSON, as the error of a save whose expected version
+/// no longer matches the file, so the editor can offer a merge or an overwrite.
+#[derive(Debug, Serialize)]
+pub struct SaveConflict {
+    pub error: String, // Always "conflict"
+    pub message: String,
+    pub current_version: Option<FileVersion>, // None when the file was deleted
+    pub current_content: Option<String>, // None when deleted, binary or too large
+}
+
+const BINARY_PLACEHOLDER: &str = "[Binary file - content not displayable]";
+
+// The bytes on disk and their version; None when the file does not exist
+fn read_versioned(path: &Path) -> Result<Option<(Vec<u8>, FileVersion)>, String> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let bytes = safe_file_operation(|| fs::read(path), "Failed to read file bytes")?;
+    let metadata = safe_file_operation(|| fs::metadata(path), "Failed to get file metadata")?;
+    let version = FileVersion::of(&metadata, &bytes);
+    Ok(Some((bytes, version)))
+}
+
+/// Fails with a SaveConflict when the file is no longer at the expected version.
+fn check_version(path: &Path, expected: &FileVersion) -> Result<(), String> {
+    let size = fs::metadata(path).map(|metadata| metadata.len()).unwrap_or(0);
+    let current = if size > MAX_FULL_READ_BYTES { None } else { read_versioned(path)? };
+    if current.as_ref().map_or(false, |(_, current)| current.matches(expected)) {
+        return Ok(());
+    }
+
+    let message = if path.exists() {
+        "The file was changed by another program since it was opened"
+    } else {
+        "The file was deleted since it was opened"
+    };
+    let current_version = match &current {
+        Some((_, version)) => Some(version.clone()),
+        // Too large to hash here; size and time still tell the editor it changed
+        None => fs::metadata(path).ok().map(|metadata| FileVersion {
+            modified: FileVersion::of(&metadata, &[]).modified,
+            size: metadata.len(),
+            hash: String::new(),
+        }),
+    };
+    let conflict = SaveConflict {
+        error: "conflict".to_string(),
+        message: message.to_string(),
+        current_version,
+        current_content: current.and_then(|(bytes, _)| decode_text(&bytes)).map(|(text, _)| text),
+    };
+    Err(serde_json::to_string(&conflict).unwrap_or_else(|_| message.to_string()))
 }
 
 #[tauri::command]
@@ -76,42 +184,78 @@ pub async fn get_file_content(
     file_path: String, 
     project_id: String
 ) -> Result<String, String> {
+    read_file_content(&app, &node_id, &file_path, &project_id).map(|file| file.content)
+}
+
+/// Like get_file_content, with the version to pass back to save_file_content.
+#[tauri::command]
+pub async fn get_file_content_versioned(
+    app: AppHandle,
+    node_id: String,
+    file_path: String,
+    project_id: String
+) -> Result<FileContent, String> {
+    read_file_content(&app, &node_id, &file_path, &project_id)
+}
+
+/// The current version of a file, for noticing outside changes while it is open.
+#[tauri::command]
+pub async fn get_file_version(
+    app: AppHandle,
+    node_id: String,
+    file_path: String,
+    project_id: String
+) -> Result<Option<FileVersion>, String> {
     let full_path = resolve_file_path(&app, &node_id, &file_path, &project_id)?;
+    Ok(read_versioned(&full_path)?.map(|(_, version)| version))
+}
+
+fn read_file_content(app: &AppHandle, node_id: &str, file_path: &str, project_id: &str) -> Result<FileContent, String> {
+    let full_path = resolve_file_path(app, node_id, file_path, project_id)?;
     
     println!("📖 Reading file: {:?} (node_id: {})", full_path, node_id);
+    record_node_access(app, node_id);
     
     if !full_path.exists() {
         println!("⚠️ File not found: {:?}", full_path);
-        return Ok(String::new());
+        return Ok(FileContent { content: String::new(), version: None, is_binary: false });
     }
     
-    // Check if file is binary based on extension first
-    if is_binary_file(&full_path) {
-        println!("📋 Binary file detected by extension: {:?}", full_path);
-        return Ok("[Binary file - content not displayable]".to_string());
+    // Check if file is binary from its type first
+    if detect_app_file_type(app, &full_path).is_binary {
+        println!("📋 Binary file detected: {:?}", full_path);
+        return Ok(FileContent { content: BINARY_PLACEHOLDER.to_string(), version: None, is_binary: true });
     }
     
-    // Try to read as bytes first to check for binary content
-    let bytes = safe_file_operation(
-        || fs::read(&full_path),
-        "Failed to read file bytes"
-    )?;
-    
-    // Check if content is binary
-    if is_content_binary(&bytes) {
-        println!("📋 Binary content detected: {:?}", full_path);
-        return Ok("[Binary file - content not displayable]".to_string());
+    // Loading a huge file whole would freeze the editor
+    let size = fs::metadata(&full_path).map(|metadata| metadata.len()).unwrap_or(0);
+    if size > MAX_FULL_READ_BYTES {
+        return Err(format!(
+            "File is too large to open in full ({} MB); read it in ranges with read_file_range",
+            size / (1024 * 1024)
+        ));
     }
     
-    // Convert bytes to string if it's text content
-    match String::from_utf8(bytes) {
-        Ok(content) => {
-            println!("📄 Successfully read text file: {:?} ({} chars)", full_path, content.len());
-            Ok(content)
+    // Read the bytes once, so the version describes exactly what was decoded
+    let (bytes, version) = match read_versioned(&full_path)? {
+        Some(file) => file,
+        None => return Err("Cannot read a folder as text".to_string()),
+    };
+    
+    // Decode from whatever encoding the file uses; only NUL-bearing content is binary
+    match decode_text(&bytes) {
+        Some((content, format)) => {
+            println!("📄 Successfully read text file: {:?} ({} chars, {}, {})",
+                full_path, content.len(), format.encoding, format.line_ending);
+            if format.lossy {
+                eprintln!("⚠️ CONTENT: {:?} has bytes that are not valid {}; saving it needs an explicit encoding",
+                    full_path, format.encoding);
+            }
+            Ok(FileContent { content, version: Some(version), is_binary: false })
         }
-        Err(_) => {
-            println!("📋 UTF-8 conversion failed, treating as binary: {:?}", full_path);
-            Ok("[Binary file - content not displayable]".to_string())
+        None => {
+            println!("📋 Binary content detected: {:?}", full_path);
+            Ok(FileContent { content: BINARY_PLACEHOLDER.to_string(), version: None, is_binary: true })
         }
     }
 }
@@ -123,26 +267,54 @@ pub async fn save_file_content(
     content: String,
     file_path: String,
     project_id: String,
-) -> Result<(), String> {
+    encoding: Option<String>, // Converts the file, e.g. "UTF-8"; defaults to its current encoding
+    expected_version: Option<FileVersion>, // From get_file_content_versioned; omit to overwrite
+) -> Result<FileVersion, String> {
     let full_path = resolve_file_path(&app, &node_id, &file_path, &project_id)?;
     
     println!("💾 Saving file: {:?} (node_id: {}, {} bytes)", full_path, node_id, content.len());
     
     // Don't allow saving to binary files
-    if is_binary_file(&full_path) {
-        return Err("Cannot save content to binary file".to_string());
+    if detect_app_file_type(&app, &full_path).is_binary {
+        return Err("Cannot save text content to a binary file; use write_file_bytes".to_string());
+    }
+    
+    // Saves that say which version they edited must not clobber newer changes
+    if let Some(expected) = &expected_version {
+        check_version(&full_path, expected)?;
     }
     
+    // Existing files keep their encoding, byte order mark and line endings,
+    // judged from all of their bytes
+    let mut format = match fs::read(&full_path) {
+        Ok(existing) => decode_text(&existing).map(|(_, format)| format).unwrap_or_default(),
+        Err(_) => TextFormat::default(),
+    };
+    match encoding.as_deref() {
+        Some(label) => format = format.with_encoding(label)?,
+        // The editor got replacement characters for some bytes; writing them back would lose those bytes
+        None if format.lossy => return Err(format!(
+            "This file is not valid {} and was opened with some characters replaced; save it with an explicit encoding such as UTF-8",
+            format.encoding
+        )),
+        None => {}
+    }
+    let bytes = encode_text(&content, &format)?;
+    
     ensure_parent_dir(&full_path)
         .map_err(|e| format!("Failed to create parent directory: {}", e))?;
     
     safe_file_operation(
-        || fs::write(&full_path, &content),
+        || fs::write(&full_path, &bytes),
         "Failed to write file"
     )?;
+    update_search_index(&app, &project_id, &[&full_path]);
+    // A script running meanwhile would otherwise be credited with this edit
+    app.state::<ProjectRuns>().touch(&project_id);
     
     println!("✅ File saved successfully: {:?}", full_path);
-    Ok(())
+    let metadata = safe_file_operation(|| fs::metadata(&full_path), "Failed to get file metadata")?;
+    Ok(FileVersion::of(&metadata, &bytes))
 }
 
 #[tauri::command]
@@ -152,7 +324,7 @@ pub async fn get_file_stats(
     file_path: String,
 ) -> Result<FileStats, String> {
     let project_dir = get_project_dir(&app, &project_id)?;
-    let full_path = project_dir.join(&file_path);
+    let full_path = resolve_project_path(&project_dir, &file_path)?;
     
     if !full_path.exists() {
         return Err("File not found".to_string());
@@ -175,14 +347,671 @@ pub async fn get_file_stats(
         .map_err(|e| format!("Invalid created time: {}", e))?
         .as_secs() as i64;
     
-    let is_binary = is_binary_file(&full_path);
-    let file_type = get_file_type_description(&full_path);
+    let file_type = detect_app_file_type(&app, &full_path);
+    let format = if file_type.is_binary || metadata.is_dir() {
+        None
+    } else {
+        read_text_format(&full_path)
+    };
     
     Ok(FileStats {
         size: metadata.len(),
         modified,
         created,
-        is_binary,
-        file_type,
+        is_binary: file_type.is_binary,
+        file_type: file_type.description,
+        mime_type: file_type.mime,
+        category: file_type.category,
+        encoding: format.as_ref().map(|format| format.encoding.clone()),
+        line_ending: format.map(|format| format.line_ending),
+    })
+}
+/// A window of a text file, for paging through files too big to load whole.
+#[derive(Debug, Deserialize)]
+pub struct FileRangeRequest {
+    pub project_id: String,
+    pub node_id: Option<String>,
+    pub file_path: String,
+    pub mode: Option<String>, // "bytes" (default), "lines" or "tail"
+    pub offset: Option<u64>, // Bytes mode
+    pub length: Option<u64>, // Bytes mode; capped at MAX_RANGE_BYTES
+    pub start_line: Option<u64>, // Lines mode, 1-based
+    pub line_count: Option<u64>, // Lines and tail mode
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileRange {
+    pub content: String,
+    pub offset: u64, // Byte offset the content starts at
+    pub length: u64, // Bytes of the file the content covers
+    pub next_offset: u64, // Where the following read continues; the file size at EOF
+    pub total_size: u64,
+    pub modified: i64,
+    pub start_line: Option<u64>, // 1-based, when known
+    pub line_count: u64, // Lines in the content
+    pub total_lines: u64,
+    pub total_lines_exact: bool, // False when total_lines is estimated from the content
+    pub eof: bool,
+    pub encoding: String, // Encoding the content was decoded from
+}
+
+// Byte offsets of every LINE_CHECKPOINT_INTERVAL-th line, so paging deep into a
+// big file does not rescan it from the start each time
+struct LineIndex {
+    signature: (u64, i64),
+    format: TextFormat, // Judging it can mean reading the whole file, so once per version
+    checkpoints: Vec<u64>, // checkpoints[k] = offset of line k * LINE_CHECKPOINT_INTERVAL + 1
+    total_lines: Option<u64>,
+}
+
+// None until the file is first read
+type LineIndexSlot = Arc<Mutex<Option<LineIndex>>>;
+
+/// Line indexes of recently read files, each behind its own lock so a slow
+/// read of one file does not hold up reads of the others.
+#[derive(Default)]
+pub struct FileLineIndexes {
+    indexes: Mutex<HashMap<PathBuf, (u64, LineIndexSlot)>>, // Path -> (last use, index)
+    uses: AtomicU64,
+}
+
+impl FileLineIndexes {
+    // The slot for a file, dropping the least recently used one when full
+    fn slot(&self, path: &Path) -> LineIndexSlot {
+        let used = self.uses.fetch_add(1, Ordering::Relaxed);
+        let mut indexes = self.indexes.lock().unwrap();
+        if !indexes.contains_key(path) && indexes.len() >= MAX_LINE_INDEXES {
+            let oldest = indexes.iter().min_by_key(|(_, (last_used, _))| *last_used).map(|(path, _)| path.clone());
+            if let Some(oldest) = oldest {
+                indexes.remove(&oldest);
+            }
+        }
+        let entry = indexes.entry(path.to_path_buf()).or_insert_with(|| (used, Arc::new(Mutex::new(None))));
+        entry.0 = used;
+        entry.1.clone()
+    }
+}
+
+fn modified_secs(metadata: &fs::Metadata) -> i64 {
+    metadata.modified().ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+// Content signature that changes whenever the file is rewritten or grows
+fn file_signature(metadata: &fs::Metadata) -> (u64, i64) {
+    let nanos = metadata.modified().ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|duration| duration.as_nanos() as i64)
+        .unwrap_or(0);
+    (metadata.len(), nanos)
+}
+
+/// Decodes a chunk cut out of a UTF-8 file: a partial character at the start
+/// is skipped and one at the end left for the next read. Returns the text and
+/// the bytes skipped at the front and left at the back.
+fn decode_chunk(bytes: &[u8], at_start: bool, at_end: bool) -> (String, usize, usize) {
+    let skipped = if at_start {
+        0
+    } else {
+        bytes.iter().take(3).take_while(|byte| (**byte & 0xC0) == 0x80).count()
+    };
+    let bytes = &bytes[skipped..];
+
+    match std::str::from_utf8(bytes) {
+        Ok(text) => (text.to_string(), skipped, 0),
+        Err(e) if e.error_len().is_none() && !at_end => {
+            let valid = e.valid_up_to();
+            (String::from_utf8_lossy(&bytes[..valid]).into_owned(), skipped, bytes.len() - valid)
+        }
+        Err(_) => (String::from_utf8_lossy(bytes).into_owned(), skipped, 0),
+    }
+}
+
+// UTF-16 chunks must not split a surrogate pair either
+fn decode_utf16_chunk(bytes: &[u8], encoding: &'static Encoding, at_start: bool, at_end: bool) -> (String, usize, usize) {
+    let unit = |pair: &[u8]| if encoding == UTF_16LE {
+        u16::from_le_bytes([pair[0], pair[1]])
+    } else {
+        u16::from_be_bytes([pair[0], pair[1]])
+    };
+    let skipped = if !at_start && bytes.len() >= 2 && (0xDC00..=0xDFFF).contains(&unit(&bytes[..2])) { 2 } else { 0 };
+    let bytes = &bytes[skipped..];
+    let left = if !at_end && bytes.len() >= 2 && (0xD800..=0xDBFF).contains(&unit(&bytes[bytes.len() - 2..])) { 2 } else { 0 };
+    let (text, _) = encoding.decode_without_bom_handling(&bytes[..bytes.len() - left]);
+    (text.into_owned(), skipped, left)
+}
+
+fn count_newlines(bytes: &[u8]) -> u64 {
+    bytes.iter().filter(|byte| **byte == b'\n').count() as u64
+}
+
+// Lines in a whole file; a last line without a break still counts
+fn count_text_lines(bytes: &[u8], format: &TextFormat) -> u64 {
+    if format.is_utf16() {
+        let (text, _) = format.encoding().decode_without_bom_handling(&bytes[format.bom_length().min(bytes.len())..]);
+        return count_text_lines(text.as_bytes(), &TextFormat::default());
+    }
+    count_newlines(bytes) + (!bytes.is_empty() && bytes.last() != Some(&b'\n')) as u64
+}
+
+/// Offset where `line` (1-based) starts, walking from the closest checkpoint and
+/// recording new checkpoints on the way. None when the file has fewer lines.
+fn seek_line(file: &mut fs::File, index: &mut LineIndex, line: u64) -> Result<Option<u64>, String> {
+    let slot = ((line - 1) / LINE_CHECKPOINT_INTERVAL) as usize;
+    let known = slot.min(index.checkpoints.len() - 1);
+    let mut offset = index.checkpoints[known];
+    let mut current = known as u64 * LINE_CHECKPOINT_INTERVAL + 1;
+
+    file.seek(SeekFrom::Start(offset)).map_err(|e| format!("Failed to seek: {}", e))?;
+    let mut reader = BufReader::with_capacity(READ_BLOCK_BYTES, file);
+    let mut buffer = Vec::new();
+    while current < line {
+        buffer.clear();
+        let read = reader.read_until(b'\n', &mut buffer).map_err(|e| format!("Failed to read file: {}", e))?;
+        if read == 0 || buffer.last() != Some(&b'\n') {
+            // Reached the end: the line count is now exact
+            index.total_lines = Some(current - 1 + (read > 0) as u64);
+            return Ok(None);
+        }
+        offset += read as u64;
+        current += 1;
+        if (current - 1) % LINE_CHECKPOINT_INTERVAL == 0
+            && (current - 1) / LINE_CHECKPOINT_INTERVAL == index.checkpoints.len() as u64
+        {
+            index.checkpoints.push(offset);
+        }
+    }
+    Ok(Some(offset))
+}
+
+/// Reads a range of a text file. `line_indexes` keeps line checkpoints for
+/// files that are paged by line.
+fn read_range(path: &Path, request: &FileRangeRequest, line_indexes: &FileLineIndexes) -> Result<FileRange, String> {
+    let mut file = safe_file_operation(|| fs::File::open(path), "Failed to open file")?;
+    let metadata = safe_file_operation(|| file.metadata(), "Failed to get file metadata")?;
+    let total_size = metadata.len();
+
+    let slot = line_indexes.slot(path);
+    let mut slot = slot.lock().unwrap();
+    let signature = file_signature(&metadata);
+    if slot.as_ref().map_or(true, |index| index.signature != signature) {
+        let format = read_text_format(path).ok_or_else(|| "Binary file - content not displayable".to_string())?;
+        *slot = Some(LineIndex { signature, format, checkpoints: vec![0], total_lines: None });
+    }
+    let index = slot.as_mut().unwrap();
+    let format = index.format.clone();
+
+    let mode = request.mode.as_deref().unwrap_or("bytes");
+    if format.is_utf16() && mode != "bytes" {
+        // Line breaks cannot be found by scanning bytes in UTF-16
+        return Err("Line and tail reads are not supported for UTF-16 files; read byte ranges instead".to_string());
+    }
+    let bom_length = format.bom_length() as u64;
+    if index.total_lines.is_none() && total_size <= EXACT_LINE_COUNT_BYTES {
+        let mut all = Vec::new();
+        safe_file_operation(|| file.read_to_end(&mut all), "Failed to read file")?;
+        index.total_lines = Some(count_text_lines(&all, &format));
+    }
+
+    let line_count = request.line_count.unwrap_or(DEFAULT_RANGE_LINES).max(1);
+    let (start, end, start_line) = match mode {
+        "bytes" => {
+            let mut start = request.offset.unwrap_or(0).max(bom_length).min(total_size);
+            let length = request.length.unwrap_or(MAX_RANGE_BYTES).min(MAX_RANGE_BYTES);
+            let mut end = (start + length).min(total_size);
+            if format.is_utf16() {
+                // Stay on code unit boundaries; a stray last byte is read with the final chunk
+                start -= (start - bom_length) % 2;
+                if end < total_size {
+                    end -= (end - start) % 2;
+                }
+            }
+            (start, end, (start == bom_length).then(|| 1))
+        }
+        "lines" => {
+            let start_line = request.start_line.unwrap_or(1).max(1);
+            match seek_line(&mut file, index, start_line)? {
+                Some(start) => {
+                    // Find the end of the window within the byte cap
+                    file.seek(SeekFrom::Start(start)).map_err(|e| format!("Failed to seek: {}", e))?;
+                    let mut reader = BufReader::with_capacity(READ_BLOCK_BYTES, &mut file);
+                    let (mut end, mut lines, mut buffer) = (start, 0, Vec::new());
+                    while lines < line_count && end - start < MAX_RANGE_BYTES {
+                        buffer.clear();
+                        let read = reader.read_until(b'\n', &mut buffer).map_err(|e| format!("Failed to read file: {}", e))?;
+                        if read == 0 {
+                            break;
+                        }
+                        end += read as u64;
+                        lines += 1;
+                    }
+                    (start, end.min(start + MAX_RANGE_BYTES), Some(start_line))
+                }
+                None => (total_size, total_size, None),
+            }
+        }
+        "tail" => {
+            // Walk back from the end until enough line breaks are found
+            let trailing_newline = total_size > 0 && {
+                let mut last = [0u8; 1];
+                file.seek(SeekFrom::Start(total_size - 1)).and_then(|_| file.read_exact(&mut last)).is_ok() && last[0] == b'\n'
+            };
+            let search_end = total_size - trailing_newline as u64;
+            let floor = total_size.saturating_sub(MAX_RANGE_BYTES);
+            let mut start = search_end;
+            let mut found = 0;
+            let mut block = vec![0u8; READ_BLOCK_BYTES];
+            'search: while start > floor {
+                let block_start = start.saturating_sub(READ_BLOCK_BYTES as u64).max(floor);
+                let block = &mut block[..(start - block_start) as usize];
+                file.seek(SeekFrom::Start(block_start)).and_then(|_| file.read_exact(block))
+                    .map_err(|e| format!("Failed to read file: {}", e))?;
+                for (position, byte) in block.iter().enumerate().rev() {
+                    if *byte == b'\n' {
+                        found += 1;
+                        if found == line_count {
+                            start = block_start + position as u64 + 1;
+                            break 'search;
+                        }
+                    }
+                }
+                start = block_start;
+            }
+            // Numbered once the chunk is read: the byte cap can cut the tail short
+            (start, total_size, None)
+        }
+        other => return Err(format!("Unknown read mode: {}", other)),
+    };
+    let start = start.max(bom_length);
+    let end = end.max(start);
+
+    let mut bytes = vec![0u8; (end - start) as usize];
+    file.seek(SeekFrom::Start(start))
+        .and_then(|_| file.read_exact(&mut bytes))
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    if !format.is_utf16() && is_content_binary(&bytes) {
+        return Err("Binary file - content not displayable".to_string());
+    }
+
+    let encoding = format.encoding();
+    let (at_start, at_end) = (start == bom_length, end == total_size);
+    let (content, skipped, left) = if encoding == UTF_8 {
+        decode_chunk(&bytes, at_start, at_end)
+    } else if format.is_utf16() {
+        decode_utf16_chunk(&bytes, encoding, at_start, at_end)
+    } else {
+        (encoding.decode_without_bom_handling(&bytes).0.into_owned(), 0, 0)
+    };
+    let offset = start + skipped as u64;
+    let next_offset = end - left as u64;
+    let chunk_lines = count_newlines(content.as_bytes()) + (!content.is_empty() && !content.ends_with('\n')) as u64;
+    let start_line = match (mode, index.total_lines) {
+        ("tail", _) if start <= bom_length => Some(1),
+        ("tail", Some(total)) => Some(total + 1 - chunk_lines.min(total)),
+        _ => start_line,
+    };
+
+    // Small files are counted exactly; big ones are estimated from the chunk
+    let (total_lines, total_lines_exact) = match index.total_lines {
+        Some(total) => (total, true),
+        None if next_offset > offset => {
+            let newlines = count_newlines(content.as_bytes()).max(1);
+            ((total_size as f64 * newlines as f64 / (next_offset - offset) as f64).round() as u64, false)
+        }
+        None => (0, false),
+    };
+
+    Ok(FileRange {
+        content,
+        offset,
+        length: next_offset - offset,
+        next_offset,
+        total_size,
+        modified: modified_secs(&metadata),
+        start_line,
+        line_count: chunk_lines,
+        total_lines,
+        total_lines_exact,
+        eof: next_offset >= total_size,
+        encoding: format.encoding,
+    })
+}
+
+#[tauri::command]
+pub async fn read_file_range(
+    app: AppHandle,
+    request: FileRangeRequest,
+) -> Result<FileRange, String> {
+    let full_path = resolve_file_path(&app, request.node_id.as_deref().unwrap_or(""), &request.file_path, &request.project_id)?;
+    if !full_path.is_file() {
+        return Err("File not found".to_string());
+    }
+    if detect_app_file_type(&app, &full_path).is_binary {
+        return Err("Binary file - content not displayable".to_string());
+    }
+    // Counting lines can mean reading megabytes
+    tauri::async_runtime::spawn_blocking(move || read_range(&full_path, &request, &app.state::<FileLineIndexes>()))
+        .await
+        .map_err(|e| format!("Failed to read file range: {}", e))?
+}
+
+/// A byte range of any file, for image, PDF and hex previews.
+#[derive(Debug, Deserialize)]
+pub struct FileBytesRequest {
+    pub project_id: String,
+    pub node_id: Option<String>,
+    pub file_path: String,
+    pub offset: Option<u64>,
+    pub length: Option<u64>, // Capped at MAX_BYTES_READ
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileBytes {
+    pub data: String, // base64
+    pub offset: u64,
+    pub length: u64,
+    pub total_size: u64,
+    pub modified: i64,
+    pub eof: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WriteFileBytesRequest {
+    pub project_id: String,
+    pub node_id: Option<String>,
+    pub file_path: String,
+    pub data: String, // base64
+    pub offset: Option<u64>, // Patches the file in place; the whole file is replaced when absent
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileBytesWritten {
+    pub bytes_written: u64,
+    pub total_size: u64,
+}
+
+fn read_bytes(path: &Path, offset: u64, length: u64) -> Result<(Vec<u8>, u64, fs::Metadata), String> {
+    let mut file = safe_file_operation(|| fs::File::open(path), "Failed to open file")?;
+    let metadata = safe_file_operation(|| file.metadata(), "Failed to get file metadata")?;
+    let start = offset.min(metadata.len());
+    let end = start.saturating_add(length.min(MAX_BYTES_READ)).min(metadata.len());
+
+    let mut bytes = vec![0u8; (end - start) as usize];
+    file.seek(SeekFrom::Start(start))
+        .and_then(|_| file.read_exact(&mut bytes))
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    Ok((bytes, start, metadata))
+}
+
+/// Writes bytes at `offset`, or replaces the whole file when there is none.
+/// Whole-file writes go through a temporary file so readers never see half of one;
+/// the replaced file's permissions carry over.
+fn write_bytes(path: &Path, bytes: &[u8], offset: Option<u64>) -> Result<u64, String> {
+    ensure_parent_dir(path).map_err(|e| format!("Failed to create parent directory: {}", e))?;
+
+    match offset {
+        None => {
+            let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+            let temp_path = path.with_file_name(format!(".{}.write-tmp", name));
+            safe_file_operation(|| fs::write(&temp_path, bytes), "Failed to write file")?;
+            if let Err(e) = copy_permissions(path, &temp_path).and_then(|_| fs::rename(&temp_path, path)) {
+                let _ = fs::remove_file(&temp_path);
+                return Err(format!("Failed to replace file: {}", e));
+            }
+        }
+        Some(offset) => {
+            let size = fs::metadata(path).map(|metadata| metadata.len()).unwrap_or(0);
+            if offset > size {
+                return Err(format!("Offset {} is past the end of the file ({} bytes)", offset, size));
+            }
+            let mut file = safe_file_operation(
+                || fs::OpenOptions::new().write(true).create(true).truncate(false).open(path),
+                "Failed to open file"
+            )?;
+            file.seek(SeekFrom::Start(offset))
+                .and_then(|_| file.write_all(bytes))
+                .map_err(|e| format!("Failed to write file: {}", e))?;
+        }
+    }
+    Ok(safe_file_operation(|| fs::metadata(path), "Failed to get file metadata")?.len())
+}
+
+#[tauri::command]
+pub async fn read_file_bytes(
+    app: AppHandle,
+    request: FileBytesRequest,
+) -> Result<FileBytes, String> {
+    let full_path = resolve_file_path(&app, request.node_id.as_deref().unwrap_or(""), &request.file_path, &request.project_id)?;
+    if !full_path.is_file() {
+        return Err("File not found".to_string());
+    }
+
+    let (bytes, offset, metadata) = read_bytes(&full_path, request.offset.unwrap_or(0), request.length.unwrap_or(MAX_BYTES_READ))?;
+    let length = bytes.len() as u64;
+    Ok(FileBytes {
+        data: BASE64.encode(&bytes),
+        offset,
+        length,
+        total_size: metadata.len(),
+        modified: modified_secs(&metadata),
+        eof: offset + length >= metadata.len(),
+    })
+}
+
+#[tauri::command]
+pub async fn write_file_bytes(
+    app: AppHandle,
+    request: WriteFileBytesRequest,
+) -> Result<FileBytesWritten, String> {
+    let full_path = resolve_file_path(&app, request.node_id.as_deref().unwrap_or(""), &request.file_path, &request.project_id)?;
+    if full_path.is_dir() {
+        return Err("Cannot write bytes to a folder".to_string());
+    }
+    let bytes = BASE64.decode(request.data.trim())
+        .map_err(|e| format!("Invalid base64 data: {}", e))?;
+
+    let existed = full_path.exists();
+    let total_size = write_bytes(&full_path, &bytes, request.offset)?;
+    println!("💾 Wrote {} bytes to {:?}", bytes.len(), full_path);
+
+    // New outputs show up in the tree; existing nodes get fresh metadata
+    let project_dir = get_project_dir(&app, &request.project_id)?;
+    if let Ok(relative) = full_path.strip_prefix(&project_dir) {
+        let relative = vec![relative.to_string_lossy().replace('\\', "/")];
+        let (created, modified) = if existed { (Vec::new(), relative) } else { (relative, Vec::new()) };
+        if let Err(e) = register_project_files(&app, &request.project_id, &created, &modified, None) {
+            eprintln!("⚠️ Failed to register written file {:?}: {}", full_path, e);
+        }
+    }
+
+    Ok(FileBytesWritten {
+        bytes_written: bytes.len() as u64,
+        total_size,
     })
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(mode: &str) -> FileRangeRequest {
+        FileRangeRequest {
+            project_id: "p1".to_string(),
+            node_id: None,
+            file_path: "log.txt".to_string(),
+            mode: Some(mode.to_string()),
+            offset: None,
+            length: None,
+            start_line: None,
+            line_count: None,
+        }
+    }
+
+    fn log_file(lines: u64) -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.txt");
+        let content: String = (1..=lines).map(|line| format!("line {}\n", line)).collect();
+        fs::write(&path, content).unwrap();
+        (dir, path)
+    }
+
+    #[test]
+    fn byte_ranges_stay_on_character_boundaries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.txt");
+        fs::write(&path, "héllo\nwörld\n").unwrap();
+        let indexes = FileLineIndexes::default();
+
+        let mut bytes = request("bytes");
+        bytes.length = Some(2); // Ends inside "é"
+        let first = read_range(&path, &bytes, &indexes).unwrap();
+        assert_eq!((first.content.as_str(), first.next_offset, first.eof), ("h", 1, false));
+
+        bytes.offset = Some(2); // Starts inside "é"
+        bytes.length = Some(100);
+        let rest = read_range(&path, &bytes, &indexes).unwrap();
+        assert_eq!((rest.content.as_str(), rest.offset, rest.eof), ("llo\nwörld\n", 3, true));
+        assert_eq!((rest.total_size, rest.total_lines, rest.total_lines_exact), (14, 2, true));
+    }
+
+    #[test]
+    fn line_windows_use_checkpoints() {
+        let (_dir, path) = log_file(LINE_CHECKPOINT_INTERVAL * 2 + 5);
+        let indexes = FileLineIndexes::default();
+
+        let mut lines = request("lines");
+        lines.start_line = Some(LINE_CHECKPOINT_INTERVAL * 2 + 3);
+        lines.line_count = Some(2);
+        let window = read_range(&path, &lines, &indexes).unwrap();
+        let expected = format!("line {}\nline {}\n", LINE_CHECKPOINT_INTERVAL * 2 + 3, LINE_CHECKPOINT_INTERVAL * 2 + 4);
+        assert_eq!(window.content, expected);
+        assert_eq!((window.line_count, window.start_line), (2, Some(LINE_CHECKPOINT_INTERVAL * 2 + 3)));
+        assert_eq!(indexes.slot(&path).lock().unwrap().as_ref().unwrap().checkpoints.len(), 3);
+
+        lines.start_line = Some(LINE_CHECKPOINT_INTERVAL * 3);
+        let past_end = read_range(&path, &lines, &indexes).unwrap();
+        assert!(past_end.content.is_empty() && past_end.eof);
+        assert_eq!(past_end.total_lines, LINE_CHECKPOINT_INTERVAL * 2 + 5);
+    }
+
+    #[test]
+    fn least_recently_used_line_indexes_are_dropped() {
+        let indexes = FileLineIndexes::default();
+        let first = indexes.slot(Path::new("/logs/0.log"));
+        for n in 1..MAX_LINE_INDEXES {
+            indexes.slot(Path::new(&format!("/logs/{}.log", n)));
+        }
+        // Using the first again makes the second the oldest
+        assert!(Arc::ptr_eq(&first, &indexes.slot(Path::new("/logs/0.log"))));
+        indexes.slot(Path::new("/logs/new.log"));
+
+        let kept = indexes.indexes.lock().unwrap();
+        assert_eq!(kept.len(), MAX_LINE_INDEXES);
+        assert!(kept.contains_key(Path::new("/logs/0.log")) && !kept.contains_key(Path::new("/logs/1.log")));
+    }
+
+    #[test]
+    fn utf16_ranges_skip_the_bom_and_keep_surrogate_pairs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.txt");
+        let text = "a😀b\n";
+        let bytes: Vec<u8> = [0xFEFF].iter().copied().chain(text.encode_utf16()).flat_map(|unit| unit.to_le_bytes()).collect();
+        fs::write(&path, bytes).unwrap();
+        let indexes = FileLineIndexes::default();
+
+        let mut range = request("bytes");
+        range.length = Some(5); // Ends between the two halves of the emoji
+        let first = read_range(&path, &range, &indexes).unwrap();
+        assert_eq!((first.content.as_str(), first.offset, first.next_offset), ("a", 2, 4));
+        assert_eq!((first.encoding.as_str(), first.total_lines), ("UTF-16LE", 1));
+
+        range.offset = Some(first.next_offset);
+        range.length = None;
+        assert_eq!(read_range(&path, &range, &indexes).unwrap().content, "😀b\n");
+        assert!(read_range(&path, &request("tail"), &indexes).is_err());
+    }
+
+    #[test]
+    fn saves_conflict_when_the_file_changed_since_it_was_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        fs::write(&path, "draft\n").unwrap();
+        let (_, read) = read_versioned(&path).unwrap().unwrap();
+        assert!(check_version(&path, &read).is_ok());
+
+        // Rewriting the same bytes is not a change
+        fs::write(&path, "draft\n").unwrap();
+        assert!(check_version(&path, &read).is_ok());
+
+        fs::write(&path, "edited elsewhere\n").unwrap();
+        let conflict: serde_json::Value = serde_json::from_str(&check_version(&path, &read).unwrap_err()).unwrap();
+        assert_eq!(conflict["error"], "conflict");
+        assert_eq!(conflict["current_content"], "edited elsewhere\n");
+        assert_eq!(conflict["current_version"]["size"], 17);
+        assert_eq!(conflict["current_version"]["hash"], sha256_hex(b"edited elsewhere\n"));
+
+        fs::remove_file(&path).unwrap();
+        let deleted: serde_json::Value = serde_json::from_str(&check_version(&path, &read).unwrap_err()).unwrap();
+        assert_eq!((deleted["current_version"].is_null(), deleted["current_content"].is_null()), (true, true));
+    }
+
+    #[test]
+    fn byte_reads_and_writes_cover_ranges() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out/image.bin");
+
+        assert_eq!(write_bytes(&path, &[0x89, b'P', b'N', b'G', 0, 1], None).unwrap(), 6);
+        assert_eq!(write_bytes(&path, &[0xFF, 0xFE, 0xFD], Some(4)).unwrap(), 7);
+        assert!(write_bytes(&path, &[0], Some(9)).unwrap_err().contains("past the end"));
+
+        let (bytes, offset, metadata) = read_bytes(&path, 3, 2).unwrap();
+        assert_eq!((bytes, offset, metadata.len()), (vec![b'G', 0xFF], 3, 7));
+        assert_eq!(read_bytes(&path, 50, 10).unwrap().0, Vec::<u8>::new());
+        assert_eq!(fs::read(&path).unwrap(), [0x89, b'P', b'N', b'G', 0xFF, 0xFE, 0xFD]);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o751)).unwrap();
+            write_bytes(&path, b"replaced", None).unwrap();
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o751);
+        }
+    }
+
+    #[test]
+    fn tail_returns_the_last_lines_of_a_growing_file() {
+        let (_dir, path) = log_file(50);
+        let indexes = FileLineIndexes::default();
+
+        let mut tail = request("tail");
+        tail.line_count = Some(3);
+        let end = read_range(&path, &tail, &indexes).unwrap();
+        assert_eq!(end.content, "line 48\nline 49\nline 50\n");
+        assert_eq!((end.start_line, end.next_offset), (Some(48), end.total_size));
+
+        // Followers poll from next_offset for appended output
+        fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(b"line 51\n").unwrap();
+        let mut follow = request("bytes");
+        follow.offset = Some(end.next_offset);
+        assert_eq!(read_range(&path, &follow, &indexes).unwrap().content, "line 51\n");
+    }
+
+    #[test]
+    fn capped_tails_are_numbered_from_what_was_read() {
+        // 5000 lines of 1000 bytes: the last 4MB starts partway through line 806
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.txt");
+        let content: String = (1..=5000).map(|line| format!("{:>999}\n", line)).collect();
+        fs::write(&path, content).unwrap();
+
+        let mut tail = request("tail");
+        tail.line_count = Some(10_000);
+        let range = read_range(&path, &tail, &FileLineIndexes::default()).unwrap();
+
+        assert_eq!(range.length, MAX_RANGE_BYTES);
+        assert_eq!((range.start_line, range.line_count), (Some(806), 4195));
+        assert_eq!(range.content.lines().nth(1).unwrap().trim(), "807");
+    }
+}
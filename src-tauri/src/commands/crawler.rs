@@ -0,0 +1,909 @@
+// src-tauri/src/commands/crawler.rs
+// Polite multi-page crawler: robots.txt, per-host rate limits and project output
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use regex::Regex;
+use reqwest::{Client, Url};
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+use uuid::Uuid;
+use super::{
+    artifacts::register_project_files,
+    extraction::extract_links,
+    utils::*,
+    web_scraping::{decode_html, destination_path},
+};
+
+const USER_AGENT: &str = "FileManagerCrawler/0.1";
+const ROBOTS_AGENT: &str = "filemanagercrawler";
+const DEFAULT_MAX_DEPTH: u32 = 2;
+const DEFAULT_MAX_PAGES: usize = 50;
+const MAX_PAGES_LIMIT: usize = 10_000;
+const DEFAULT_DELAY_MS: u64 = 1000;
+const MAX_CRAWL_DELAY_SECS: f64 = 60.0;
+const MAX_PAGE_BYTES: u64 = 20 * 1024 * 1024;
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+const MAX_REDIRECT_HOPS: u32 = 10; // Per chain, so a server minting new URLs cannot loop forever
+const MANIFEST_FILE_NAME: &str = "crawl.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlRequest {
+    pub project_id: String,
+    pub parent_id: Option<String>,
+    pub output_folder: String, // Relative to the parent folder, or to the project root
+    pub seeds: Vec<String>,
+    #[serde(default)]
+    pub include: Vec<String>, // Regexes on the full URL; without any, links stay on the seed hosts
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    pub max_depth: Option<u32>,
+    pub max_pages: Option<usize>,
+    pub delay_ms: Option<u64>, // Minimum gap between requests to the same host
+    pub respect_robots: Option<bool>, // Defaults to true
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CrawlProgress {
+    pub crawl_id: String,
+    pub project_id: String,
+    pub output_folder: String,
+    pub status: String, // "running", "completed", "cancelled" or "failed"
+    pub pages_fetched: usize,
+    pub pages_failed: usize,
+    pub pages_skipped: usize, // Disallowed by robots.txt or redirected out of scope
+    pub queued: usize,
+    pub current_url: Option<String>,
+    pub error: Option<String>,
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CrawledPage {
+    pub url: String,
+    pub final_url: Option<String>, // Where a redirect pointed
+    pub depth: u32,
+    pub status: Option<u16>,
+    pub content_type: Option<String>,
+    pub title: Option<String>,
+    pub file: Option<String>, // Relative to the output folder
+    pub skipped: Option<String>, // Why the page was not fetched
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CrawlManifest<'a> {
+    crawl_id: &'a str,
+    seeds: &'a [String],
+    status: &'a str,
+    started_at: i64,
+    finished_at: i64,
+    pages: &'a [CrawledPage],
+}
+
+/// Files a crawl wrote, relative to the output folder.
+struct CrawlOutcome {
+    created: Vec<String>,
+    modified: Vec<String>,
+}
+
+impl CrawlOutcome {
+    fn write(&mut self, output_dir: &Path, name: &str, content: &[u8]) -> Result<(), String> {
+        let path = output_dir.join(name);
+        let existed = path.exists();
+        safe_file_operation(|| fs::write(&path, content), "Failed to write crawled page")?;
+        if existed {
+            self.modified.push(name.to_string());
+        } else {
+            self.created.push(name.to_string());
+        }
+        Ok(())
+    }
+}
+
+struct CrawlJob {
+    cancel: Arc<AtomicBool>,
+    progress: CrawlProgress,
+}
+
+/// Background crawls by id, finished ones included until the app restarts.
+#[derive(Default)]
+pub struct CrawlJobs {
+    jobs: Mutex<HashMap<String, CrawlJob>>,
+}
+
+#[derive(Debug, Default, Clone)]
+struct RobotsRules {
+    rules: Vec<(bool, String)>, // (allow, path pattern)
+    crawl_delay: Option<f64>,
+}
+
+impl RobotsRules {
+    // The longest matching pattern decides; Allow wins a tie
+    fn allows(&self, path: &str) -> bool {
+        let mut best: Option<(usize, bool)> = None;
+        for (allow, pattern) in &self.rules {
+            if robots_pattern_matches(pattern, path) {
+                let candidate = (pattern.len(), *allow);
+                if best.map_or(true, |current| candidate > current) {
+                    best = Some(candidate);
+                }
+            }
+        }
+        best.map_or(true, |(_, allow)| allow)
+    }
+}
+
+// Prefix match with `*` wildcards and an optional `$` end anchor
+fn robots_pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let parts: Vec<&str> = pattern.split('*').collect();
+
+    if !path.starts_with(parts[0]) {
+        return false;
+    }
+    let mut position = parts[0].len();
+    let last = parts.len() - 1;
+    for (index, part) in parts.iter().enumerate().skip(1) {
+        if index == last && anchored {
+            return path.len() >= position + part.len() && path.ends_with(part);
+        }
+        match path[position..].find(part) {
+            Some(found) => position += found + part.len(),
+            None => return false,
+        }
+    }
+    !anchored || position == path.len()
+}
+
+/// Rules for our user agent, falling back to the `*` group.
+fn parse_robots(content: &str) -> RobotsRules {
+    let mut specific = RobotsRules::default();
+    let mut wildcard = RobotsRules::default();
+    let mut has_specific = false;
+    let mut group_agents: Vec<String> = Vec::new();
+    let mut in_rules = false;
+
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field.trim().to_lowercase(), value.trim()),
+            None => continue,
+        };
+
+        match field.as_str() {
+            "user-agent" => {
+                // A user-agent line after rules starts a new group
+                if in_rules {
+                    group_agents.clear();
+                    in_rules = false;
+                }
+                group_agents.push(value.to_lowercase());
+            }
+            "allow" | "disallow" | "crawl-delay" => {
+                in_rules = true;
+                let applies_specific = group_agents.iter().any(|agent| agent == ROBOTS_AGENT);
+                let applies_wildcard = group_agents.iter().any(|agent| agent == "*");
+                has_specific |= applies_specific;
+
+                for (rules, applies) in [(&mut specific, applies_specific), (&mut wildcard, applies_wildcard)] {
+                    if !applies {
+                        continue;
+                    }
+                    match field.as_str() {
+                        "crawl-delay" => rules.crawl_delay = value.parse().ok(),
+                        // An empty Disallow allows everything
+                        _ if value.is_empty() => {}
+                        _ => rules.rules.push((field == "allow", value.to_string())),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if has_specific { specific } else { wildcard }
+}
+
+fn host_key(url: &Url) -> String {
+    format!("{}://{}:{}", url.scheme(), url.host_str().unwrap_or(""), url.port_or_known_default().unwrap_or(0))
+}
+
+fn same_site(a: &Url, b: &Url) -> bool {
+    let bare = |url: &Url| url.host_str().map(|host| host.trim_start_matches("www.").to_string());
+    bare(a).is_some() && bare(a) == bare(b)
+}
+
+// URLs differing only by fragment are the same page
+fn normalize_url(url: &Url) -> Url {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    url
+}
+
+fn compile_patterns(patterns: &[String]) -> Result<Vec<Regex>, String> {
+    patterns.iter()
+        .map(|pattern| Regex::new(pattern).map_err(|e| format!("Invalid pattern '{}': {}", pattern, e)))
+        .collect()
+}
+
+/// Which links and redirects a crawl follows.
+struct CrawlScope {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+    seed_hosts: HashSet<String>,
+}
+
+impl CrawlScope {
+    fn new(request: &CrawlRequest, seeds: &[Url]) -> Result<Self, String> {
+        Ok(CrawlScope {
+            include: compile_patterns(&request.include)?,
+            exclude: compile_patterns(&request.exclude)?,
+            seed_hosts: seeds.iter().map(host_key).collect(),
+        })
+    }
+
+    fn follows(&self, url: &Url) -> bool {
+        let text = url.as_str();
+        if self.exclude.iter().any(|pattern| pattern.is_match(text)) {
+            return false;
+        }
+        if self.include.is_empty() {
+            self.seed_hosts.contains(&host_key(url))
+        } else {
+            self.include.iter().any(|pattern| pattern.is_match(text))
+        }
+    }
+}
+
+fn parse_seeds(seeds: &[String]) -> Result<Vec<Url>, String> {
+    if seeds.is_empty() {
+        return Err("A crawl needs at least one seed URL".to_string());
+    }
+    seeds.iter()
+        .map(|seed| {
+            let url = Url::parse(seed.trim()).map_err(|e| format!("Invalid seed URL {}: {}", seed, e))?;
+            match url.scheme() {
+                "http" | "https" => Ok(normalize_url(&url)),
+                scheme => Err(format!("Unsupported seed URL scheme: {}", scheme)),
+            }
+        })
+        .collect()
+}
+
+fn page_file_name(index: usize, url: &Url, is_html: bool) -> String {
+    let raw = format!("{}{}", url.host_str().unwrap_or("page"), url.path());
+    let slug: String = raw.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    let slug: String = slug.trim_matches('_').chars().take(80).collect();
+
+    let extension = if is_html {
+        "html".to_string()
+    } else {
+        Path::new(url.path()).extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .filter(|extension| extension.len() <= 5 && extension.chars().all(|c| c.is_ascii_alphanumeric()))
+            .unwrap_or_else(|| "bin".to_string())
+    };
+
+    format!("{:04}-{}.{}", index, slug, extension)
+}
+
+fn page_title(html_content: &str) -> Option<String> {
+    let document = Html::parse_document(html_content);
+    let title = document.select(&Selector::parse("title").unwrap()).next()?;
+    let text = title.text().collect::<Vec<_>>().join(" ").split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() { None } else { Some(text) }
+}
+
+// Read a body, giving up as soon as it outgrows `limit` whatever Content-Length claimed
+async fn read_capped(mut response: reqwest::Response, limit: u64) -> Result<Vec<u8>, String> {
+    if response.content_length().map_or(false, |length| length > limit) {
+        return Err("Page is too large to crawl".to_string());
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| format!("Failed to read page: {}", e))? {
+        if (body.len() + chunk.len()) as u64 > limit {
+            return Err("Page is too large to crawl".to_string());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+// Sleep until `until`, waking regularly to notice a cancel. Returns false if cancelled.
+async fn wait_until(until: Instant, cancel: &AtomicBool) -> bool {
+    loop {
+        if cancel.load(Ordering::SeqCst) {
+            return false;
+        }
+        let now = Instant::now();
+        if now >= until {
+            return true;
+        }
+        tokio::time::sleep((until - now).min(Duration::from_millis(100))).await;
+    }
+}
+
+/// Spaces out requests per host and caches each host's robots.txt.
+struct Politeness {
+    client: Client, // Follows redirects, which robots.txt often has
+    delay: Duration,
+    respect_robots: bool,
+    last_request: HashMap<String, Instant>,
+    robots: HashMap<String, RobotsRules>,
+}
+
+impl Politeness {
+    fn host_delay(&self, host: &str) -> Duration {
+        let crawl_delay = self.robots.get(host)
+            .and_then(|rules| rules.crawl_delay)
+            .map(|seconds| Duration::from_secs_f64(seconds.clamp(0.0, MAX_CRAWL_DELAY_SECS)))
+            .unwrap_or_default();
+        self.delay.max(crawl_delay)
+    }
+
+    // Wait for the host's turn; false when the crawl was cancelled meanwhile
+    async fn wait_turn(&mut self, host: &str, cancel: &AtomicBool) -> bool {
+        if let Some(last) = self.last_request.get(host) {
+            if !wait_until(*last + self.host_delay(host), cancel).await {
+                return false;
+            }
+        }
+        self.last_request.insert(host.to_string(), Instant::now());
+        true
+    }
+
+    // Whether robots.txt lets us fetch `url`; None when the crawl was cancelled meanwhile
+    async fn allows(&mut self, url: &Url, cancel: &AtomicBool) -> Option<bool> {
+        if !self.respect_robots {
+            return Some(true);
+        }
+
+        let host = host_key(url);
+        if !self.robots.contains_key(&host) {
+            if !self.wait_turn(&host, cancel).await {
+                return None;
+            }
+            // A missing or unreadable robots.txt allows everything
+            let response = match url.join("/robots.txt") {
+                Ok(robots_url) => self.client.get(robots_url).send().await.ok(),
+                Err(_) => None,
+            };
+            let rules = match response {
+                Some(response) if response.status().is_success() => {
+                    response.text().await.map(|content| parse_robots(&content)).unwrap_or_default()
+                }
+                _ => RobotsRules::default(),
+            };
+            self.robots.insert(host.clone(), rules);
+        }
+
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        Some(self.robots[&host].allows(&path))
+    }
+}
+
+enum Fetched {
+    Page(Vec<u8>),
+    Redirect(Url),
+}
+
+/// Breadth-first crawl from the seeds, writing each page and a `crawl.json`
+/// manifest into `output_dir`. `progress` is updated in place and passed to
+/// `on_progress` after every page.
+async fn run_crawl(
+    request: &CrawlRequest,
+    output_dir: &Path,
+    cancel: &AtomicBool,
+    progress: &mut CrawlProgress,
+    mut on_progress: impl FnMut(&CrawlProgress),
+) -> Result<CrawlOutcome, String> {
+    let seeds = parse_seeds(&request.seeds)?;
+    let mut scope = CrawlScope::new(request, &seeds)?;
+    let max_depth = request.max_depth.unwrap_or(DEFAULT_MAX_DEPTH);
+    let max_pages = request.max_pages.unwrap_or(DEFAULT_MAX_PAGES).clamp(1, MAX_PAGES_LIMIT);
+
+    // Page redirects are queued like links, so robots.txt and the scope apply to them too
+    let client = Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let robots_client = Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let mut politeness = Politeness {
+        client: robots_client,
+        delay: Duration::from_millis(request.delay_ms.unwrap_or(DEFAULT_DELAY_MS)),
+        respect_robots: request.respect_robots.unwrap_or(true),
+        last_request: HashMap::new(),
+        robots: HashMap::new(),
+    };
+
+    fs::create_dir_all(output_dir)
+        .map_err(|e| format!("Failed to create output folder: {}", e))?;
+
+    let mut seen: HashSet<String> = HashSet::new();
+    // URL, depth and how many redirects led to it
+    let mut queue: VecDeque<(Url, u32, u32)> = VecDeque::new();
+    for seed in seeds {
+        if seen.insert(seed.to_string()) {
+            queue.push_back((seed, 0, 0));
+        }
+    }
+
+    let mut pages: Vec<CrawledPage> = Vec::new();
+    let mut outcome = CrawlOutcome { created: Vec::new(), modified: Vec::new() };
+
+    while let Some((url, depth, hops)) = queue.pop_front() {
+        if cancel.load(Ordering::SeqCst) || progress.pages_fetched + progress.pages_failed >= max_pages {
+            break;
+        }
+        progress.queued = queue.len();
+        progress.current_url = Some(url.to_string());
+
+        let mut page = CrawledPage {
+            url: url.to_string(),
+            final_url: None,
+            depth,
+            status: None,
+            content_type: None,
+            title: None,
+            file: None,
+            skipped: None,
+            error: None,
+        };
+
+        match politeness.allows(&url, cancel).await {
+            None => break,
+            Some(false) => {
+                page.skipped = Some("Disallowed by robots.txt".to_string());
+                progress.pages_skipped += 1;
+                pages.push(page);
+                on_progress(progress);
+                continue;
+            }
+            Some(true) => {}
+        }
+        if !politeness.wait_turn(&host_key(&url), cancel).await {
+            break;
+        }
+
+        let fetched = async {
+            let response = client.get(url.clone()).send().await
+                .map_err(|e| format!("Request failed: {}", e))?;
+            page.status = Some(response.status().as_u16());
+            page.content_type = response.headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+
+            if response.status().is_redirection() {
+                let target = response.headers()
+                    .get(reqwest::header::LOCATION)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|location| url.join(location).ok())
+                    .ok_or_else(|| format!("HTTP {} without a valid Location", response.status()))?;
+                return Ok(Fetched::Redirect(normalize_url(&target)));
+            }
+            if !response.status().is_success() {
+                return Err(format!("HTTP error: {}", response.status()));
+            }
+            Ok(Fetched::Page(read_capped(response, MAX_PAGE_BYTES).await?))
+        }.await;
+
+        match fetched {
+            Ok(Fetched::Redirect(target)) => {
+                page.final_url = Some(target.to_string());
+                // A seed moving to https or www keeps its site in scope
+                if depth == 0 && request.include.is_empty() && same_site(&url, &target) {
+                    scope.seed_hosts.insert(host_key(&target));
+                }
+                if !(target.scheme() == "http" || target.scheme() == "https") || !scope.follows(&target) {
+                    page.skipped = Some("Redirected out of scope".to_string());
+                    progress.pages_skipped += 1;
+                } else if hops >= MAX_REDIRECT_HOPS {
+                    page.error = Some(format!("Stopped after {} redirects", MAX_REDIRECT_HOPS));
+                    progress.pages_failed += 1;
+                } else if seen.insert(target.to_string()) {
+                    // Next in line, so the redirect resolves before the rest of the queue
+                    queue.push_front((target, depth, hops + 1));
+                }
+            }
+            Ok(Fetched::Page(body)) => {
+                let final_url = url.clone();
+                let is_html = page.content_type.as_deref().map_or(false, |content_type| content_type.contains("html"));
+                let file_name = page_file_name(pages.len() + 1, &final_url, is_html);
+                outcome.write(output_dir, &file_name, &body)?;
+                page.file = Some(file_name);
+                progress.pages_fetched += 1;
+
+                if is_html {
+                    let html_content = decode_html(&body, page.content_type.as_deref());
+                    page.title = page_title(&html_content);
+
+                    if depth < max_depth {
+                        for link in extract_links(&html_content, final_url.as_str()).unwrap_or_default() {
+                            let link = match Url::parse(&link.url) {
+                                Ok(link) if link.scheme() == "http" || link.scheme() == "https" => normalize_url(&link),
+                                _ => continue,
+                            };
+                            if scope.follows(&link) && seen.insert(link.to_string()) {
+                                queue.push_back((link, depth + 1, 0));
+                            }
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                page.error = Some(e);
+                progress.pages_failed += 1;
+            }
+        }
+
+        pages.push(page);
+        progress.queued = queue.len();
+        on_progress(progress);
+    }
+
+    progress.current_url = None;
+    progress.finished_at = Some(chrono::Utc::now().timestamp());
+    progress.status = if cancel.load(Ordering::SeqCst) { "cancelled" } else { "completed" }.to_string();
+
+    let manifest = CrawlManifest {
+        crawl_id: &progress.crawl_id,
+        seeds: &request.seeds,
+        status: &progress.status,
+        started_at: progress.started_at,
+        finished_at: progress.finished_at.unwrap_or_default(),
+        pages: &pages,
+    };
+    let json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize crawl manifest: {}", e))?;
+    outcome.write(output_dir, MANIFEST_FILE_NAME, json.as_bytes())?;
+
+    Ok(outcome)
+}
+
+// Record the latest progress for list_crawls and tell the UI
+fn publish_progress(app: &AppHandle, progress: &CrawlProgress) {
+    if let Some(job) = app.state::<CrawlJobs>().jobs.lock().unwrap().get_mut(&progress.crawl_id) {
+        job.progress = progress.clone();
+    }
+    if let Err(e) = app.emit_all("crawl-progress", progress) {
+        eprintln!("⚠️ CRAWLER: Failed to emit progress: {}", e);
+    }
+}
+
+#[tauri::command]
+pub async fn start_crawl(
+    app: AppHandle,
+    crawls: State<'_, CrawlJobs>,
+    request: CrawlRequest,
+) -> Result<String, String> {
+    // Reject bad seeds and patterns up front rather than in the background
+    let seeds = parse_seeds(&request.seeds)?;
+    CrawlScope::new(&request, &seeds)?;
+
+    let project_dir = get_project_dir(&app, &request.project_id)?;
+    let output_folder = destination_path(
+        &load_project_data(&app)?,
+        &request.project_id,
+        request.parent_id.as_deref(),
+        &request.output_folder,
+    )?;
+    let output_dir = resolve_project_path(&project_dir, &output_folder)?;
+
+    let crawl_id = Uuid::new_v4().to_string();
+    let cancel = Arc::new(AtomicBool::new(false));
+    let mut progress = CrawlProgress {
+        crawl_id: crawl_id.clone(),
+        project_id: request.project_id.clone(),
+        output_folder: output_folder.clone(),
+        status: "running".to_string(),
+        pages_fetched: 0,
+        pages_failed: 0,
+        pages_skipped: 0,
+        queued: seeds.len(),
+        current_url: None,
+        error: None,
+        started_at: chrono::Utc::now().timestamp(),
+        finished_at: None,
+    };
+
+    crawls.jobs.lock().unwrap().insert(crawl_id.clone(), CrawlJob {
+        cancel: cancel.clone(),
+        progress: progress.clone(),
+    });
+    println!("🕸️ CRAWLER: Starting {} from {} seeds into {}", crawl_id, seeds.len(), output_folder);
+
+    tauri::async_runtime::spawn(async move {
+        let result = run_crawl(&request, &output_dir, &cancel, &mut progress, |progress| {
+            publish_progress(&app, progress);
+        }).await;
+
+        let registered = result.and_then(|outcome| {
+            let in_output = |names: Vec<String>| -> Vec<String> {
+                names.into_iter().map(|name| format!("{}/{}", output_folder, name)).collect()
+            };
+            register_project_files(&app, &request.project_id, &in_output(outcome.created), &in_output(outcome.modified), None)
+        });
+
+        if let Err(e) = registered {
+            println!("❌ CRAWLER: {} failed: {}", progress.crawl_id, e);
+            progress.status = "failed".to_string();
+            progress.error = Some(e);
+            progress.current_url = None;
+            progress.finished_at = Some(chrono::Utc::now().timestamp());
+        } else {
+            println!("✅ CRAWLER: {} {} ({} pages, {} failed, {} skipped)", progress.crawl_id, progress.status,
+                     progress.pages_fetched, progress.pages_failed, progress.pages_skipped);
+        }
+        publish_progress(&app, &progress);
+    });
+
+    Ok(crawl_id)
+}
+
+#[tauri::command]
+pub async fn cancel_crawl(
+    crawls: State<'_, CrawlJobs>,
+    crawl_id: String,
+) -> Result<(), String> {
+    let jobs = crawls.jobs.lock().unwrap();
+    let job = jobs.get(&crawl_id)
+        .ok_or_else(|| format!("Crawl not found: {}", crawl_id))?;
+    if job.progress.status != "running" {
+        return Err(format!("Crawl {} is already {}", crawl_id, job.progress.status));
+    }
+
+    job.cancel.store(true, Ordering::SeqCst);
+    println!("🛑 CRAWLER: Cancel requested for {}", crawl_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_crawls(crawls: State<'_, CrawlJobs>) -> Result<Vec<CrawlProgress>, String> {
+    let mut crawls: Vec<CrawlProgress> = crawls.jobs.lock().unwrap()
+        .values()
+        .map(|job| job.progress.clone())
+        .collect();
+    crawls.sort_by_key(|crawl| std::cmp::Reverse(crawl.started_at));
+    Ok(crawls)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_server::*;
+
+    // A small site: an index with pagination, a private area and an off-site link
+    fn site(request: &MockRequest) -> MockResponse {
+        let html = |body: &str| respond(200, "text/html; charset=utf-8", format!("<html><head><title>{}</title></head><body>{}</body></html>", request.target, body).into_bytes());
+        match request.target.as_str() {
+            "/robots.txt" => respond(200, "text/plain", b"User-agent: *\nDisallow: /private\nAllow: /private/open\n".to_vec()),
+            "/" => html(r#"<a href="/list?page=1">1</a><a href="/list?page=2#top">2</a><a href="/private/a">p</a>
+                <a href="/private/open">o</a><a href="https://elsewhere.example/">x</a><a href="/list?page=1">again</a>"#),
+            "/list?page=1" => html(r#"<a href="/item/1">item</a><a href="/list?page=2">next</a>"#),
+            "/list?page=2" => html(r#"<a href="/item/2">item</a><a href="/data.csv">csv</a>"#),
+            "/item/1" | "/item/2" | "/private/open" => html("<p>detail</p>"),
+            "/data.csv" => respond(200, "text/csv", b"a,b\n1,2\n".to_vec()),
+            "/moved" => redirect("/item/1"),
+            "/moved-private" => redirect("/private/a"),
+            "/moved-away" => redirect("https://elsewhere.example/"),
+            target if target.starts_with("/r/") => {
+                // Endless chain of fresh URLs
+                let hop: u32 = target[3..].parse().unwrap_or(0);
+                redirect(&format!("/r/{}", hop + 1))
+            }
+            _ => respond(404, "text/plain", b"missing".to_vec()),
+        }
+    }
+
+    fn redirect(location: &str) -> MockResponse {
+        let mut response = respond(301, "text/plain", Vec::new());
+        response.headers.push(("Location", location.to_string()));
+        response
+    }
+
+    fn crawl_request(seed: String) -> CrawlRequest {
+        CrawlRequest {
+            project_id: "project".to_string(),
+            parent_id: None,
+            output_folder: "crawl".to_string(),
+            seeds: vec![seed],
+            include: Vec::new(),
+            exclude: Vec::new(),
+            max_depth: None,
+            max_pages: None,
+            delay_ms: Some(0),
+            respect_robots: None,
+        }
+    }
+
+    fn new_progress() -> CrawlProgress {
+        CrawlProgress {
+            crawl_id: "test".to_string(),
+            project_id: "project".to_string(),
+            output_folder: "crawl".to_string(),
+            status: "running".to_string(),
+            pages_fetched: 0,
+            pages_failed: 0,
+            pages_skipped: 0,
+            queued: 0,
+            current_url: None,
+            error: None,
+            started_at: 0,
+            finished_at: None,
+        }
+    }
+
+    async fn crawl(request: &CrawlRequest, output_dir: &Path, cancel: bool) -> (CrawlProgress, CrawlOutcome, Vec<serde_json::Value>) {
+        let mut progress = new_progress();
+        let outcome = run_crawl(request, output_dir, &AtomicBool::new(cancel), &mut progress, |_| {}).await.unwrap();
+        let manifest: serde_json::Value = serde_json::from_str(&fs::read_to_string(output_dir.join(MANIFEST_FILE_NAME)).unwrap()).unwrap();
+        let pages = manifest["pages"].as_array().unwrap().clone();
+        (progress, outcome, pages)
+    }
+
+    fn urls(pages: &[serde_json::Value], base: &str) -> Vec<String> {
+        pages.iter().map(|page| page["url"].as_str().unwrap().replace(base, "")).collect()
+    }
+
+    #[tokio::test]
+    async fn crawls_breadth_first_with_dedupe_and_robots() {
+        let base = spawn_server(site);
+        let output = tempfile::tempdir().unwrap();
+
+        let (progress, outcome, pages) = crawl(&crawl_request(format!("{}/", base)), output.path(), false).await;
+
+        assert_eq!(urls(&pages, &base), vec![
+            "/", "/list?page=1", "/list?page=2", "/private/a", "/private/open",
+            "/item/1", "/item/2", "/data.csv",
+        ]);
+        assert_eq!(progress.status, "completed");
+        assert_eq!(progress.pages_fetched, 7);
+        assert_eq!(progress.pages_skipped, 1);
+        assert_eq!(pages[3]["skipped"], "Disallowed by robots.txt");
+        assert_eq!(pages[0]["title"], "/");
+        assert_eq!(pages[7]["file"], "0008-127_0_0_1_data_csv.csv");
+        assert_eq!(outcome.created.len(), 8); // Seven pages and the manifest
+        assert!(output.path().join("0001-127_0_0_1.html").exists());
+    }
+
+    #[tokio::test]
+    async fn depth_page_limits_and_patterns_bound_the_crawl() {
+        let base = spawn_server(site);
+        let output = tempfile::tempdir().unwrap();
+
+        let mut shallow = crawl_request(format!("{}/", base));
+        shallow.max_depth = Some(1);
+        shallow.exclude = vec!["/private".to_string()];
+        let (_, _, pages) = crawl(&shallow, output.path(), false).await;
+        assert_eq!(urls(&pages, &base), vec!["/", "/list?page=1", "/list?page=2"]);
+
+        let mut limited = crawl_request(format!("{}/", base));
+        limited.max_pages = Some(2);
+        let (progress, _, pages) = crawl(&limited, output.path(), false).await;
+        assert_eq!(progress.pages_fetched, 2);
+        assert_eq!(pages.len(), 2);
+
+        let mut items_only = crawl_request(format!("{}/list?page=1", base));
+        items_only.include = vec![r"/item/\d+$".to_string()];
+        let (_, _, pages) = crawl(&items_only, output.path(), false).await;
+        assert_eq!(urls(&pages, &base), vec!["/list?page=1", "/item/1"]);
+    }
+
+    #[tokio::test]
+    async fn requests_to_a_host_are_spaced_out() {
+        let base = spawn_server(site);
+        let output = tempfile::tempdir().unwrap();
+        let mut request = crawl_request(format!("{}/list?page=2", base));
+        request.max_depth = Some(1);
+        request.delay_ms = Some(150);
+
+        let started = Instant::now();
+        let (progress, _, _) = crawl(&request, output.path(), false).await;
+
+        // robots.txt plus three pages: three gaps of at least the delay
+        assert_eq!(progress.pages_fetched, 3);
+        assert!(started.elapsed() >= Duration::from_millis(450));
+    }
+
+    #[tokio::test]
+    async fn redirects_are_checked_against_robots_and_scope() {
+        let base = spawn_server(site);
+        let output = tempfile::tempdir().unwrap();
+
+        for (seed, expected) in [("/moved", "/item/1"), ("/moved-private", "/private/a"), ("/moved-away", "https://elsewhere.example/")] {
+            let mut request = crawl_request(format!("{}{}", base, seed));
+            request.max_depth = Some(0);
+            let (progress, _, pages) = crawl(&request, output.path(), false).await;
+            assert_eq!(pages[0]["status"], 301);
+            assert_eq!(pages[0]["final_url"].as_str().unwrap().replace(&base, ""), expected);
+
+            match seed {
+                "/moved" => {
+                    assert_eq!(urls(&pages, &base), vec!["/moved", "/item/1"]);
+                    assert_eq!(progress.pages_fetched, 1);
+                }
+                "/moved-private" => assert_eq!(pages[1]["skipped"], "Disallowed by robots.txt"),
+                _ => {
+                    assert_eq!(pages.len(), 1);
+                    assert_eq!(pages[0]["skipped"], "Redirected out of scope");
+                    assert_eq!(progress.pages_skipped, 1);
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn endless_redirect_chains_are_cut_off() {
+        let base = spawn_server(site);
+        let output = tempfile::tempdir().unwrap();
+
+        let (progress, _, pages) = crawl(&crawl_request(format!("{}/r/0", base)), output.path(), false).await;
+
+        assert_eq!(pages.len(), MAX_REDIRECT_HOPS as usize + 1);
+        let last = pages.last().unwrap();
+        assert_eq!(last["url"].as_str().unwrap().replace(&base, ""), format!("/r/{}", MAX_REDIRECT_HOPS));
+        assert_eq!(last["error"], format!("Stopped after {} redirects", MAX_REDIRECT_HOPS));
+        assert_eq!((progress.pages_fetched, progress.pages_failed), (0, 1));
+    }
+
+    #[tokio::test]
+    async fn cancelled_crawl_stops_and_still_writes_manifest() {
+        let base = spawn_server(site);
+        let output = tempfile::tempdir().unwrap();
+
+        let (progress, outcome, pages) = crawl(&crawl_request(format!("{}/", base)), output.path(), true).await;
+
+        assert_eq!(progress.status, "cancelled");
+        assert!(pages.is_empty());
+        assert_eq!(outcome.created, vec![MANIFEST_FILE_NAME.to_string()]);
+    }
+
+    #[test]
+    fn robots_rules_pick_the_most_specific_group_and_pattern() {
+        let robots = "User-agent: *\nDisallow: /\n\nUser-agent: FileManagerCrawler\nUser-agent: other\nDisallow: /tmp\nAllow: /tmp/public\nDisallow: /*.pdf$\nCrawl-delay: 2\n";
+        let rules = parse_robots(robots);
+
+        assert!(rules.allows("/index.html"));
+        assert!(!rules.allows("/tmp/file"));
+        assert!(rules.allows("/tmp/public/file"));
+        assert!(!rules.allows("/docs/report.pdf"));
+        assert!(rules.allows("/docs/report.pdf?download=1"));
+        assert_eq!(rules.crawl_delay, Some(2.0));
+
+        assert!(!parse_robots("User-agent: *\nDisallow: /\n").allows("/anything"));
+        assert!(parse_robots("User-agent: *\nDisallow:\n").allows("/anything"));
+    }
+
+    #[test]
+    fn invalid_seeds_and_patterns_are_rejected() {
+        assert!(parse_seeds(&[]).is_err());
+        assert!(parse_seeds(&["ftp://example.com".to_string()]).is_err());
+
+        let mut request = crawl_request("http://example.com/".to_string());
+        request.include = vec!["(".to_string()];
+        let seeds = parse_seeds(&request.seeds).unwrap();
+        assert!(CrawlScope::new(&request, &seeds).is_err());
+    }
+}
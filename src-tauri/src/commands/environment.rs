@@ -0,0 +1,464 @@
+// src-tauri/src/commands/environment.rs
+// Per-project environment variables and encrypted secrets for executions
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+use super::utils::*;
+
+const KEY_FILE_NAME: &str = "secrets.key";
+const DOTENV_FILE_NAME: &str = ".env";
+const REDACTED: &str = "[REDACTED]";
+const MIN_SECRET_LINE_LEN: usize = 8;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct EncryptedValue {
+    nonce: String, // base64
+    ciphertext: String, // base64
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct EnvironmentStore {
+    #[serde(default)]
+    variables: BTreeMap<String, String>,
+    #[serde(default)]
+    secrets: BTreeMap<String, EncryptedValue>,
+}
+
+/// What the UI sees: secret values never leave the backend.
+#[derive(Debug, Serialize)]
+pub struct ProjectEnvironment {
+    pub variables: BTreeMap<String, String>,
+    pub secrets: Vec<String>,
+    pub dotenv: BTreeMap<String, String>, // Read-only, from the project's .env file
+}
+
+/// Serializes writers of the environment stores.
+#[derive(Default)]
+pub struct ProjectEnvironments {
+    write_lock: Mutex<()>,
+}
+
+/// Variables to inject into a child process, plus the secret values to scrub from its output.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ExecutionEnvironment {
+    pub vars: Vec<(String, String)>,
+    secret_values: Vec<String>,
+}
+
+impl ExecutionEnvironment {
+    pub(crate) fn redact(&self, text: &str) -> String {
+        let mut redacted = text.to_string();
+        for secret in &self.secret_values {
+            if redacted.contains(secret.as_str()) {
+                redacted = redacted.replace(secret.as_str(), REDACTED);
+            }
+        }
+        redacted
+    }
+
+    /// Redacts every string in a JSON value. Redacting its serialized form would
+    /// miss secrets whose characters were escaped on the way out.
+    pub(crate) fn redact_json(&self, value: &serde_json::Value) -> serde_json::Value {
+        use serde_json::Value;
+        match value {
+            Value::String(text) => Value::String(self.redact(text)),
+            Value::Array(items) => Value::Array(items.iter().map(|item| self.redact_json(item)).collect()),
+            Value::Object(map) => Value::Object(map.iter()
+                .map(|(key, item)| (self.redact(key), self.redact_json(item)))
+                .collect()),
+            other => other.clone(),
+        }
+    }
+
+    /// Redacts one piece of a stream that arrives in pieces. A tail that could be the
+    /// start of a secret is held back in `held` until the next piece shows whether it is.
+    pub(crate) fn redact_chunk(&self, held: &mut String, chunk: &str) -> String {
+        held.push_str(chunk);
+        let text = self.redact(held);
+        let keep = self.secret_values.iter()
+            .map(|secret| partial_secret_len(&text, secret))
+            .max()
+            .unwrap_or(0);
+        *held = text[text.len() - keep..].to_string();
+        text[..text.len() - keep].to_string()
+    }
+}
+
+// Length of the longest tail of `text` that is the start, but not the whole, of `secret`
+fn partial_secret_len(text: &str, secret: &str) -> usize {
+    secret.char_indices()
+        .map(|(index, _)| index)
+        .filter(|&index| index > 0)
+        .rev()
+        .find(|&index| text.ends_with(&secret[..index]))
+        .unwrap_or(0)
+}
+
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() || first == '_' => {
+            chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        _ => false,
+    }
+}
+
+fn get_store_file(app: &AppHandle, project_id: &str) -> Result<PathBuf, String> {
+    Ok(get_environment_dir(app)?.join(format!("{}.json", project_id)))
+}
+
+fn load_store(app: &AppHandle, project_id: &str) -> Result<EnvironmentStore, String> {
+    let store_file = get_store_file(app, project_id)?;
+    if !store_file.exists() {
+        return Ok(EnvironmentStore::default());
+    }
+
+    let content = safe_file_operation(
+        || fs::read_to_string(&store_file),
+        "Failed to read project environment"
+    )?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse project environment: {}", e))
+}
+
+fn save_store(app: &AppHandle, project_id: &str, store: &EnvironmentStore) -> Result<(), String> {
+    let store_file = get_store_file(app, project_id)?;
+    ensure_parent_dir(&store_file)
+        .map_err(|e| format!("Failed to create environment directory: {}", e))?;
+
+    let json = serde_json::to_string_pretty(store)
+        .map_err(|e| format!("Failed to serialize project environment: {}", e))?;
+    safe_file_operation(
+        || fs::write(&store_file, json),
+        "Failed to write project environment"
+    )
+}
+
+// Write a new file readable only by the current user where the platform allows it
+fn write_private_file(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(content)
+}
+
+// The key is generated on first use and kept in the app-local data dir, away from
+// the stores it decrypts. Keys from older versions sat next to the stores and are moved.
+fn load_or_create_cipher(app: &AppHandle) -> Result<Aes256Gcm, String> {
+    let key_file = get_secrets_key_file(app)?;
+    let legacy_key_file = get_environment_dir(app)?.join(KEY_FILE_NAME);
+
+    if !key_file.exists() && legacy_key_file.exists() {
+        let encoded = safe_file_operation(
+            || fs::read(&legacy_key_file),
+            "Failed to read secrets key"
+        )?;
+        ensure_parent_dir(&key_file)
+            .map_err(|e| format!("Failed to create key directory: {}", e))?;
+        safe_file_operation(
+            || write_private_file(&key_file, &encoded),
+            "Failed to move secrets key"
+        )?;
+        if let Err(e) = fs::remove_file(&legacy_key_file) {
+            eprintln!("⚠️ ENV: Failed to remove old secrets key: {}", e);
+        }
+        println!("🔐 ENV: Moved secrets key to {:?}", key_file);
+    }
+
+    load_or_create_key(&key_file)
+}
+
+fn load_or_create_key(key_file: &Path) -> Result<Aes256Gcm, String> {
+    if !key_file.exists() {
+        ensure_parent_dir(key_file)
+            .map_err(|e| format!("Failed to create key directory: {}", e))?;
+        let key = Aes256Gcm::generate_key(OsRng);
+        safe_file_operation(
+            || write_private_file(key_file, BASE64.encode(key).as_bytes()),
+            "Failed to create secrets key"
+        )?;
+        println!("🔐 ENV: Generated secrets key");
+    }
+
+    // Tighten keys written before they were created private
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Ok(metadata) = fs::metadata(key_file) {
+            if metadata.permissions().mode() & 0o077 != 0 {
+                let _ = fs::set_permissions(key_file, fs::Permissions::from_mode(0o600));
+            }
+        }
+    }
+
+    let encoded = safe_file_operation(
+        || fs::read_to_string(key_file),
+        "Failed to read secrets key"
+    )?;
+    let key_bytes = BASE64.decode(encoded.trim())
+        .map_err(|e| format!("Secrets key is corrupted: {}", e))?;
+    if key_bytes.len() != 32 {
+        return Err("Secrets key is corrupted: wrong length".to_string());
+    }
+
+    Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key_bytes)))
+}
+
+fn encrypt_secret(cipher: &Aes256Gcm, value: &str) -> Result<EncryptedValue, String> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher.encrypt(&nonce, value.as_bytes())
+        .map_err(|_| "Failed to encrypt secret".to_string())?;
+
+    Ok(EncryptedValue {
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    })
+}
+
+fn decrypt_secret(cipher: &Aes256Gcm, name: &str, value: &EncryptedValue) -> Result<String, String> {
+    let corrupted = || format!("Secret {} could not be decrypted", name);
+
+    let nonce = BASE64.decode(&value.nonce).map_err(|_| corrupted())?;
+    let ciphertext = BASE64.decode(&value.ciphertext).map_err(|_| corrupted())?;
+    if nonce.len() != 12 {
+        return Err(corrupted());
+    }
+
+    let plaintext = cipher.decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| corrupted())?;
+    String::from_utf8(plaintext).map_err(|_| corrupted())
+}
+
+// Minimal .env reader: KEY=VALUE lines, optional `export`, quotes and # comments
+fn read_dotenv(project_dir: &Path) -> BTreeMap<String, String> {
+    let content = match fs::read_to_string(project_dir.join(DOTENV_FILE_NAME)) {
+        Ok(content) => content,
+        Err(_) => return BTreeMap::new(),
+    };
+
+    content.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let key = key.trim();
+            if !is_valid_key(key) {
+                return None;
+            }
+
+            let value = value.trim();
+            let value = if value.len() >= 2
+                && ((value.starts_with('"') && value.ends_with('"'))
+                    || (value.starts_with('\'') && value.ends_with('\'')))
+            {
+                &value[1..value.len() - 1]
+            } else {
+                // Unquoted values may carry a trailing comment
+                value.split(" #").next().unwrap_or(value).trim_end()
+            };
+            Some((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Everything an execution in this project should see: the project's .env file,
+/// then stored variables, then decrypted secrets (later sources win).
+pub(crate) fn load_execution_environment(
+    app: &AppHandle,
+    project_id: &str,
+) -> Result<ExecutionEnvironment, String> {
+    let project_dir = get_project_dir(app, project_id)?;
+    let store = load_store(app, project_id)?;
+
+    let mut vars = read_dotenv(&project_dir);
+    vars.extend(store.variables);
+
+    let mut secret_values = Vec::new();
+    if !store.secrets.is_empty() {
+        let cipher = load_or_create_cipher(app)?;
+        for (name, encrypted) in &store.secrets {
+            let value = decrypt_secret(&cipher, name, encrypted)?;
+            if !value.is_empty() {
+                secret_values.push(value.clone());
+            }
+            // Output is often scrubbed a line at a time, so multi-line secrets
+            // (keys, certificates) are also scrubbed line by line
+            if value.contains('\n') {
+                secret_values.extend(value.lines()
+                    .map(str::trim)
+                    .filter(|line| line.len() >= MIN_SECRET_LINE_LEN)
+                    .map(str::to_string));
+            }
+            vars.insert(name.clone(), value);
+        }
+    }
+
+    // Longest first so a secret containing another is scrubbed whole
+    secret_values.sort_by_key(|value| std::cmp::Reverse(value.len()));
+
+    Ok(ExecutionEnvironment {
+        vars: vars.into_iter().collect(),
+        secret_values,
+    })
+}
+
+#[tauri::command]
+pub async fn get_project_environment(
+    app: AppHandle,
+    project_id: String,
+) -> Result<ProjectEnvironment, String> {
+    let project_dir = get_project_dir(&app, &project_id)?;
+    let store = load_store(&app, &project_id)?;
+
+    Ok(ProjectEnvironment {
+        variables: store.variables,
+        secrets: store.secrets.into_keys().collect(),
+        dotenv: read_dotenv(&project_dir),
+    })
+}
+
+#[tauri::command]
+pub async fn set_project_env_var(
+    app: AppHandle,
+    environments: State<'_, ProjectEnvironments>,
+    project_id: String,
+    key: String,
+    value: String,
+    secret: Option<bool>,
+) -> Result<(), String> {
+    let key = key.trim().to_string();
+    if !is_valid_key(&key) {
+        return Err(format!("Invalid variable name: {}", key));
+    }
+
+    let _guard = environments.write_lock.lock().unwrap();
+    let mut store = load_store(&app, &project_id)?;
+
+    // A name is either a plain variable or a secret, never both
+    if secret.unwrap_or(false) {
+        let cipher = load_or_create_cipher(&app)?;
+        store.variables.remove(&key);
+        store.secrets.insert(key.clone(), encrypt_secret(&cipher, &value)?);
+        println!("🔐 ENV: Stored secret {} for project {}", key, project_id);
+    } else {
+        store.secrets.remove(&key);
+        store.variables.insert(key.clone(), value);
+        println!("🌱 ENV: Stored variable {} for project {}", key, project_id);
+    }
+
+    save_store(&app, &project_id, &store)
+}
+
+#[tauri::command]
+pub async fn delete_project_env_var(
+    app: AppHandle,
+    environments: State<'_, ProjectEnvironments>,
+    project_id: String,
+    key: String,
+) -> Result<(), String> {
+    let _guard = environments.write_lock.lock().unwrap();
+    let mut store = load_store(&app, &project_id)?;
+
+    let removed = store.variables.remove(&key).is_some() | store.secrets.remove(&key).is_some();
+    if !removed {
+        return Err(format!("Variable not found: {}", key));
+    }
+
+    println!("🗑️ ENV: Removed {} from project {}", key, project_id);
+    save_store(&app, &project_id, &store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn environment(secrets: &[&str]) -> ExecutionEnvironment {
+        ExecutionEnvironment {
+            vars: Vec::new(),
+            secret_values: secrets.iter().map(|secret| secret.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn redacts_secrets_in_text_json_and_split_chunks() {
+        let env = environment(&["hunter2\"pass", "s3cr3t"]);
+        assert_eq!(env.redact("token=s3cr3t; again s3cr3t"), "token=[REDACTED]; again [REDACTED]");
+
+        // Serialized, the quote would read \" and slip past a plain replace
+        let data = serde_json::json!({ "text/plain": "pw: hunter2\"pass", "n": 1 });
+        assert_eq!(env.redact_json(&data), serde_json::json!({ "text/plain": "pw: [REDACTED]", "n": 1 }));
+
+        let mut held = String::new();
+        let mut output = String::new();
+        for chunk in ["value: s3", "cr", "3t and s", "ome more"] {
+            output.push_str(&env.redact_chunk(&mut held, chunk));
+        }
+        output.push_str(&held);
+        assert_eq!(output, "value: [REDACTED] and some more");
+    }
+
+    #[test]
+    fn validates_variable_names() {
+        for key in ["PATH", "_private", "API_KEY_2"] {
+            assert!(is_valid_key(key), "{}", key);
+        }
+        for key in ["", "2FAST", "WITH-DASH", "HAS SPACE", "ÜBER"] {
+            assert!(!is_valid_key(key), "{}", key);
+        }
+    }
+
+    #[test]
+    fn secrets_round_trip_through_the_store_and_a_private_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let key_file = dir.path().join("keys").join(KEY_FILE_NAME);
+        let cipher = load_or_create_key(&key_file).unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&key_file).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+
+        let mut store = EnvironmentStore::default();
+        store.variables.insert("MODE".to_string(), "dev".to_string());
+        store.secrets.insert("TOKEN".to_string(), encrypt_secret(&cipher, "abc123").unwrap());
+        let json = serde_json::to_string(&store).unwrap();
+        assert!(!json.contains("abc123"));
+
+        // A reloaded key decrypts what the first one encrypted
+        let reloaded: EnvironmentStore = serde_json::from_str(&json).unwrap();
+        let cipher = load_or_create_key(&key_file).unwrap();
+        assert_eq!(reloaded.variables["MODE"], "dev");
+        assert_eq!(decrypt_secret(&cipher, "TOKEN", &reloaded.secrets["TOKEN"]).unwrap(), "abc123");
+
+        let mut tampered = reloaded.secrets["TOKEN"].clone();
+        tampered.ciphertext = BASE64.encode(b"not the ciphertext");
+        assert!(decrypt_secret(&cipher, "TOKEN", &tampered).is_err());
+    }
+
+    #[test]
+    fn reads_dotenv_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(DOTENV_FILE_NAME), "# comment\nexport A=1\nB=\"two words\"\nC=3 # note\nbad-key=4\n").unwrap();
+
+        let vars = read_dotenv(dir.path());
+        assert_eq!(vars.len(), 3);
+        assert_eq!(vars["A"], "1");
+        assert_eq!(vars["B"], "two words");
+        assert_eq!(vars["C"], "3");
+    }
+}
@@ -1,153 +1,171 @@
 // src-tauri/src/commands/execution.rs
-// Commands for executing Python files and managing Python environment
+// Commands for executing project files and managing Python environment
 
-use std::process::{Command, Stdio};
+use std::process::{Child, Command, Stdio};
 use std::io::{BufRead, BufReader};
-use tauri::AppHandle;
-use super::{ExecutionResult, utils::*};
-
-#[tauri::command]
-pub async fn execute_python_file(
-    app: AppHandle,
-    _node_id: String,
-    file_path: String,
-    project_id: String,
+use tauri::{AppHandle, Manager};
+use uuid::Uuid;
+use super::{
+    ExecutionResult,
+    artifacts::{diff_snapshots, register_project_files, snapshot_project, ProjectRuns},
+    history::{record_execution, record_package_install},
+    runners::run_with_registry,
+    utils::*,
+};
+
+// Drain a spawned child's output and wait for it to exit
+fn collect_child_output(
+    mut child: Child,
+    start_time: std::time::Instant,
 ) -> Result<ExecutionResult, String> {
-    let project_dir = get_project_dir(&app, &project_id)?;
-    let full_path = project_dir.join(&file_path);
-    
-    if !full_path.exists() {
-        return Err("Python file not found".to_string());
-    }
-
-    let start_time = std::time::Instant::now();
-    println!("🐍 Executing Python file: {:?}", full_path);
-
-    // Try different Python commands
-    let python_commands = ["python3", "python", "py"];
-    let mut last_error = String::new();
-
-    for python_cmd in &python_commands {
-        match Command::new(python_cmd)
-            .arg(&full_path)
-            .current_dir(&project_dir)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-        {
-            Ok(mut child) => {
-                let stdout = child.stdout.take().unwrap();
-                let stderr = child.stderr.take().unwrap();
-
-                let stdout_reader = BufReader::new(stdout);
-                let stderr_reader = BufReader::new(stderr);
-
-                let mut stdout_lines = Vec::new();
-                let mut stderr_lines = Vec::new();
-
-                // Read stdout
-                for line in stdout_reader.lines() {
-                    if let Ok(line) = line {
-                        stdout_lines.push(line);
-                    }
-                }
+    let stdout = child.stdout.take().unwrap();
+    let stderr = child.stderr.take().unwrap();
+
+    // Drain stderr on its own thread so a full pipe can't stall the child
+    let stderr_thread = std::thread::spawn(move || {
+        BufReader::new(stderr).lines().map_while(Result::ok).collect::<Vec<String>>()
+    });
+    let stdout_lines: Vec<String> = BufReader::new(stdout).lines().map_while(Result::ok).collect();
+    let stderr_lines = stderr_thread.join().unwrap_or_default();
+
+    let exit_status = child.wait().map_err(|e| e.to_string())?;
+    let duration = start_time.elapsed().as_millis() as u64;
+
+    Ok(ExecutionResult {
+        success: exit_status.success(),
+        stdout: stdout_lines.join("\n"),
+        stderr: stderr_lines.join("\n"),
+        exit_code: exit_status.code(),
+        duration_ms: duration,
+        run_id: None,
+        created_files: Vec::new(),
+        modified_files: Vec::new(),
+        new_nodes: Vec::new(),
+        files_overlapped: false,
+        cancelled: false,
+        timed_out: false,
+    })
+}
 
-                // Read stderr
-                for line in stderr_reader.lines() {
-                    if let Ok(line) = line {
-                        stderr_lines.push(line);
-                    }
-                }
+/// A finished script run together with the command line that produced it.
+pub(crate) struct ScriptRun {
+    pub interpreter: String,
+    pub args: Vec<String>,
+    pub result: ExecutionResult,
+}
 
-                let exit_status = child.wait().map_err(|e| e.to_string())?;
-                let duration = start_time.elapsed().as_millis() as u64;
+/// What to run and where it came from, for execution history.
+#[derive(Clone)]
+pub(crate) struct ExecutionRequest {
+    pub node_id: Option<String>,
+    pub project_id: String,
+    pub file_path: String,
+    pub trigger: String, // "manual", "queue", "pipeline" or "rerun"
+    pub database: Option<String>, // For runners that execute against a database (sqlite3)
+}
 
-                let result = ExecutionResult {
-                    success: exit_status.success(),
-                    stdout: stdout_lines.join("\n"),
-                    stderr: stderr_lines.join("\n"),
-                    exit_code: exit_status.code(),
-                    duration_ms: duration,
-                };
+/// Runs a project file off the async runtime and records it in the execution history.
+pub(crate) async fn execute_and_record(
+    app: &AppHandle,
+    request: ExecutionRequest,
+) -> Result<ExecutionResult, String> {
+    let project_dir = get_project_dir(app, &request.project_id)?;
+    let started_at = chrono::Utc::now().timestamp_millis();
+    let run_id = Uuid::new_v4().to_string();
+
+    // Snapshot around the run so files the script writes can be attributed to it
+    app.state::<ProjectRuns>().start(&request.project_id, &run_id);
+    let blocking_app = app.clone();
+    let blocking_run_id = run_id.clone();
+    let blocking_request = request.clone();
+    let (mut outcome, created, modified) = tauri::async_runtime::spawn_blocking(move || {
+        let before = snapshot_project(&project_dir);
+        let outcome = run_with_registry(&blocking_app, &blocking_run_id, &blocking_request, &project_dir);
+        let (created, modified) = diff_snapshots(&before, &snapshot_project(&project_dir));
+        (outcome, created, modified)
+    })
+        .await
+        .unwrap_or_else(|e| (Err(format!("Execution task failed: {}", e)), Vec::new(), Vec::new()));
+
+    // With other writers in the project the diff also holds their files, so
+    // the files are registered but not credited to this run
+    let attributable = app.state::<ProjectRuns>().finish(&request.project_id, &run_id);
+    if !attributable {
+        println!("⚠️ ARTIFACTS: Run {} overlapped other writes to project {}; its outputs are not attributed",
+                 run_id, request.project_id);
+    }
+    let produced_by = Some(run_id.as_str()).filter(|_| attributable);
 
-                println!("✅ Python execution completed in {}ms", duration);
-                return Ok(result);
-            }
-            Err(e) => {
-                last_error = format!("{}: {}", python_cmd, e);
-                continue;
-            }
+    let new_nodes = match register_project_files(app, &request.project_id, &created, &modified, produced_by) {
+        Ok(nodes) => nodes,
+        Err(e) => {
+            eprintln!("⚠️ ARTIFACTS: Failed to register run outputs: {}", e);
+            Vec::new()
         }
+    };
+
+    // The lists are kept but flagged: they cover the run, though maybe not only its files
+    if let Ok(run) = &mut outcome {
+        run.result.files_overlapped = !attributable;
     }
+    record_execution(app, &run_id, &request, started_at, &outcome, &created, &modified);
+    outcome.map(|run| ExecutionResult {
+        run_id: Some(run_id),
+        created_files: created,
+        modified_files: modified,
+        new_nodes,
+        ..run.result
+    })
+}
 
-    Err(format!("Failed to execute Python. Last error: {}", last_error))
+#[tauri::command]
+pub async fn execute_python_file(
+    app: AppHandle,
+    node_id: String,
+    file_path: String,
+    project_id: String,
+) -> Result<ExecutionResult, String> {
+    execute_and_record(&app, ExecutionRequest {
+        node_id: Some(node_id),
+        project_id,
+        file_path,
+        trigger: "manual".to_string(),
+        database: None,
+    }).await
 }
 
 #[tauri::command]
 pub async fn execute_jupyter_notebook(
     app: AppHandle,
-    _node_id: String,
+    node_id: String,
     file_path: String,
     project_id: String,
 ) -> Result<ExecutionResult, String> {
-    let project_dir = get_project_dir(&app, &project_id)?;
-    let full_path = project_dir.join(&file_path);
-    
-    if !full_path.exists() {
-        return Err("Jupyter notebook not found".to_string());
-    }
-
-    let start_time = std::time::Instant::now();
-    println!("📓 Executing Jupyter notebook: {:?}", full_path);
-
-    // Try jupyter nbconvert
-    match Command::new("jupyter")
-        .args(&["nbconvert", "--to", "notebook", "--execute", "--inplace"])
-        .arg(&full_path)
-        .current_dir(&project_dir)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-    {
-        Ok(mut child) => {
-            let stdout = child.stdout.take().unwrap();
-            let stderr = child.stderr.take().unwrap();
-
-            let stdout_reader = BufReader::new(stdout);
-            let stderr_reader = BufReader::new(stderr);
-
-            let mut stdout_lines = Vec::new();
-            let mut stderr_lines = Vec::new();
-
-            // Read outputs
-            for line in stdout_reader.lines() {
-                if let Ok(line) = line {
-                    stdout_lines.push(line);
-                }
-            }
-
-            for line in stderr_reader.lines() {
-                if let Ok(line) = line {
-                    stderr_lines.push(line);
-                }
-            }
-
-            let exit_status = child.wait().map_err(|e| e.to_string())?;
-            let duration = start_time.elapsed().as_millis() as u64;
-
-            let result = ExecutionResult {
-                success: exit_status.success(),
-                stdout: stdout_lines.join("\n"),
-                stderr: stderr_lines.join("\n"),
-                exit_code: exit_status.code(),
-                duration_ms: duration,
-            };
+    execute_and_record(&app, ExecutionRequest {
+        node_id: Some(node_id),
+        project_id,
+        file_path,
+        trigger: "manual".to_string(),
+        database: None,
+    }).await
+}
 
-            println!("✅ Jupyter execution completed in {}ms", duration);
-            Ok(result)
-        }
-        Err(e) => Err(format!("Failed to execute Jupyter notebook: {}", e))
-    }
+// Runs any file that has a runner registered for its extension
+#[tauri::command]
+pub async fn execute_file(
+    app: AppHandle,
+    node_id: Option<String>,
+    file_path: String,
+    project_id: String,
+    database: Option<String>,
+) -> Result<ExecutionResult, String> {
+    execute_and_record(&app, ExecutionRequest {
+        node_id,
+        project_id,
+        file_path,
+        trigger: "manual".to_string(),
+        database,
+    }).await
 }
 
 #[tauri::command]
@@ -182,53 +200,28 @@ pub async fn check_python_installation() -> Result<Vec<String>, String> {
 
 #[tauri::command]
 pub async fn install_python_package(
+    app: AppHandle,
     package_name: String,
     python_cmd: Option<String>,
+    project_id: Option<String>,
 ) -> Result<ExecutionResult, String> {
     let python_command = python_cmd.unwrap_or_else(|| "pip".to_string());
     let start_time = std::time::Instant::now();
+    let started_at = chrono::Utc::now().timestamp_millis();
 
     println!("📦 Installing Python package: {}", package_name);
 
-    match Command::new(&python_command)
-        .args(&["install", &package_name])
+    let outcome = match Command::new(&python_command)
+        .args(["install", &package_name])
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .spawn()
     {
-        Ok(mut child) => {
-            let stdout = child.stdout.take().unwrap();
-            let stderr = child.stderr.take().unwrap();
-
-            let stdout_reader = BufReader::new(stdout);
-            let stderr_reader = BufReader::new(stderr);
-
-            let mut stdout_lines = Vec::new();
-            let mut stderr_lines = Vec::new();
-
-            for line in stdout_reader.lines() {
-                if let Ok(line) = line {
-                    stdout_lines.push(line);
-                }
-            }
-
-            for line in stderr_reader.lines() {
-                if let Ok(line) = line {
-                    stderr_lines.push(line);
-                }
-            }
-
-            let exit_status = child.wait().map_err(|e| e.to_string())?;
-            let duration = start_time.elapsed().as_millis() as u64;
-
-            Ok(ExecutionResult {
-                success: exit_status.success(),
-                stdout: stdout_lines.join("\n"),
-                stderr: stderr_lines.join("\n"),
-                exit_code: exit_status.code(),
-                duration_ms: duration,
-            })
-        }
+        Ok(child) => collect_child_output(child, start_time),
         Err(e) => Err(format!("Failed to install package: {}", e))
-    }
+    };
+
+    let project_id = project_id.unwrap_or_default();
+    record_package_install(&app, &project_id, &python_command, &package_name, started_at, &outcome);
+    outcome
 }
\ No newline at end of file
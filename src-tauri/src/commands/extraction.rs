@@ -0,0 +1,458 @@
+// src-tauri/src/commands/extraction.rs
+// Structured extraction from web pages: field specs, tables, links and images
+
+use std::collections::HashSet;
+use std::path::Path;
+use reqwest::Url;
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::AppHandle;
+use super::{Node, utils::*, web_scraping::{fetch_html, save_project_file}};
+
+const MAX_COLSPAN: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct FieldSpec {
+    pub name: String,
+    pub selector: String, // Relative to the container
+    pub attribute: Option<String>, // Read this attribute instead of the element text
+    #[serde(default)]
+    pub multiple: bool, // Collect every match into an array instead of the first one
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExtractionSpec {
+    pub container: Option<String>, // One row per match; the whole page is a single row when absent
+    pub fields: Vec<FieldSpec>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScrapedTable {
+    pub index: usize,
+    pub caption: Option<String>,
+    pub headers: Vec<String>, // Empty when the table has no header row
+    pub rows: Vec<Vec<String>>,
+    pub csv: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PageLink {
+    pub url: String, // Absolute
+    pub text: String,
+    pub rel: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PageImage {
+    pub url: String, // Absolute
+    pub alt: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SaveScrapeRequest {
+    pub project_id: String,
+    pub parent_id: Option<String>,
+    pub file_path: String, // .csv or .json, relative to the parent folder
+    pub data: Value, // Rows as objects or arrays; anything JSON for .json files
+    pub columns: Option<Vec<String>>, // CSV column order for object rows
+}
+
+fn parse_selector(css_selector: &str) -> Result<Selector, String> {
+    Selector::parse(css_selector)
+        .map_err(|e| format!("Invalid CSS selector '{}': {}", css_selector, e))
+}
+
+// Element text with runs of whitespace collapsed, as a reader would see it
+fn element_text(element: ElementRef) -> String {
+    element.text().collect::<Vec<_>>().join(" ").split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn field_value(element: ElementRef, attribute: Option<&str>) -> Option<String> {
+    match attribute {
+        Some(attribute) => element.value().attr(attribute).map(|value| value.trim().to_string()),
+        None => Some(element_text(element)),
+    }
+}
+
+/// Applies an extraction spec to a page, returning one JSON object per container match.
+fn extract_rows(html_content: &str, spec: &ExtractionSpec) -> Result<Vec<Value>, String> {
+    if spec.fields.is_empty() {
+        return Err("Extraction spec has no fields".to_string());
+    }
+
+    let mut names = HashSet::new();
+    let mut fields = Vec::new();
+    for field in &spec.fields {
+        if field.name.trim().is_empty() || !names.insert(field.name.as_str()) {
+            return Err(format!("Field names must be unique and non-empty: '{}'", field.name));
+        }
+        fields.push((field, parse_selector(&field.selector)?));
+    }
+
+    let document = Html::parse_document(html_content);
+    let containers: Vec<ElementRef> = match &spec.container {
+        Some(container) => document.select(&parse_selector(container)?).collect(),
+        None => vec![document.root_element()],
+    };
+
+    let rows = containers.into_iter()
+        .map(|container| {
+            let mut row = Map::new();
+            for (field, selector) in &fields {
+                let mut values = container.select(selector)
+                    .filter_map(|element| field_value(element, field.attribute.as_deref()));
+
+                let value = if field.multiple {
+                    Value::Array(values.map(Value::String).collect())
+                } else {
+                    values.next().map_or(Value::Null, Value::String)
+                };
+                row.insert(field.name.clone(), value);
+            }
+            Value::Object(row)
+        })
+        .collect();
+
+    Ok(rows)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_csv(rows: &[Vec<String>]) -> String {
+    let mut csv = String::new();
+    for row in rows {
+        csv.push_str(&row.iter().map(|cell| csv_field(cell)).collect::<Vec<_>>().join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+// The cells of a row, with colspans repeated so columns stay aligned
+fn row_cells(row: ElementRef) -> (Vec<String>, bool) {
+    let mut cells = Vec::new();
+    let mut all_headers = true;
+
+    for cell in row.children().filter_map(ElementRef::wrap) {
+        let name = cell.value().name();
+        if name != "th" && name != "td" {
+            continue;
+        }
+        all_headers &= name == "th";
+
+        let span = cell.value().attr("colspan")
+            .and_then(|span| span.trim().parse::<usize>().ok())
+            .unwrap_or(1)
+            .clamp(1, MAX_COLSPAN);
+        let text = element_text(cell);
+        cells.extend(std::iter::repeat(text).take(span));
+    }
+
+    let is_header = all_headers && !cells.is_empty();
+    (cells, is_header)
+}
+
+fn extract_tables(html_content: &str) -> Vec<ScrapedTable> {
+    let document = Html::parse_document(html_content);
+    let table_selector = parse_selector("table").unwrap();
+    let row_selector = parse_selector("tr").unwrap();
+    let caption_selector = parse_selector("caption").unwrap();
+
+    document.select(&table_selector)
+        .enumerate()
+        .map(|(index, table)| {
+            // Rows of nested tables belong to those tables
+            let own_rows = table.select(&row_selector).filter(|row| {
+                row.ancestors()
+                    .filter_map(ElementRef::wrap)
+                    .find(|ancestor| ancestor.value().name() == "table")
+                    .map(|ancestor| ancestor.id()) == Some(table.id())
+            });
+
+            let mut headers = Vec::new();
+            let mut rows = Vec::new();
+            for row in own_rows {
+                let (cells, is_header) = row_cells(row);
+                if cells.is_empty() {
+                    continue;
+                }
+                let in_thead = row.parent()
+                    .and_then(ElementRef::wrap)
+                    .map_or(false, |parent| parent.value().name() == "thead");
+
+                if headers.is_empty() && rows.is_empty() && (is_header || in_thead) {
+                    headers = cells;
+                } else {
+                    rows.push(cells);
+                }
+            }
+
+            let mut all_rows = Vec::with_capacity(rows.len() + 1);
+            if !headers.is_empty() {
+                all_rows.push(headers.clone());
+            }
+            all_rows.extend(rows.iter().cloned());
+
+            ScrapedTable {
+                index,
+                caption: table.select(&caption_selector).next().map(element_text),
+                headers,
+                rows,
+                csv: to_csv(&all_rows),
+            }
+        })
+        .collect()
+}
+
+// Relative references resolve against <base href> when the page declares one
+fn base_url(document: &Html, page_url: &str) -> Result<Url, String> {
+    let page_url = Url::parse(page_url).map_err(|e| format!("Invalid URL {}: {}", page_url, e))?;
+    let base = document.select(&parse_selector("base[href]").unwrap())
+        .next()
+        .and_then(|base| base.value().attr("href"))
+        .and_then(|href| page_url.join(href).ok());
+    Ok(base.unwrap_or(page_url))
+}
+
+fn resolve_reference(base: &Url, reference: &str) -> Option<String> {
+    let url = base.join(reference.trim()).ok()?;
+    match url.scheme() {
+        "http" | "https" | "mailto" | "ftp" | "data" => Some(url.to_string()),
+        _ => None, // javascript: and friends
+    }
+}
+
+pub(crate) fn extract_links(html_content: &str, page_url: &str) -> Result<Vec<PageLink>, String> {
+    let document = Html::parse_document(html_content);
+    let base = base_url(&document, page_url)?;
+    let mut seen = HashSet::new();
+
+    Ok(document.select(&parse_selector("a[href]").unwrap())
+        .filter_map(|link| {
+            let url = resolve_reference(&base, link.value().attr("href")?)?;
+            if !seen.insert(url.clone()) {
+                return None;
+            }
+            Some(PageLink {
+                url,
+                text: element_text(link),
+                rel: link.value().attr("rel").map(str::to_string),
+            })
+        })
+        .collect())
+}
+
+fn extract_images(html_content: &str, page_url: &str) -> Result<Vec<PageImage>, String> {
+    let document = Html::parse_document(html_content);
+    let base = base_url(&document, page_url)?;
+    let mut seen = HashSet::new();
+
+    Ok(document.select(&parse_selector("img[src]").unwrap())
+        .filter_map(|image| {
+            let url = resolve_reference(&base, image.value().attr("src")?)?;
+            if !seen.insert(url.clone()) {
+                return None;
+            }
+            Some(PageImage {
+                url,
+                alt: image.value().attr("alt").map(str::to_string),
+            })
+        })
+        .collect())
+}
+
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items.iter().map(cell_text).collect::<Vec<_>>().join("; "),
+        other => other.to_string(),
+    }
+}
+
+/// Turns rows (arrays of cells, or objects keyed by column) into CSV.
+fn rows_to_csv(data: &Value, columns: Option<&[String]>) -> Result<String, String> {
+    let rows = data.as_array()
+        .ok_or_else(|| "CSV export needs an array of rows".to_string())?;
+
+    if rows.iter().all(Value::is_array) {
+        let rows: Vec<Vec<String>> = rows.iter()
+            .map(|row| row.as_array().unwrap().iter().map(cell_text).collect())
+            .collect();
+        return Ok(to_csv(&rows));
+    }
+
+    if !rows.iter().all(Value::is_object) {
+        return Err("CSV rows must all be arrays or all be objects".to_string());
+    }
+
+    // Without an explicit order, columns appear as they are first seen
+    let columns: Vec<String> = match columns {
+        Some(columns) => columns.to_vec(),
+        None => {
+            let mut columns: Vec<String> = Vec::new();
+            for row in rows {
+                for key in row.as_object().unwrap().keys() {
+                    if !columns.contains(key) {
+                        columns.push(key.clone());
+                    }
+                }
+            }
+            columns
+        }
+    };
+
+    let mut table = vec![columns.clone()];
+    for row in rows {
+        let row = row.as_object().unwrap();
+        table.push(columns.iter()
+            .map(|column| row.get(column).map(cell_text).unwrap_or_default())
+            .collect());
+    }
+    Ok(to_csv(&table))
+}
+
+#[tauri::command]
+pub async fn scrape_structured(app: AppHandle, url: String, spec: ExtractionSpec) -> Result<Vec<Value>, String> {
+    println!("🕷️ Extracting {} fields from: {}", spec.fields.len(), url);
+    let (html_content, _) = fetch_html(&app, &url).await?;
+    let rows = extract_rows(&html_content, &spec)?;
+    println!("✅ Extracted {} rows", rows.len());
+    Ok(rows)
+}
+
+#[tauri::command]
+pub async fn scrape_tables(app: AppHandle, url: String) -> Result<Vec<ScrapedTable>, String> {
+    println!("🕷️ Extracting tables from: {}", url);
+    let (html_content, _) = fetch_html(&app, &url).await?;
+    Ok(extract_tables(&html_content))
+}
+
+#[tauri::command]
+pub async fn scrape_links(app: AppHandle, url: String) -> Result<Vec<PageLink>, String> {
+    println!("🕷️ Extracting links from: {}", url);
+    let (html_content, final_url) = fetch_html(&app, &url).await?;
+    extract_links(&html_content, &final_url)
+}
+
+#[tauri::command]
+pub async fn scrape_images(app: AppHandle, url: String) -> Result<Vec<PageImage>, String> {
+    println!("🕷️ Extracting images from: {}", url);
+    let (html_content, final_url) = fetch_html(&app, &url).await?;
+    extract_images(&html_content, &final_url)
+}
+
+#[tauri::command]
+pub async fn save_scrape_result(app: AppHandle, request: SaveScrapeRequest) -> Result<Node, String> {
+    let content = match get_file_extension(Path::new(&request.file_path)).as_deref() {
+        Some("csv") => rows_to_csv(&request.data, request.columns.as_deref())?,
+        Some("json") => serde_json::to_string_pretty(&request.data)
+            .map_err(|e| format!("Failed to serialize results: {}", e))?,
+        _ => return Err("Scrape results can be saved as .csv or .json".to_string()),
+    };
+
+    save_project_file(&app, &request.project_id, request.parent_id.as_deref(), &request.file_path, content.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<html><head><base href="https://example.com/shop/"></head><body>
+        <div class="product"><h2> Lamp </h2><span class="price">12.50</span><a href="lamp">Details</a>
+            <span class="tag">home</span><span class="tag">light</span></div>
+        <div class="product"><h2>Chair</h2><a href="/chair">Details</a></div>
+        <a href="javascript:void(0)">Nowhere</a><a href="lamp">Again</a>
+        <img src="img/lamp.png" alt="A lamp">
+    </body></html>"#;
+
+    fn field(name: &str, selector: &str, attribute: Option<&str>, multiple: bool) -> FieldSpec {
+        FieldSpec {
+            name: name.to_string(),
+            selector: selector.to_string(),
+            attribute: attribute.map(str::to_string),
+            multiple,
+        }
+    }
+
+    #[test]
+    fn extracts_one_row_per_container() {
+        let spec = ExtractionSpec {
+            container: Some("div.product".to_string()),
+            fields: vec![
+                field("name", "h2", None, false),
+                field("price", ".price", None, false),
+                field("link", "a", Some("href"), false),
+                field("tags", ".tag", None, true),
+            ],
+        };
+
+        let rows = extract_rows(PAGE, &spec).unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0], serde_json::json!({"name": "Lamp", "price": "12.50", "link": "lamp", "tags": ["home", "light"]}));
+        assert_eq!(rows[1]["price"], Value::Null);
+        assert_eq!(rows[1]["tags"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn rejects_duplicate_fields_and_bad_selectors() {
+        let duplicate = ExtractionSpec {
+            container: None,
+            fields: vec![field("a", "h2", None, false), field("a", "p", None, false)],
+        };
+        assert!(extract_rows(PAGE, &duplicate).is_err());
+
+        let invalid = ExtractionSpec { container: None, fields: vec![field("a", "p[", None, false)] };
+        assert!(extract_rows(PAGE, &invalid).is_err());
+    }
+
+    #[test]
+    fn tables_become_csv_with_headers_and_colspans() {
+        let html = r#"<table><caption>Sales</caption>
+            <thead><tr><th>Region</th><th>Q1</th><th>Q2</th></tr></thead>
+            <tbody><tr><td>North, East</td><td colspan="2">10</td></tr>
+            <tr><td>Say "hi"</td><td>1</td><td><table><tr><td>nested</td></tr></table></td></tr></tbody>
+        </table>"#;
+
+        let tables = extract_tables(html);
+
+        assert_eq!(tables.len(), 2);
+        assert_eq!(tables[0].caption.as_deref(), Some("Sales"));
+        assert_eq!(tables[0].headers, vec!["Region", "Q1", "Q2"]);
+        assert_eq!(tables[0].rows.len(), 2);
+        assert_eq!(tables[0].csv, "Region,Q1,Q2\n\"North, East\",10,10\n\"Say \"\"hi\"\"\",1,nested\n");
+        assert_eq!(tables[1].rows, vec![vec!["nested".to_string()]]);
+    }
+
+    #[test]
+    fn links_and_images_are_absolute_and_deduplicated() {
+        let links = extract_links(PAGE, "https://example.com/shop/index.html").unwrap();
+        let urls: Vec<&str> = links.iter().map(|link| link.url.as_str()).collect();
+        assert_eq!(urls, vec!["https://example.com/shop/lamp", "https://example.com/chair"]);
+
+        let images = extract_images(PAGE, "https://example.com/").unwrap();
+        assert_eq!(images[0].url, "https://example.com/shop/img/lamp.png");
+        assert_eq!(images[0].alt.as_deref(), Some("A lamp"));
+    }
+
+    #[test]
+    fn rows_convert_to_csv() {
+        let objects = serde_json::json!([{"name": "Lamp", "tags": ["a", "b"]}, {"name": "Chair", "price": 3}]);
+        assert_eq!(rows_to_csv(&objects, None).unwrap(), "name,tags,price\nLamp,a; b,\nChair,,3\n");
+
+        let ordered = vec!["price".to_string(), "name".to_string()];
+        assert_eq!(rows_to_csv(&objects, Some(&ordered)).unwrap(), "price,name\n,Lamp\n3,Chair\n");
+
+        let arrays = serde_json::json!([["a", "b"], ["1", null]]);
+        assert_eq!(rows_to_csv(&arrays, None).unwrap(), "a,b\n1,\n");
+
+        assert!(rows_to_csv(&serde_json::json!({"not": "rows"}), None).is_err());
+    }
+}
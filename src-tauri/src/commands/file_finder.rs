@@ -0,0 +1,419 @@
+// src-tauri/src/commands/file_finder.rs
+// Fuzzy file finder over node names and paths, ranked by match quality and recent access
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+use super::{ProjectData, utils::*};
+
+const DEFAULT_FINDER_LIMIT: usize = 50;
+const MAX_RECENT_FILES: usize = 500;
+const MATCH_SCORE: i64 = 16;
+const CONSECUTIVE_BONUS: i64 = 8;
+const GAP_PENALTY: i64 = 1;
+const MAX_LEADING_PENALTY: i64 = 10;
+const NAME_BONUS: i64 = 20; // A match in the name beats the same match in the path
+const EXACT_NAME_BONUS: i64 = 50;
+const NAME_PREFIX_BONUS: i64 = 25;
+const RECENCY_BONUS: f64 = 30.0; // Halves every day since the last access
+const NONE: i64 = i64::MIN / 2;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FinderMatch {
+    pub node_id: String,
+    pub project_id: String,
+    pub project_name: String,
+    pub client_id: Option<String>,
+    pub name: String,
+    pub file_path: Option<String>,
+    pub r#type: String,
+    pub score: i64,
+    pub matched_field: String, // "name" or "path"
+    pub positions: Vec<usize>, // Matched character indices in that field
+    pub last_accessed: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+struct FinderEntry {
+    node_id: String,
+    project_id: String,
+    project_name: String,
+    client_id: Option<String>,
+    name: String,
+    file_path: Option<String>,
+    r#type: String,
+}
+
+#[derive(Default)]
+struct FinderCache {
+    signature: Option<(u64, i64)>, // projects.json size and mtime the entries were built from
+    entries: Vec<FinderEntry>,
+}
+
+/// In-memory view of the node store, rebuilt whenever projects.json changes,
+/// plus the recently accessed files.
+#[derive(Default)]
+pub struct FileFinder {
+    cache: Mutex<FinderCache>,
+    recent: Mutex<Option<HashMap<String, i64>>>, // node id -> last access, Unix millis
+}
+
+// Bonus for matching at the start of a word: after a separator or at a camelCase hump
+fn boundary_bonus(chars: &[char], index: usize) -> i64 {
+    if index == 0 {
+        return 10;
+    }
+    let (previous, current) = (chars[index - 1], chars[index]);
+    match previous {
+        '/' | '\\' => 8,
+        '_' | '-' | '.' | ' ' => 7,
+        _ if previous.is_lowercase() && current.is_uppercase() => 6,
+        _ if !previous.is_alphanumeric() => 5,
+        _ => 0,
+    }
+}
+
+/// Best alignment of `query` (lowercased) as a subsequence of `candidate`,
+/// with the matched character positions. None when it is not a subsequence.
+fn fuzzy_match(query: &[char], candidate: &str) -> Option<(i64, Vec<usize>)> {
+    let chars: Vec<char> = candidate.chars().collect();
+    let lower: Vec<char> = chars.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect();
+    let (n, m) = (query.len(), chars.len());
+    if n == 0 || n > m {
+        return None;
+    }
+
+    // Cheap subsequence check before the full alignment
+    let mut remaining = query.iter().peekable();
+    for c in &lower {
+        if remaining.peek() == Some(&c) {
+            remaining.next();
+        }
+    }
+    if remaining.peek().is_some() {
+        return None;
+    }
+
+    // score[i][j]: best score with query[i] matched at candidate[j]
+    let mut score = vec![vec![NONE; m]; n];
+    let mut from = vec![vec![usize::MAX; m]; n];
+    for i in 0..n {
+        let mut best_gap = NONE; // max over k <= j - 2 of score[i - 1][k] + GAP_PENALTY * k
+        let mut best_gap_at = usize::MAX;
+        for j in 0..m {
+            if i > 0 && j >= 2 && score[i - 1][j - 2] > NONE {
+                let candidate = score[i - 1][j - 2] + GAP_PENALTY * (j - 2) as i64;
+                if candidate > best_gap {
+                    best_gap = candidate;
+                    best_gap_at = j - 2;
+                }
+            }
+            if lower[j] != query[i] {
+                continue;
+            }
+
+            let bonus = MATCH_SCORE + boundary_bonus(&chars, j);
+            if i == 0 {
+                score[0][j] = bonus - (j as i64).min(MAX_LEADING_PENALTY);
+                continue;
+            }
+            if j > 0 && score[i - 1][j - 1] > NONE {
+                score[i][j] = score[i - 1][j - 1] + bonus + CONSECUTIVE_BONUS;
+                from[i][j] = j - 1;
+            }
+            if best_gap > NONE {
+                let gapped = best_gap - GAP_PENALTY * (j - 1) as i64 + bonus;
+                if gapped > score[i][j] {
+                    score[i][j] = gapped;
+                    from[i][j] = best_gap_at;
+                }
+            }
+        }
+    }
+
+    let (mut j, best) = score[n - 1].iter().enumerate().max_by_key(|(_, score)| **score)?;
+    if *best <= NONE {
+        return None;
+    }
+    let best = *best;
+    let mut positions = vec![0; n];
+    for i in (0..n).rev() {
+        positions[i] = j;
+        j = from[i][j];
+    }
+    Some((best, positions))
+}
+
+fn recency_bonus(last_accessed: Option<i64>, now: i64) -> i64 {
+    match last_accessed {
+        Some(accessed) => {
+            let age_days = (now - accessed).max(0) as f64 / 86_400_000.0;
+            (RECENCY_BONUS * 0.5f64.powf(age_days)).round() as i64
+        }
+        None => 0,
+    }
+}
+
+fn score_entry(entry: &FinderEntry, query: &str, query_chars: &[char]) -> Option<(i64, &'static str, Vec<usize>)> {
+    let path = entry.file_path.as_deref().map(|path| path.replace('\\', "/"));
+
+    // Queries with a slash are about where the file lives
+    let path_match = path.as_deref().and_then(|path| fuzzy_match(query_chars, path));
+    if query.contains('/') {
+        return path_match.map(|(score, positions)| (score, "path", positions));
+    }
+
+    let name_match = fuzzy_match(query_chars, &entry.name).map(|(mut score, positions)| {
+        let name = entry.name.to_lowercase();
+        score += NAME_BONUS;
+        if name == query {
+            score += EXACT_NAME_BONUS;
+        } else if name.starts_with(query) {
+            score += NAME_PREFIX_BONUS;
+        }
+        (score, "name", positions)
+    });
+    let path_match = path_match.map(|(score, positions)| (score, "path", positions));
+
+    match (name_match, path_match) {
+        (Some(name), Some(path)) => Some(if path.0 > name.0 { path } else { name }),
+        (name, path) => name.or(path),
+    }
+}
+
+fn rank(
+    entries: &[FinderEntry],
+    query: &str,
+    client_id: Option<&str>,
+    include_folders: bool,
+    recent: &HashMap<String, i64>,
+    now: i64,
+) -> Vec<FinderMatch> {
+    let query = query.trim().to_lowercase();
+    let query_chars: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).collect();
+    let query: String = query_chars.iter().collect();
+    if query_chars.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches: Vec<FinderMatch> = entries.iter()
+        .filter(|entry| include_folders || entry.r#type == "file")
+        .filter(|entry| client_id.map_or(true, |client_id| entry.client_id.as_deref() == Some(client_id)))
+        .filter_map(|entry| {
+            let (score, field, positions) = score_entry(entry, &query, &query_chars)?;
+            let last_accessed = recent.get(&entry.node_id).cloned();
+            Some(FinderMatch {
+                node_id: entry.node_id.clone(),
+                project_id: entry.project_id.clone(),
+                project_name: entry.project_name.clone(),
+                client_id: entry.client_id.clone(),
+                name: entry.name.clone(),
+                file_path: entry.file_path.clone(),
+                r#type: entry.r#type.clone(),
+                score: score + recency_bonus(last_accessed, now),
+                matched_field: field.to_string(),
+                positions,
+                last_accessed,
+            })
+        })
+        .collect();
+
+    // Best score first; shorter paths win ties
+    matches.sort_by(|a, b| {
+        b.score.cmp(&a.score)
+            .then_with(|| a.file_path.as_ref().map(String::len).cmp(&b.file_path.as_ref().map(String::len)))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    matches
+}
+
+fn build_entries(data: &ProjectData) -> Vec<FinderEntry> {
+    let projects: HashMap<&str, (&str, Option<String>)> = data.projects.iter()
+        .map(|project| {
+            // Membership is recorded on both the project and the client
+            let client_id = project.client_id.clone().or_else(|| {
+                data.clients.iter()
+                    .find(|client| client.projects.contains(&project.id))
+                    .map(|client| client.id.clone())
+            });
+            (project.id.as_str(), (project.name.as_str(), client_id))
+        })
+        .collect();
+
+    data.nodes.iter()
+        .filter(|node| node.hidden != Some(true) && node.name != "__PROJECT_ROOT__")
+        .filter_map(|node| {
+            let (project_name, client_id) = projects.get(node.project_id.as_str())?;
+            Some(FinderEntry {
+                node_id: node.id.clone(),
+                project_id: node.project_id.clone(),
+                project_name: project_name.to_string(),
+                client_id: client_id.clone(),
+                name: node.name.clone(),
+                file_path: node.file_path.clone(),
+                r#type: node.r#type.clone(),
+            })
+        })
+        .collect()
+}
+
+fn file_signature(path: &Path) -> Option<(u64, i64)> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok()?
+        .duration_since(std::time::UNIX_EPOCH).ok()?
+        .as_nanos() as i64;
+    Some((metadata.len(), modified))
+}
+
+// Current entries, reloading the node store only when projects.json changed
+fn finder_entries(app: &AppHandle, finder: &FileFinder) -> Result<Vec<FinderEntry>, String> {
+    let signature = file_signature(&get_projects_file(app)?);
+    let mut cache = finder.cache.lock().unwrap();
+    if cache.signature.is_none() || cache.signature != signature {
+        let entries = build_entries(&load_project_data(app)?);
+        println!("🔍 FINDER: Indexed {} nodes", entries.len());
+        *cache = FinderCache { signature, entries };
+    }
+    Ok(cache.entries.clone())
+}
+
+fn recent_files_path(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    Ok(get_app_data_dir(app)?.join("recent_files.json"))
+}
+
+fn with_recent<R, F>(app: &AppHandle, finder: &FileFinder, f: F) -> Result<R, String>
+where
+    F: FnOnce(&mut HashMap<String, i64>) -> R,
+{
+    let mut recent = finder.recent.lock().unwrap();
+    if recent.is_none() {
+        let path = recent_files_path(app)?;
+        let loaded = fs::read_to_string(&path).ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        *recent = Some(loaded);
+    }
+    Ok(f(recent.as_mut().unwrap()))
+}
+
+/// Remembers that a node was opened, for ranking. Failures are only logged.
+pub(crate) fn record_node_access(app: &AppHandle, node_id: &str) {
+    let finder = app.state::<FileFinder>();
+    let result = with_recent(app, &finder, |recent| {
+        recent.insert(node_id.to_string(), chrono::Utc::now().timestamp_millis());
+        if recent.len() > MAX_RECENT_FILES {
+            let mut by_age: Vec<(String, i64)> = recent.iter().map(|(id, at)| (id.clone(), *at)).collect();
+            by_age.sort_by_key(|(_, at)| std::cmp::Reverse(*at));
+            *recent = by_age.into_iter().take(MAX_RECENT_FILES).collect();
+        }
+        serde_json::to_string(recent)
+    }).and_then(|json| {
+        let json = json.map_err(|e| format!("Failed to serialize recent files: {}", e))?;
+        let path = recent_files_path(app)?;
+        safe_file_operation(|| fs::write(&path, json), "Failed to write recent files")
+    });
+    if let Err(e) = result {
+        eprintln!("⚠️ FINDER: {}", e);
+    }
+}
+
+#[tauri::command]
+pub async fn find_files(
+    app: AppHandle,
+    finder: State<'_, FileFinder>,
+    query: String,
+    client_id: Option<String>,
+    include_folders: Option<bool>,
+    limit: Option<usize>,
+) -> Result<Vec<FinderMatch>, String> {
+    let entries = finder_entries(&app, &finder)?;
+    let recent = with_recent(&app, &finder, |recent| recent.clone())?;
+
+    let mut matches = rank(
+        &entries,
+        &query,
+        client_id.as_deref(),
+        include_folders.unwrap_or(false),
+        &recent,
+        chrono::Utc::now().timestamp_millis(),
+    );
+    matches.truncate(limit.unwrap_or(DEFAULT_FINDER_LIMIT));
+    Ok(matches)
+}
+
+#[tauri::command]
+pub async fn record_file_access(app: AppHandle, node_id: String) -> Result<(), String> {
+    record_node_access(&app, &node_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, client_id: Option<&str>, path: &str) -> FinderEntry {
+        FinderEntry {
+            node_id: id.to_string(),
+            project_id: "p1".to_string(),
+            project_name: "Project".to_string(),
+            client_id: client_id.map(str::to_string),
+            name: path.rsplit('/').next().unwrap().to_string(),
+            file_path: Some(path.to_string()),
+            r#type: "file".to_string(),
+        }
+    }
+
+    fn ids(matches: &[FinderMatch]) -> Vec<&str> {
+        matches.iter().map(|m| m.node_id.as_str()).collect()
+    }
+
+    #[test]
+    fn fuzzy_match_prefers_word_starts_and_runs() {
+        let query: Vec<char> = "cfg".chars().collect();
+        let (boundary, positions) = fuzzy_match(&query, "load_config_file.py").unwrap();
+        assert_eq!(positions, vec![5, 8, 10]);
+        let (scattered, _) = fuzzy_match(&query, "acrefagx").unwrap();
+        assert!(boundary > scattered);
+
+        let run: Vec<char> = "conf".chars().collect();
+        let (_, positions) = fuzzy_match(&run, "cxoxnxf/config.toml").unwrap();
+        assert_eq!(positions, vec![8, 9, 10, 11]);
+        assert!(fuzzy_match(&query, "figure").is_none());
+    }
+
+    #[test]
+    fn ranks_names_above_paths_and_filters_by_client() {
+        let entries = vec![
+            entry("deep", Some("c1"), "reports/soil/data/readme.md"),
+            entry("name", Some("c1"), "notes/soil_report.md"),
+            entry("exact", Some("c2"), "archive/soil.md"),
+        ];
+
+        let matches = rank(&entries, "soil", None, false, &HashMap::new(), 0);
+        assert_eq!(ids(&matches), vec!["exact", "name", "deep"]);
+        assert_eq!(matches[2].matched_field, "path");
+
+        let matches = rank(&entries, "soil", Some("c1"), false, &HashMap::new(), 0);
+        assert_eq!(ids(&matches), vec!["name", "deep"]);
+
+        // Slash queries match paths only
+        let matches = rank(&entries, "rep/soil", None, false, &HashMap::new(), 0);
+        assert_eq!(ids(&matches), vec!["deep"]);
+    }
+
+    #[test]
+    fn recent_access_breaks_ties_and_fades() {
+        let entries = vec![entry("a", None, "one/notes.md"), entry("b", None, "two/notes.md")];
+        let now = 10 * 86_400_000;
+
+        let recent: HashMap<String, i64> = vec![("b".to_string(), now - 3_600_000)].into_iter().collect();
+        assert_eq!(ids(&rank(&entries, "notes", None, false, &recent, now)), vec!["b", "a"]);
+
+        assert_eq!(recency_bonus(Some(now), now), 30);
+        assert_eq!(recency_bonus(Some(now - 86_400_000), now), 15);
+        assert_eq!(recency_bonus(None, now), 0);
+    }
+}
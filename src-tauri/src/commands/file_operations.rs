@@ -5,7 +5,7 @@ use std::fs;
 use std::path::PathBuf;
 use tauri::AppHandle;
 use uuid::Uuid;
-use super::{ProjectData, utils::*};
+use super::{ProjectData, search_index::{remove_search_index, update_search_index}, utils::*};
 
 // Helper function to build the full path based on node hierarchy
 fn build_node_path(
@@ -121,6 +121,7 @@ pub async fn create_file(
     )?;
     
     println!("✅ Created file: {:?}", file_path);
+    update_search_index(&app, &project_id, &[&file_path]);
     
     Ok(file_id)
 }
@@ -178,6 +179,7 @@ pub async fn rename_node(
             "Failed to rename file/folder"
         )?;
         
+        update_search_index(&app, &project_id, &[&old_path, &new_path]);
         println!("✅ Renamed successfully: {:?} -> {:?}", old_path, new_path);
     } else {
         println!("⚠️ Original file/folder not found, skipping rename: {:?}", old_path);
@@ -254,6 +256,7 @@ pub async fn delete_node(
         )?;
         println!("✅ FIXED: File deleted: {:?}", full_path);
     }
+    update_search_index(&app, &project_id, &[&full_path]);
 
     Ok(())
 }
@@ -278,5 +281,9 @@ pub async fn delete_project_directory(
         println!("⚠️ FIXED: Project directory not found: {:?}", project_dir);
     }
     
+    if let Err(e) = remove_search_index(&app, &project_id) {
+        eprintln!("⚠️ INDEX: {}", e);
+    }
+    
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,110 @@
+// src-tauri/src/commands/file_signatures.rs
+// Magic-number signatures for recognizing binary file types from their first bytes
+
+// Enough for every signature below, including tar's marker at byte 257
+pub(crate) const SIGNATURE_HEADER_BYTES: usize = 512;
+
+/// A file type recognized by its content.
+#[derive(Debug)]
+pub struct Signature {
+    pub mime: &'static str,
+    pub category: &'static str,
+    pub description: &'static str,
+    checks: &'static [(usize, &'static [u8])], // Bytes expected at each offset
+}
+
+macro_rules! signature {
+    ($mime:expr, $category:expr, $description:expr, [$(($offset:expr, $bytes:expr)),+]) => {
+        Signature { mime: $mime, category: $category, description: $description, checks: &[$(($offset, $bytes)),+] }
+    };
+}
+
+// More specific signatures come before the ones they share a prefix with
+static SIGNATURES: &[Signature] = &[
+    // Images
+    signature!("image/png", "image", "PNG Image", [(0, b"\x89PNG\r\n\x1a\n")]),
+    signature!("image/jpeg", "image", "JPEG Image", [(0, b"\xFF\xD8\xFF")]),
+    signature!("image/gif", "image", "GIF Image", [(0, b"GIF87a")]),
+    signature!("image/gif", "image", "GIF Image", [(0, b"GIF89a")]),
+    signature!("image/webp", "image", "WebP Image", [(0, b"RIFF"), (8, b"WEBP")]),
+    signature!("image/bmp", "image", "BMP Image", [(0, b"BM"), (6, b"\0\0\0\0")]),
+    signature!("image/tiff", "image", "TIFF Image", [(0, b"II*\0")]),
+    signature!("image/tiff", "image", "TIFF Image", [(0, b"MM\0*")]),
+    signature!("image/x-icon", "image", "Icon", [(0, b"\0\0\x01\0")]),
+    // Documents
+    signature!("application/pdf", "document", "PDF Document", [(0, b"%PDF-")]),
+    signature!("application/x-ole-storage", "document", "Microsoft Office Document (legacy)", [(0, b"\xD0\xCF\x11\xE0\xA1\xB1\x1A\xE1")]),
+    // Archives
+    signature!("application/zip", "archive", "ZIP Archive", [(0, b"PK\x03\x04")]),
+    signature!("application/zip", "archive", "ZIP Archive", [(0, b"PK\x05\x06")]),
+    signature!("application/gzip", "archive", "GZIP Archive", [(0, b"\x1F\x8B")]),
+    signature!("application/x-bzip2", "archive", "BZIP2 Archive", [(0, b"BZh")]),
+    signature!("application/x-xz", "archive", "XZ Archive", [(0, b"\xFD7zXZ\0")]),
+    signature!("application/zstd", "archive", "Zstandard Archive", [(0, b"\x28\xB5\x2F\xFD")]),
+    signature!("application/x-7z-compressed", "archive", "7-Zip Archive", [(0, b"7z\xBC\xAF\x27\x1C")]),
+    signature!("application/vnd.rar", "archive", "RAR Archive", [(0, b"Rar!\x1A\x07")]),
+    signature!("application/x-tar", "archive", "TAR Archive", [(257, b"ustar")]),
+    // Data
+    signature!("application/vnd.sqlite3", "data", "SQLite Database", [(0, b"SQLite format 3\0")]),
+    signature!("application/vnd.apache.parquet", "data", "Parquet Data", [(0, b"PAR1")]),
+    signature!("application/vnd.apache.arrow.file", "data", "Arrow Data", [(0, b"ARROW1")]),
+    signature!("application/x-hdf5", "data", "HDF5 Data", [(0, b"\x89HDF\r\n\x1a\n")]),
+    signature!("application/x-npy", "data", "NumPy Array", [(0, b"\x93NUMPY")]),
+    // Audio and video
+    signature!("audio/wav", "audio", "WAV Audio", [(0, b"RIFF"), (8, b"WAVE")]),
+    signature!("video/x-msvideo", "video", "AVI Video", [(0, b"RIFF"), (8, b"AVI ")]),
+    signature!("audio/mpeg", "audio", "MP3 Audio", [(0, b"ID3")]),
+    signature!("audio/flac", "audio", "FLAC Audio", [(0, b"fLaC")]),
+    signature!("audio/ogg", "audio", "Ogg Media", [(0, b"OggS")]),
+    signature!("audio/midi", "audio", "MIDI Audio", [(0, b"MThd")]),
+    signature!("video/mp4", "video", "MPEG-4 Media", [(4, b"ftyp")]),
+    signature!("video/x-matroska", "video", "Matroska Video", [(0, b"\x1A\x45\xDF\xA3")]),
+    // Fonts
+    signature!("font/woff", "font", "WOFF Font", [(0, b"wOFF")]),
+    signature!("font/woff2", "font", "WOFF2 Font", [(0, b"wOF2")]),
+    signature!("font/otf", "font", "OpenType Font", [(0, b"OTTO")]),
+    signature!("font/ttf", "font", "TrueType Font", [(0, b"\0\x01\0\0\0")]),
+    // Executables
+    signature!("application/x-executable", "executable", "ELF Executable", [(0, b"\x7FELF")]),
+    signature!("application/x-mach-binary", "executable", "Mach-O Executable", [(0, b"\xCF\xFA\xED\xFE")]),
+    signature!("application/vnd.microsoft.portable-executable", "executable", "Windows Executable", [(0, b"MZ")]),
+    signature!("application/wasm", "executable", "WebAssembly Module", [(0, b"\0asm")]),
+];
+
+impl Signature {
+    /// Bytes the signature checks; short ones can match text by chance.
+    pub(crate) fn check_length(&self) -> usize {
+        self.checks.iter().map(|(_, bytes)| bytes.len()).sum()
+    }
+}
+
+/// The signature matching the start of a file, if any.
+pub(crate) fn sniff_signature(header: &[u8]) -> Option<&'static Signature> {
+    SIGNATURES.iter().find(|signature| {
+        signature.checks.iter().all(|(offset, bytes)| {
+            header.get(*offset..offset + bytes.len()) == Some(*bytes)
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signatures_match_at_their_offsets() {
+        assert_eq!(sniff_signature(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap().mime, "image/png");
+        assert_eq!(sniff_signature(b"RIFF\x24\0\0\0WAVEfmt ").unwrap().description, "WAV Audio");
+        assert_eq!(sniff_signature(b"RIFF\x24\0\0\0WEBPVP8 ").unwrap().mime, "image/webp");
+        assert_eq!(sniff_signature(b"\0\0\0\x20ftypisom").unwrap().mime, "video/mp4");
+
+        let mut tar = vec![0u8; 300];
+        tar[257..262].copy_from_slice(b"ustar");
+        assert_eq!(sniff_signature(&tar).unwrap().mime, "application/x-tar");
+
+        // Text that happens to start like a weak signature is not matched
+        assert!(sniff_signature(b"BMI report\n").is_none());
+        assert!(sniff_signature(b"plain text").is_none());
+        assert!(sniff_signature(b"").is_none());
+    }
+}
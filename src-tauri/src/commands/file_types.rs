@@ -0,0 +1,427 @@
+// src-tauri/src/commands/file_types.rs
+// File type detection from magic bytes, text content and the extension, extensible through file_types.json
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use super::{
+    file_signatures::{sniff_signature, Signature, SIGNATURE_HEADER_BYTES},
+    text_encoding::decode_text,
+    utils::*,
+};
+
+// Bytes read to judge a file; enough for signatures and the text check
+const SNIFF_BYTES: usize = 8192;
+
+/// What a file is, whichever way that was found out.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileTypeInfo {
+    pub mime: String,
+    pub category: String, // "text", "code", "data", "document", "image", "audio", "video", "archive", "font", "executable" or "binary"
+    pub description: String,
+    pub is_binary: bool,
+    pub detected_by: String, // "config", "signature", "extension" or "content"
+}
+
+/// A file type declared in file_types.json. `binary` left out means the
+/// content decides, as for `.dat` files that may hold text or bytes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileTypeEntry {
+    pub mime: String,
+    pub category: String,
+    pub description: String,
+    #[serde(default)]
+    pub binary: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignatureEntry {
+    #[serde(default)]
+    pub offset: usize,
+    pub hex: String, // Bytes expected at the offset, e.g. "CAFEBABE"
+    #[serde(flatten)]
+    pub file_type: FileTypeEntry,
+}
+
+/// User additions to the built-in tables, read from `app_data/file_types.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileTypeConfig {
+    #[serde(default)]
+    pub extensions: HashMap<String, FileTypeEntry>, // Keyed by lowercase extension without the dot
+    #[serde(default)]
+    pub signatures: Vec<SignatureEntry>,
+}
+
+/// The loaded config, reloaded whenever the file changes.
+#[derive(Default)]
+pub struct FileTypes {
+    config: Mutex<Option<(Option<std::time::SystemTime>, Arc<FileTypeConfig>)>>,
+}
+
+#[derive(Clone, Copy)]
+enum Binary {
+    Yes,
+    No,
+    Sniff, // Decided by the content
+}
+
+// Extension, MIME type, category, description, binary
+static EXTENSIONS: &[(&str, &str, &str, &str, Binary)] = &[
+    // Documents
+    ("pdf", "application/pdf", "document", "PDF Document", Binary::Yes),
+    ("doc", "application/msword", "document", "Word Document", Binary::Yes),
+    ("docx", "application/vnd.openxmlformats-officedocument.wordprocessingml.document", "document", "Word Document", Binary::Yes),
+    ("xls", "application/vnd.ms-excel", "document", "Excel Spreadsheet", Binary::Yes),
+    ("xlsx", "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet", "document", "Excel Spreadsheet", Binary::Yes),
+    ("ppt", "application/vnd.ms-powerpoint", "document", "PowerPoint Presentation", Binary::Yes),
+    ("pptx", "application/vnd.openxmlformats-officedocument.presentationml.presentation", "document", "PowerPoint Presentation", Binary::Yes),
+    ("odt", "application/vnd.oasis.opendocument.text", "document", "OpenDocument Text", Binary::Yes),
+    ("ods", "application/vnd.oasis.opendocument.spreadsheet", "document", "OpenDocument Spreadsheet", Binary::Yes),
+    ("odp", "application/vnd.oasis.opendocument.presentation", "document", "OpenDocument Presentation", Binary::Yes),
+    ("epub", "application/epub+zip", "document", "EPUB Book", Binary::Yes),
+    ("rtf", "application/rtf", "document", "Rich Text Document", Binary::No),
+    // Images
+    ("jpg", "image/jpeg", "image", "JPEG Image", Binary::Yes),
+    ("jpeg", "image/jpeg", "image", "JPEG Image", Binary::Yes),
+    ("png", "image/png", "image", "PNG Image", Binary::Yes),
+    ("gif", "image/gif", "image", "GIF Image", Binary::Yes),
+    ("bmp", "image/bmp", "image", "BMP Image", Binary::Yes),
+    ("tiff", "image/tiff", "image", "TIFF Image", Binary::Yes),
+    ("tif", "image/tiff", "image", "TIFF Image", Binary::Yes),
+    ("ico", "image/x-icon", "image", "Icon", Binary::Yes),
+    ("webp", "image/webp", "image", "WebP Image", Binary::Yes),
+    ("heic", "image/heic", "image", "HEIC Image", Binary::Yes),
+    ("svg", "image/svg+xml", "image", "SVG Image", Binary::No),
+    // Audio
+    ("mp3", "audio/mpeg", "audio", "MP3 Audio", Binary::Yes),
+    ("wav", "audio/wav", "audio", "WAV Audio", Binary::Yes),
+    ("flac", "audio/flac", "audio", "FLAC Audio", Binary::Yes),
+    ("ogg", "audio/ogg", "audio", "Ogg Audio", Binary::Yes),
+    ("aac", "audio/aac", "audio", "AAC Audio", Binary::Yes),
+    ("m4a", "audio/mp4", "audio", "MPEG-4 Audio", Binary::Yes),
+    ("wma", "audio/x-ms-wma", "audio", "Windows Media Audio", Binary::Yes),
+    // Video
+    ("mp4", "video/mp4", "video", "MPEG-4 Video", Binary::Yes),
+    ("m4v", "video/mp4", "video", "MPEG-4 Video", Binary::Yes),
+    ("mov", "video/quicktime", "video", "QuickTime Video", Binary::Yes),
+    ("avi", "video/x-msvideo", "video", "AVI Video", Binary::Yes),
+    ("wmv", "video/x-ms-wmv", "video", "Windows Media Video", Binary::Yes),
+    ("flv", "video/x-flv", "video", "Flash Video", Binary::Yes),
+    ("webm", "video/webm", "video", "WebM Video", Binary::Yes),
+    ("mkv", "video/x-matroska", "video", "Matroska Video", Binary::Yes),
+    // Archives
+    ("zip", "application/zip", "archive", "ZIP Archive", Binary::Yes),
+    ("rar", "application/vnd.rar", "archive", "RAR Archive", Binary::Yes),
+    ("7z", "application/x-7z-compressed", "archive", "7-Zip Archive", Binary::Yes),
+    ("tar", "application/x-tar", "archive", "TAR Archive", Binary::Yes),
+    ("gz", "application/gzip", "archive", "GZIP Archive", Binary::Yes),
+    ("bz2", "application/x-bzip2", "archive", "BZIP2 Archive", Binary::Yes),
+    ("xz", "application/x-xz", "archive", "XZ Archive", Binary::Yes),
+    ("jar", "application/java-archive", "archive", "Java Archive", Binary::Yes),
+    ("whl", "application/zip", "archive", "Python Wheel", Binary::Yes),
+    // Executables
+    ("exe", "application/vnd.microsoft.portable-executable", "executable", "Windows Executable", Binary::Yes),
+    ("dll", "application/vnd.microsoft.portable-executable", "executable", "Windows Library", Binary::Yes),
+    ("so", "application/x-sharedlib", "executable", "Shared Library", Binary::Yes),
+    ("dylib", "application/x-mach-binary", "executable", "Dynamic Library", Binary::Yes),
+    ("bin", "application/octet-stream", "binary", "Binary File", Binary::Sniff),
+    // Data
+    ("csv", "text/csv", "data", "CSV Data", Binary::No),
+    ("tsv", "text/tab-separated-values", "data", "TSV Data", Binary::No),
+    ("json", "application/json", "data", "JSON Data", Binary::No),
+    ("jsonl", "application/jsonl", "data", "JSON Lines Data", Binary::No),
+    ("xml", "application/xml", "data", "XML Document", Binary::No),
+    ("yaml", "application/yaml", "data", "YAML Data", Binary::No),
+    ("yml", "application/yaml", "data", "YAML Data", Binary::No),
+    ("toml", "application/toml", "data", "TOML Data", Binary::No),
+    ("parquet", "application/vnd.apache.parquet", "data", "Parquet Data", Binary::Yes),
+    ("db", "application/vnd.sqlite3", "data", "Database File", Binary::Yes),
+    ("sqlite", "application/vnd.sqlite3", "data", "SQLite Database", Binary::Yes),
+    ("sqlite3", "application/vnd.sqlite3", "data", "SQLite Database", Binary::Yes),
+    ("npy", "application/x-npy", "data", "NumPy Array", Binary::Yes),
+    ("h5", "application/x-hdf5", "data", "HDF5 Data", Binary::Yes),
+    ("pkl", "application/x-pickle", "data", "Python Pickle", Binary::Yes),
+    ("dat", "application/octet-stream", "data", "Data File", Binary::Sniff),
+    // Code
+    ("py", "text/x-python", "code", "Python Script", Binary::No),
+    ("ipynb", "application/x-ipynb+json", "code", "Jupyter Notebook", Binary::No),
+    ("js", "text/javascript", "code", "JavaScript File", Binary::No),
+    ("jsx", "text/javascript", "code", "JavaScript File", Binary::No),
+    ("ts", "text/typescript", "code", "TypeScript File", Binary::No),
+    ("tsx", "text/typescript", "code", "TypeScript File", Binary::No),
+    ("html", "text/html", "code", "HTML File", Binary::No),
+    ("htm", "text/html", "code", "HTML File", Binary::No),
+    ("css", "text/css", "code", "CSS Stylesheet", Binary::No),
+    ("sh", "application/x-sh", "code", "Shell Script", Binary::No),
+    ("r", "text/x-r", "code", "R Script", Binary::No),
+    ("sql", "application/sql", "code", "SQL Script", Binary::No),
+    ("rs", "text/x-rust", "code", "Rust Source", Binary::No),
+    ("go", "text/x-go", "code", "Go Source", Binary::No),
+    ("java", "text/x-java", "code", "Java Source", Binary::No),
+    ("c", "text/x-c", "code", "C Source", Binary::No),
+    ("h", "text/x-c", "code", "C Header", Binary::No),
+    ("cpp", "text/x-c++", "code", "C++ Source", Binary::No),
+    // Text
+    ("txt", "text/plain", "text", "Text File", Binary::No),
+    ("md", "text/markdown", "text", "Markdown File", Binary::No),
+    ("log", "text/plain", "text", "Log File", Binary::No),
+    ("ini", "text/plain", "text", "Configuration File", Binary::No),
+    ("cfg", "text/plain", "text", "Configuration File", Binary::No),
+];
+
+// Formats built on a generic container keep the name their extension gives them
+const CONTAINER_MIMES: &[&str] = &["application/zip", "application/x-ole-storage", "video/mp4"];
+
+fn info(mime: &str, category: &str, description: &str, is_binary: bool, detected_by: &str) -> FileTypeInfo {
+    FileTypeInfo {
+        mime: mime.to_string(),
+        category: category.to_string(),
+        description: description.to_string(),
+        is_binary,
+        detected_by: detected_by.to_string(),
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<Vec<u8>> {
+    let digits: String = hex.chars().filter(|c| !c.is_whitespace()).collect();
+    if digits.is_empty() || digits.len() % 2 != 0 {
+        return None;
+    }
+    (0..digits.len()).step_by(2).map(|index| u8::from_str_radix(&digits[index..index + 2], 16).ok()).collect()
+}
+
+// A two or three byte signature in what reads as text is a coincidence, like "MZ" starting a note
+fn is_weak(signature: &Signature) -> bool {
+    signature.check_length() < 4
+}
+
+/// Recognizes common text formats by how they start.
+fn sniff_text(text: &str) -> Option<(&'static str, &'static str, &'static str)> {
+    let start = text.trim_start_matches('\u{feff}').trim_start();
+    let lower: String = start.chars().take(256).collect::<String>().to_lowercase();
+
+    if lower.starts_with("<svg") || (lower.starts_with("<?xml") && text.contains("<svg")) {
+        Some(("image/svg+xml", "image", "SVG Image"))
+    } else if lower.starts_with("<!doctype html") || lower.starts_with("<html") {
+        Some(("text/html", "code", "HTML File"))
+    } else if lower.starts_with("<?xml") {
+        Some(("application/xml", "data", "XML Document"))
+    } else if lower.starts_with("{\\rtf") {
+        Some(("application/rtf", "document", "Rich Text Document"))
+    } else if lower.starts_with("#!") {
+        let shebang = lower.lines().next().unwrap_or_default();
+        if shebang.contains("python") {
+            Some(("text/x-python", "code", "Python Script"))
+        } else if shebang.contains("node") {
+            Some(("text/javascript", "code", "JavaScript File"))
+        } else {
+            Some(("application/x-sh", "code", "Shell Script"))
+        }
+    } else if (start.starts_with('{') || start.starts_with('['))
+        && (serde_json::from_str::<serde_json::Value>(start).is_ok() || start.starts_with("{\""))
+    {
+        Some(("application/json", "data", "JSON Data"))
+    } else {
+        None
+    }
+}
+
+/// Detects the type of a file from its first bytes and its extension. The
+/// config is consulted before the built-in tables.
+pub(crate) fn detect_file_type_from(extension: Option<&str>, sample: &[u8], config: &FileTypeConfig) -> FileTypeInfo {
+    let extension = extension.map(str::to_lowercase);
+
+    // Signatures from the config win over everything
+    for entry in &config.signatures {
+        let matches = parse_hex(&entry.hex)
+            .map_or(false, |bytes| sample.get(entry.offset..entry.offset + bytes.len()) == Some(&bytes[..]));
+        if matches {
+            let file_type = &entry.file_type;
+            return info(&file_type.mime, &file_type.category, &file_type.description, file_type.binary.unwrap_or(true), "config");
+        }
+    }
+
+    let text = decode_text(sample).map(|(text, _)| text);
+    let configured = extension.as_ref().and_then(|extension| config.extensions.get(extension));
+    let builtin = extension.as_ref().and_then(|extension| EXTENSIONS.iter().find(|entry| entry.0 == extension.as_str()));
+
+    if let Some(signature) = sniff_signature(&sample[..sample.len().min(SIGNATURE_HEADER_BYTES)]) {
+        if !(is_weak(signature) && text.is_some()) {
+            let named_by_extension = CONTAINER_MIMES.contains(&signature.mime)
+                && (configured.is_some() || builtin.map_or(false, |entry| matches!(entry.4, Binary::Yes)));
+            if !named_by_extension {
+                return info(signature.mime, signature.category, signature.description, true, "signature");
+            }
+        }
+    }
+
+    if let Some(entry) = configured {
+        let is_binary = entry.binary.unwrap_or_else(|| text.is_none());
+        return info(&entry.mime, &entry.category, &entry.description, is_binary, "config");
+    }
+
+    if let Some(&(_, mime, category, description, binary)) = builtin {
+        let is_binary = match binary {
+            Binary::Yes => true,
+            // Text extensions holding bytes are still binary
+            Binary::No | Binary::Sniff => text.is_none(),
+        };
+        if let (Binary::Sniff, Some(text)) = (binary, &text) {
+            // A .dat that is really text says what kind of text
+            let (mime, category, _) = sniff_text(text).unwrap_or(("text/plain", category, description));
+            return info(mime, category, description, false, "content");
+        }
+        return info(mime, category, description, is_binary, "extension");
+    }
+
+    match text {
+        Some(text) => {
+            let (mime, category, description) = sniff_text(&text).unwrap_or(("text/plain", "text", "Text File"));
+            info(mime, category, description, false, "content")
+        }
+        None => match extension {
+            Some(extension) => info("application/octet-stream", "binary", &format!("{} File", extension.to_uppercase()), true, "content"),
+            None => info("application/octet-stream", "binary", "Binary File", true, "content"),
+        },
+    }
+}
+
+fn read_sample(path: &Path) -> Vec<u8> {
+    let mut sample = Vec::with_capacity(SNIFF_BYTES);
+    if let Ok(file) = File::open(path) {
+        let _ = file.take(SNIFF_BYTES as u64).read_to_end(&mut sample);
+    }
+    sample
+}
+
+/// Detects the type of a file on disk. A missing file is judged by its
+/// extension alone.
+pub(crate) fn detect_file_type(path: &Path, config: &FileTypeConfig) -> FileTypeInfo {
+    if path.is_dir() {
+        return info("inode/directory", "folder", "Folder", false, "content");
+    }
+    detect_file_type_from(get_file_extension(path).as_deref(), &read_sample(path), config)
+}
+
+fn config_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_app_data_dir(app)?.join("file_types.json"))
+}
+
+/// The config from `app_data/file_types.json`, or the built-ins alone when
+/// there is none. A broken file is reported and ignored.
+pub(crate) fn file_type_config(app: &AppHandle) -> Arc<FileTypeConfig> {
+    let path = match config_path(app) {
+        Ok(path) => path,
+        Err(_) => return Arc::new(FileTypeConfig::default()),
+    };
+    let modified = fs::metadata(&path).and_then(|metadata| metadata.modified()).ok();
+
+    let state = app.state::<FileTypes>();
+    let mut cached = state.config.lock().unwrap();
+    if let Some((loaded_at, config)) = cached.as_ref() {
+        if *loaded_at == modified {
+            return config.clone();
+        }
+    }
+
+    let config = match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str::<FileTypeConfig>(&content).unwrap_or_else(|e| {
+            eprintln!("⚠️ FILE TYPES: Ignoring invalid {:?}: {}", path, e);
+            FileTypeConfig::default()
+        }),
+        Err(_) => FileTypeConfig::default(),
+    };
+    let mut config = config;
+    config.extensions = config.extensions.into_iter()
+        .map(|(extension, entry)| (extension.trim_start_matches('.').to_lowercase(), entry))
+        .collect();
+
+    let config = Arc::new(config);
+    *cached = Some((modified, config.clone()));
+    config
+}
+
+/// Detects a file type with the app's config.
+pub(crate) fn detect_app_file_type(app: &AppHandle, path: &Path) -> FileTypeInfo {
+    detect_file_type(path, &file_type_config(app))
+}
+
+#[tauri::command]
+pub async fn get_file_type(
+    app: AppHandle,
+    project_id: String,
+    file_path: String,
+) -> Result<FileTypeInfo, String> {
+    let full_path = resolve_project_path(&get_project_dir(&app, &project_id)?, &file_path)?;
+    if !full_path.exists() {
+        return Err("File not found".to_string());
+    }
+    Ok(detect_app_file_type(&app, &full_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(extension: Option<&str>, sample: &[u8]) -> FileTypeInfo {
+        detect_file_type_from(extension, sample, &FileTypeConfig::default())
+    }
+
+    #[test]
+    fn content_decides_before_the_extension() {
+        let png = detect(None, b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR");
+        assert_eq!((png.mime.as_str(), png.category.as_str(), png.is_binary, png.detected_by.as_str()),
+            ("image/png", "image", true, "signature"));
+
+        // A PNG saved as .txt is still a PNG; a .docx stays a Word document
+        assert_eq!(detect(Some("txt"), b"\x89PNG\r\n\x1a\n\0\0").mime, "image/png");
+        assert_eq!(detect(Some("docx"), b"PK\x03\x04\x14\0\x06\0").description, "Word Document");
+        assert_eq!(detect(Some("zip"), b"PK\x03\x04\x14\0\x06\0").description, "ZIP Archive");
+
+        // Text starting like a weak signature stays text
+        assert_eq!(detect(Some("txt"), b"MZ notes\n").mime, "text/plain");
+    }
+
+    #[test]
+    fn svg_dat_and_extensionless_files_are_judged_by_content() {
+        let svg = detect(Some("svg"), b"<svg xmlns=\"http://www.w3.org/2000/svg\"></svg>");
+        assert_eq!((svg.mime.as_str(), svg.is_binary), ("image/svg+xml", false));
+
+        let text_dat = detect(Some("dat"), b"time,value\n0,1.5\n");
+        assert_eq!((text_dat.description.as_str(), text_dat.is_binary), ("Data File", false));
+        let binary_dat = detect(Some("DAT"), b"\x01\x02\0\0\x7f");
+        assert_eq!((binary_dat.mime.as_str(), binary_dat.is_binary), ("application/octet-stream", true));
+
+        assert_eq!(detect(None, b"#!/usr/bin/env python3\nprint(1)\n").description, "Python Script");
+        assert_eq!(detect(None, b"{\"name\": \"run\"}").mime, "application/json");
+        assert_eq!(detect(None, b"<?xml version=\"1.0\"?>\n<svg/>").mime, "image/svg+xml");
+        assert_eq!(detect(None, b"Makefile rules\n").description, "Text File");
+        assert_eq!(detect(None, b"\0\0\0\x01\x02\x03\0\0\xff\xfe").description, "Binary File");
+        assert!(detect(Some("png"), b"").is_binary);
+        assert!(!detect(Some("csv"), b"").is_binary);
+    }
+
+    #[test]
+    fn config_adds_extensions_and_signatures() {
+        let config: FileTypeConfig = serde_json::from_str(r#"{
+            "extensions": {
+                "fcs": { "mime": "application/vnd.isac.fcs", "category": "data", "description": "Flow Cytometry Data", "binary": true },
+                "txt": { "mime": "text/plain", "category": "text", "description": "Lab Notes" }
+            },
+            "signatures": [
+                { "offset": 0, "hex": "CA FE BA BE", "mime": "application/java-vm", "category": "executable", "description": "Java Class" }
+            ]
+        }"#).unwrap();
+
+        let fcs = detect_file_type_from(Some("fcs"), b"FCS3.0    ", &config);
+        assert_eq!((fcs.description.as_str(), fcs.is_binary, fcs.detected_by.as_str()), ("Flow Cytometry Data", true, "config"));
+        let notes = detect_file_type_from(Some("txt"), b"plain", &config);
+        assert_eq!((notes.description.as_str(), notes.is_binary), ("Lab Notes", false));
+        let class = detect_file_type_from(Some("class"), b"\xCA\xFE\xBA\xBE\0\0\0\x34", &config);
+        assert_eq!((class.mime.as_str(), class.is_binary), ("application/java-vm", true));
+    }
+}
@@ -0,0 +1,289 @@
+// src-tauri/src/commands/hex_viewer.rs
+// Hex and ASCII dumps and byte-pattern search for inspecting binary files
+
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use super::{
+    content_management::resolve_file_path,
+    file_types::{detect_app_file_type, FileTypeInfo},
+    utils::*,
+};
+
+const DEFAULT_DUMP_BYTES: u64 = 4096;
+const MAX_DUMP_BYTES: u64 = 1024 * 1024;
+const DEFAULT_BYTES_PER_ROW: usize = 16;
+const MAX_BYTES_PER_ROW: usize = 64;
+const DEFAULT_MAX_MATCHES: usize = 1000;
+const SEARCH_BLOCK_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+pub struct HexDumpRequest {
+    pub project_id: String,
+    pub node_id: Option<String>,
+    pub file_path: String,
+    pub offset: Option<u64>,
+    pub length: Option<u64>, // Capped at MAX_DUMP_BYTES
+    pub bytes_per_row: Option<usize>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct HexRow {
+    pub offset: u64,
+    pub hex: String, // "89 50 4e 47 ..."
+    pub ascii: String, // Printable ASCII, "." for everything else
+}
+
+#[derive(Debug, Serialize)]
+pub struct HexDump {
+    pub offset: u64,
+    pub length: u64,
+    pub total_size: u64,
+    pub bytes_per_row: usize,
+    pub rows: Vec<HexRow>,
+    pub dump: String, // The rows formatted like `hexdump -C`
+    pub file_type: FileTypeInfo, // From the magic number where there is one
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ByteSearchRequest {
+    pub project_id: String,
+    pub node_id: Option<String>,
+    pub file_path: String,
+    pub pattern: String,
+    pub pattern_type: Option<String>, // "hex" (default, "??" matches any byte) or "text"
+    pub case_sensitive: Option<bool>, // Text patterns only; defaults to true
+    pub start_offset: Option<u64>,
+    pub max_results: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ByteSearchResult {
+    pub offsets: Vec<u64>,
+    pub pattern_length: usize,
+    pub truncated: bool,
+    pub bytes_searched: u64,
+}
+
+/// Parses "89 50 4E 47", "0x8950" or "ff ?? 00" into bytes, with None for
+/// wildcards.
+fn parse_hex_pattern(pattern: &str) -> Result<Vec<Option<u8>>, String> {
+    let digits: String = pattern.split_whitespace()
+        .map(|group| group.trim_start_matches("0x").trim_start_matches("0X"))
+        .collect();
+    if digits.is_empty() || digits.len() % 2 != 0 {
+        return Err(format!("Hex pattern must have an even number of digits: {}", pattern));
+    }
+
+    digits.as_bytes().chunks(2).map(|pair| {
+        let pair = std::str::from_utf8(pair).unwrap_or_default();
+        if pair == "??" {
+            return Ok(None);
+        }
+        u8::from_str_radix(pair, 16)
+            .map(Some)
+            .map_err(|_| format!("Invalid hex byte '{}' in pattern", pair))
+    }).collect()
+}
+
+fn format_rows(bytes: &[u8], start: u64, bytes_per_row: usize) -> Vec<HexRow> {
+    bytes.chunks(bytes_per_row).enumerate().map(|(index, row)| HexRow {
+        offset: start + (index * bytes_per_row) as u64,
+        hex: row.iter().map(|byte| format!("{:02x}", byte)).collect::<Vec<_>>().join(" "),
+        ascii: row.iter().map(|byte| if byte.is_ascii_graphic() || *byte == b' ' { *byte as char } else { '.' }).collect(),
+    }).collect()
+}
+
+// Offset column, hex bytes in groups of eight, then the ASCII column
+fn format_dump(rows: &[HexRow], bytes_per_row: usize, total_size: u64) -> String {
+    let offset_width = format!("{:x}", total_size).len().max(8);
+    let hex_width = bytes_per_row * 3 + (bytes_per_row - 1) / 8;
+
+    rows.iter().map(|row| {
+        let mut hex = String::new();
+        for (index, byte) in row.hex.split(' ').enumerate() {
+            if index > 0 {
+                hex.push_str(if index % 8 == 0 { "  " } else { " " });
+            }
+            hex.push_str(byte);
+        }
+        format!("{:0width$x}  {:<hex_width$}  |{}|\n", row.offset, hex, row.ascii, width = offset_width, hex_width = hex_width - 1)
+    }).collect()
+}
+
+fn hex_dump(path: &Path, offset: u64, length: u64, bytes_per_row: usize, file_type: FileTypeInfo) -> Result<HexDump, String> {
+    let mut file = safe_file_operation(|| File::open(path), "Failed to open file")?;
+    let total_size = safe_file_operation(|| file.metadata(), "Failed to get file metadata")?.len();
+    let start = offset.min(total_size);
+    let end = start.saturating_add(length.min(MAX_DUMP_BYTES)).min(total_size);
+
+    let mut bytes = vec![0u8; (end - start) as usize];
+    file.seek(SeekFrom::Start(start))
+        .and_then(|_| file.read_exact(&mut bytes))
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+
+    let rows = format_rows(&bytes, start, bytes_per_row);
+    Ok(HexDump {
+        offset: start,
+        length: bytes.len() as u64,
+        total_size,
+        bytes_per_row,
+        dump: format_dump(&rows, bytes_per_row, total_size),
+        rows,
+        file_type,
+    })
+}
+
+/// Offsets of every occurrence of `pattern`, read block by block so files of
+/// any size can be searched. Matches may overlap.
+fn search_bytes<R: Read>(
+    mut reader: R,
+    start_offset: u64,
+    pattern: &[Option<u8>],
+    ignore_case: bool,
+    max_results: usize,
+    block_size: usize,
+) -> Result<ByteSearchResult, String> {
+    let fold = |byte: u8| if ignore_case { byte.to_ascii_lowercase() } else { byte };
+    let pattern: Vec<Option<u8>> = pattern.iter().map(|byte| byte.map(fold)).collect();
+
+    let mut offsets = Vec::new();
+    let mut window: Vec<u8> = Vec::new();
+    let mut window_start = start_offset; // File offset of window[0]
+    let mut block = vec![0u8; block_size];
+    let mut bytes_searched = 0;
+
+    loop {
+        let read = reader.read(&mut block).map_err(|e| format!("Failed to read file: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        bytes_searched += read as u64;
+        window.extend_from_slice(&block[..read]);
+
+        // The tail shorter than the pattern carries over, so matches across blocks are found once
+        if window.len() >= pattern.len() {
+            for position in 0..=window.len() - pattern.len() {
+                let matches = pattern.iter().enumerate().all(|(index, expected)| {
+                    expected.map_or(true, |expected| fold(window[position + index]) == expected)
+                });
+                if matches {
+                    if offsets.len() == max_results {
+                        return Ok(ByteSearchResult { offsets, pattern_length: pattern.len(), truncated: true, bytes_searched });
+                    }
+                    offsets.push(window_start + position as u64);
+                }
+            }
+            let keep = pattern.len() - 1;
+            window_start += (window.len() - keep) as u64;
+            window.drain(..window.len() - keep);
+        }
+    }
+
+    Ok(ByteSearchResult { offsets, pattern_length: pattern.len(), truncated: false, bytes_searched })
+}
+
+#[tauri::command]
+pub async fn get_hex_dump(
+    app: AppHandle,
+    request: HexDumpRequest,
+) -> Result<HexDump, String> {
+    let full_path = resolve_file_path(&app, request.node_id.as_deref().unwrap_or(""), &request.file_path, &request.project_id)?;
+    if !full_path.is_file() {
+        return Err("File not found".to_string());
+    }
+    let bytes_per_row = request.bytes_per_row.unwrap_or(DEFAULT_BYTES_PER_ROW).clamp(1, MAX_BYTES_PER_ROW);
+
+    let file_type = detect_app_file_type(&app, &full_path);
+    hex_dump(&full_path, request.offset.unwrap_or(0), request.length.unwrap_or(DEFAULT_DUMP_BYTES), bytes_per_row, file_type)
+}
+
+#[tauri::command]
+pub async fn search_file_bytes(
+    app: AppHandle,
+    request: ByteSearchRequest,
+) -> Result<ByteSearchResult, String> {
+    let full_path = resolve_file_path(&app, request.node_id.as_deref().unwrap_or(""), &request.file_path, &request.project_id)?;
+    if !full_path.is_file() {
+        return Err("File not found".to_string());
+    }
+
+    let (pattern, ignore_case) = match request.pattern_type.as_deref().unwrap_or("hex") {
+        "hex" => (parse_hex_pattern(&request.pattern)?, false),
+        "text" => (request.pattern.bytes().map(Some).collect(), !request.case_sensitive.unwrap_or(true)),
+        other => return Err(format!("Unknown pattern type: {}", other)),
+    };
+    if pattern.is_empty() {
+        return Err("Search pattern cannot be empty".to_string());
+    }
+
+    let start_offset = request.start_offset.unwrap_or(0);
+    let max_results = request.max_results.unwrap_or(DEFAULT_MAX_MATCHES).max(1);
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut file = safe_file_operation(|| fs::File::open(&full_path), "Failed to open file")?;
+        file.seek(SeekFrom::Start(start_offset)).map_err(|e| format!("Failed to seek: {}", e))?;
+        let result = search_bytes(file, start_offset, &pattern, ignore_case, max_results, SEARCH_BLOCK_BYTES)?;
+        println!("🔎 Byte search in {:?}: {} matches", full_path, result.offsets.len());
+        Ok(result)
+    })
+    .await
+    .map_err(|e| format!("Byte search failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::file_types::{detect_file_type, FileTypeConfig};
+    use std::io::Cursor;
+
+    #[test]
+    fn dump_formats_offsets_hex_and_ascii() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("image.png");
+        let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        bytes.extend_from_slice(b"data!");
+        fs::write(&path, &bytes).unwrap();
+
+        let file_type = detect_file_type(&path, &FileTypeConfig::default());
+        let dump = hex_dump(&path, 0, 100, 16, file_type.clone()).unwrap();
+        assert_eq!(dump.rows.len(), 2);
+        assert_eq!(dump.rows[1], HexRow { offset: 16, hex: "64 61 74 61 21".to_string(), ascii: "data!".to_string() });
+        assert_eq!(dump.dump.lines().next().unwrap(),
+            "00000000  89 50 4e 47 0d 0a 1a 0a  00 00 00 0d 49 48 44 52  |.PNG........IHDR|");
+        assert_eq!(dump.dump.lines().nth(1).unwrap(),
+            "00000010  64 61 74 61 21                                    |data!|");
+        assert_eq!(dump.file_type.mime, "image/png");
+
+        let tail = hex_dump(&path, 18, 100, 4, file_type).unwrap();
+        assert_eq!((tail.offset, tail.length, tail.rows[0].ascii.as_str()), (18, 3, "ta!"));
+    }
+
+    #[test]
+    fn hex_patterns_parse_with_wildcards() {
+        assert_eq!(parse_hex_pattern("89 50 4e 47").unwrap(), vec![Some(0x89), Some(0x50), Some(0x4E), Some(0x47)]);
+        assert_eq!(parse_hex_pattern("0xFF?? 00").unwrap(), vec![Some(0xFF), None, Some(0)]);
+        assert!(parse_hex_pattern("abc").is_err());
+        assert!(parse_hex_pattern("zz").is_err());
+    }
+
+    #[test]
+    fn search_finds_matches_across_blocks() {
+        let data = b"..IEND....iend..IEND";
+        let pattern: Vec<Option<u8>> = b"IEND".iter().copied().map(Some).collect();
+
+        // Blocks of 3 split every occurrence
+        let found = search_bytes(Cursor::new(data), 100, &pattern, false, 10, 3).unwrap();
+        assert_eq!((found.offsets, found.bytes_searched), (vec![102, 116], 20));
+
+        let ignoring_case = search_bytes(Cursor::new(data), 0, &pattern, true, 10, 3).unwrap();
+        assert_eq!(ignoring_case.offsets, vec![2, 10, 16]);
+
+        let limited = search_bytes(Cursor::new(data), 0, &pattern, true, 2, 7).unwrap();
+        assert_eq!((limited.offsets, limited.truncated), (vec![2, 10], true));
+
+        let wildcard = search_bytes(Cursor::new(b"aXb aYb"), 0, &[Some(b'a'), None, Some(b'b')], false, 10, 2).unwrap();
+        assert_eq!(wildcard.offsets, vec![0, 4]);
+    }
+}
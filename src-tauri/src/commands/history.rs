@@ -0,0 +1,360 @@
+// src-tauri/src/commands/history.rs
+// Persistent execution history stored as JSON lines under logs/
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use uuid::Uuid;
+use super::{
+    ExecutionResult,
+    execution::{execute_and_record, ExecutionRequest, ScriptRun},
+    sessions::SessionExecutionResult,
+    utils::*,
+};
+
+// Output is stored from the end, where tracebacks and summaries live
+const MAX_STORED_OUTPUT: usize = 32 * 1024;
+const DEFAULT_HISTORY_LIMIT: usize = 200;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExecutionRecord {
+    pub id: String,
+    pub node_id: Option<String>,
+    pub project_id: String,
+    pub file_path: String,
+    pub interpreter: Option<String>,
+    pub args: Vec<String>,
+    pub trigger: String,
+    pub started_at: i64, // Unix time in milliseconds
+    pub duration_ms: u64,
+    pub exit_code: Option<i32>,
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+    pub stdout_truncated: bool,
+    pub stderr_truncated: bool,
+    pub error: Option<String>,
+    #[serde(default)]
+    pub created_files: Vec<String>,
+    #[serde(default)]
+    pub modified_files: Vec<String>,
+    #[serde(default)]
+    pub files_overlapped: bool, // The file lists may include files another run wrote meanwhile
+    #[serde(default)]
+    pub database: Option<String>,
+    #[serde(default = "default_kind")]
+    pub kind: String, // "script", "session" (code run in a Python session) or "package" (an install)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>, // Session code, which has no file to re-run
+}
+
+fn default_kind() -> String {
+    "script".to_string()
+}
+
+/// Serializes writers of the history file (queue jobs record in parallel).
+#[derive(Default)]
+pub struct ExecutionHistory {
+    write_lock: Mutex<()>,
+}
+
+fn truncate_output(text: &str) -> (String, bool) {
+    if text.len() <= MAX_STORED_OUTPUT {
+        return (text.to_string(), false);
+    }
+
+    let mut start = text.len() - MAX_STORED_OUTPUT;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    (format!("[... {} bytes truncated ...]\n{}", start, &text[start..]), true)
+}
+
+fn read_records(app: &AppHandle) -> Result<Vec<ExecutionRecord>, String> {
+    let history_file = get_execution_history_file(app)?;
+    if !history_file.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = safe_file_operation(
+        || fs::read_to_string(&history_file),
+        "Failed to read execution history"
+    )?;
+
+    // A partially written last line must not hide the rest of the history
+    Ok(content.lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+fn write_records(app: &AppHandle, records: &[ExecutionRecord]) -> Result<(), String> {
+    let history_file = get_execution_history_file(app)?;
+    ensure_parent_dir(&history_file)
+        .map_err(|e| format!("Failed to create logs directory: {}", e))?;
+
+    let mut content = String::new();
+    for record in records {
+        let line = serde_json::to_string(record)
+            .map_err(|e| format!("Failed to serialize execution record: {}", e))?;
+        content.push_str(&line);
+        content.push('\n');
+    }
+
+    let temp_file = history_file.with_extension("jsonl.tmp");
+    safe_file_operation(
+        || fs::write(&temp_file, content),
+        "Failed to write execution history"
+    )?;
+    safe_file_operation(
+        || fs::rename(&temp_file, &history_file),
+        "Failed to replace execution history"
+    )
+}
+
+fn append_record(app: &AppHandle, record: &ExecutionRecord) -> Result<(), String> {
+    let history_file = get_execution_history_file(app)?;
+    ensure_parent_dir(&history_file)
+        .map_err(|e| format!("Failed to create logs directory: {}", e))?;
+
+    let mut line = serde_json::to_string(record)
+        .map_err(|e| format!("Failed to serialize execution record: {}", e))?;
+    line.push('\n');
+
+    safe_file_operation(
+        || OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&history_file)
+            .and_then(|mut file| file.write_all(line.as_bytes())),
+        "Failed to append execution history"
+    )
+}
+
+/// Stores the outcome of a run. Failures are logged rather than returned so a
+/// broken history file never turns a successful run into an error.
+pub(crate) fn record_execution(
+    app: &AppHandle,
+    record_id: &str,
+    request: &ExecutionRequest,
+    started_at: i64,
+    outcome: &Result<ScriptRun, String>,
+    created_files: &[String],
+    modified_files: &[String],
+) {
+    let (interpreter, args, result, error) = match outcome {
+        Ok(run) => (Some(run.interpreter.clone()), run.args.clone(), Some(&run.result), None),
+        Err(e) => (None, Vec::new(), None, Some(e.clone())),
+    };
+
+    let (stdout, stdout_truncated) = truncate_output(result.map(|r| r.stdout.as_str()).unwrap_or(""));
+    let (stderr, stderr_truncated) = truncate_output(result.map(|r| r.stderr.as_str()).unwrap_or(""));
+
+    let record = ExecutionRecord {
+        id: record_id.to_string(),
+        node_id: request.node_id.clone(),
+        project_id: request.project_id.clone(),
+        file_path: request.file_path.clone(),
+        interpreter,
+        args,
+        trigger: request.trigger.clone(),
+        started_at,
+        duration_ms: result
+            .map(|r| r.duration_ms)
+            .unwrap_or_else(|| (chrono::Utc::now().timestamp_millis() - started_at).max(0) as u64),
+        exit_code: result.and_then(|r| r.exit_code),
+        success: result.map(|r| r.success).unwrap_or(false),
+        stdout,
+        stderr,
+        stdout_truncated,
+        stderr_truncated,
+        error,
+        created_files: created_files.to_vec(),
+        modified_files: modified_files.to_vec(),
+        files_overlapped: result.map_or(false, |r| r.files_overlapped),
+        database: request.database.clone(),
+        kind: default_kind(),
+        code: None,
+    };
+    store_record(app, &record);
+}
+
+/// Records code run in a persistent session; `file_path` is the notebook for
+/// cells and empty for code sent to the session directly.
+pub(crate) fn record_session_execution(
+    app: &AppHandle,
+    project_id: &str,
+    file_path: &str,
+    interpreter: Option<String>,
+    code: &str,
+    started_at: i64,
+    outcome: &Result<SessionExecutionResult, String>,
+) {
+    let result = outcome.as_ref().ok();
+    let (stdout, stdout_truncated) = truncate_output(result.map(|r| r.stdout.as_str()).unwrap_or(""));
+    let (stderr, stderr_truncated) = truncate_output(result.map(|r| r.stderr.as_str()).unwrap_or(""));
+    let error = match outcome {
+        Ok(result) => result.error.clone(),
+        Err(e) => Some(e.clone()),
+    };
+
+    store_record(app, &ExecutionRecord {
+        id: result.map(|r| r.execution_id.clone()).unwrap_or_else(|| Uuid::new_v4().to_string()),
+        node_id: None,
+        project_id: project_id.to_string(),
+        file_path: file_path.to_string(),
+        interpreter,
+        args: Vec::new(),
+        trigger: "manual".to_string(),
+        started_at,
+        duration_ms: result
+            .map(|r| r.duration_ms)
+            .unwrap_or_else(|| (chrono::Utc::now().timestamp_millis() - started_at).max(0) as u64),
+        exit_code: None,
+        success: result.map(|r| r.success).unwrap_or(false),
+        stdout,
+        stderr,
+        stdout_truncated,
+        stderr_truncated,
+        error,
+        created_files: Vec::new(),
+        modified_files: Vec::new(),
+        files_overlapped: false,
+        database: None,
+        kind: "session".to_string(),
+        code: Some(truncate_output(code).0),
+    });
+}
+
+/// Records a package install; `project_id` is empty when it wasn't made for a project.
+pub(crate) fn record_package_install(
+    app: &AppHandle,
+    project_id: &str,
+    installer: &str,
+    package_name: &str,
+    started_at: i64,
+    outcome: &Result<ExecutionResult, String>,
+) {
+    let result = outcome.as_ref().ok();
+    let (stdout, stdout_truncated) = truncate_output(result.map(|r| r.stdout.as_str()).unwrap_or(""));
+    let (stderr, stderr_truncated) = truncate_output(result.map(|r| r.stderr.as_str()).unwrap_or(""));
+
+    store_record(app, &ExecutionRecord {
+        id: Uuid::new_v4().to_string(),
+        node_id: None,
+        project_id: project_id.to_string(),
+        file_path: String::new(),
+        interpreter: Some(installer.to_string()),
+        args: vec!["install".to_string(), package_name.to_string()],
+        trigger: "manual".to_string(),
+        started_at,
+        duration_ms: result
+            .map(|r| r.duration_ms)
+            .unwrap_or_else(|| (chrono::Utc::now().timestamp_millis() - started_at).max(0) as u64),
+        exit_code: result.and_then(|r| r.exit_code),
+        success: result.map(|r| r.success).unwrap_or(false),
+        stdout,
+        stderr,
+        stdout_truncated,
+        stderr_truncated,
+        error: outcome.as_ref().err().cloned(),
+        created_files: Vec::new(),
+        modified_files: Vec::new(),
+        files_overlapped: false,
+        database: None,
+        kind: "package".to_string(),
+        code: None,
+    });
+}
+
+fn store_record(app: &AppHandle, record: &ExecutionRecord) {
+    let history = app.state::<ExecutionHistory>();
+    let _guard = history.write_lock.lock().unwrap();
+    if let Err(e) = append_record(app, record) {
+        eprintln!("⚠️ HISTORY: {}", e);
+    }
+}
+
+#[tauri::command]
+pub async fn get_execution_history(
+    app: AppHandle,
+    project_id: Option<String>,
+    file_path: Option<String>,
+    node_id: Option<String>,
+    success: Option<bool>,
+    limit: Option<usize>,
+) -> Result<Vec<ExecutionRecord>, String> {
+    let records = read_records(&app)?;
+    let file_path = file_path.map(|path| path.replace('\\', "/"));
+
+    let matches: Vec<ExecutionRecord> = records.into_iter()
+        .rev()
+        .filter(|record| project_id.as_ref().map_or(true, |id| &record.project_id == id))
+        .filter(|record| file_path.as_ref().map_or(true, |path| record.file_path.replace('\\', "/") == *path))
+        .filter(|record| node_id.as_ref().map_or(true, |id| record.node_id.as_ref() == Some(id)))
+        .filter(|record| success.map_or(true, |success| record.success == success))
+        .take(limit.unwrap_or(DEFAULT_HISTORY_LIMIT))
+        .collect();
+
+    Ok(matches)
+}
+
+#[tauri::command]
+pub async fn rerun_execution(
+    app: AppHandle,
+    record_id: String,
+) -> Result<ExecutionResult, String> {
+    let record = read_records(&app)?
+        .into_iter()
+        .find(|record| record.id == record_id)
+        .ok_or_else(|| "Execution record not found".to_string())?;
+    if record.kind != "script" {
+        return Err("Only script runs can be re-run from the history".to_string());
+    }
+
+    println!("🔁 HISTORY: Re-running {} from project {}", record.file_path, record.project_id);
+
+    execute_and_record(&app, ExecutionRequest {
+        node_id: record.node_id,
+        project_id: record.project_id,
+        file_path: record.file_path,
+        trigger: "rerun".to_string(),
+        database: record.database,
+    }).await
+}
+
+// Removes records older than `older_than_days` and/or beyond the newest `keep_last`
+#[tauri::command]
+pub async fn prune_execution_history(
+    app: AppHandle,
+    history: tauri::State<'_, ExecutionHistory>,
+    older_than_days: Option<u32>,
+    keep_last: Option<usize>,
+) -> Result<usize, String> {
+    if older_than_days.is_none() && keep_last.is_none() {
+        return Err("Specify older_than_days or keep_last".to_string());
+    }
+
+    let _guard = history.write_lock.lock().unwrap();
+    let mut records = read_records(&app)?;
+    let before = records.len();
+
+    if let Some(days) = older_than_days {
+        let cutoff = chrono::Utc::now().timestamp_millis() - i64::from(days) * 24 * 60 * 60 * 1000;
+        records.retain(|record| record.started_at >= cutoff);
+    }
+
+    if let Some(keep) = keep_last {
+        if records.len() > keep {
+            records.drain(..records.len() - keep);
+        }
+    }
+
+    let removed = before - records.len();
+    write_records(&app, &records)?;
+
+    println!("🧹 HISTORY: Pruned {} execution records ({} kept)", removed, records.len());
+    Ok(removed)
+}
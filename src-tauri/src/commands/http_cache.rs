@@ -0,0 +1,386 @@
+// src-tauri/src/commands/http_cache.rs
+// Opt-in on-disk HTTP response cache with TTL and an offline replay mode
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+use super::{utils::*, web_scraping::{perform_request, HttpRequest, HttpResponse}};
+
+const CACHE_DIR_NAME: &str = "cache";
+const SETTINGS_FILE_NAME: &str = "cache_settings.json";
+const DEFAULT_TTL_SECS: u64 = 3600;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpCacheSettings {
+    pub enabled: bool, // Store and reuse successful GET and HEAD responses
+    pub ttl_seconds: u64,
+    pub offline: bool, // Serve only from the cache, whatever the entries' age
+}
+
+impl Default for HttpCacheSettings {
+    fn default() -> Self {
+        HttpCacheSettings {
+            enabled: false,
+            ttl_seconds: DEFAULT_TTL_SECS,
+            offline: false,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct HttpCacheStatus {
+    pub settings: HttpCacheSettings,
+    pub entries: usize,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    method: String,
+    url: String,
+    stored_at: i64, // Unix seconds
+    response: HttpResponse,
+}
+
+fn get_cache_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_http_dir(app)?.join(CACHE_DIR_NAME))
+}
+
+fn get_settings_file(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_http_dir(app)?.join(SETTINGS_FILE_NAME))
+}
+
+fn load_cache_settings(app: &AppHandle) -> Result<HttpCacheSettings, String> {
+    let settings_file = get_settings_file(app)?;
+    if !settings_file.exists() {
+        return Ok(HttpCacheSettings::default());
+    }
+
+    let content = safe_file_operation(
+        || fs::read_to_string(&settings_file),
+        "Failed to read HTTP cache settings"
+    )?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse HTTP cache settings: {}", e))
+}
+
+/// Identifies a request by method, URL, query, headers and body. Header names
+/// are case-insensitive and map order never matters.
+fn cache_key(request: &HttpRequest) -> String {
+    let mut query: Vec<(&String, &String)> = request.query.iter().flatten().collect();
+    query.sort();
+    let mut headers: Vec<(String, &String)> = request.headers.iter()
+        .flatten()
+        .map(|(name, value)| (name.to_lowercase(), value))
+        .collect();
+    headers.sort();
+
+    let mut hasher = Sha256::new();
+    hasher.update(request.method.to_uppercase());
+    hasher.update(b"\n");
+    hasher.update(&request.url);
+    for (name, value) in query {
+        hasher.update(format!("\nq:{}={}", name, value));
+    }
+    for (name, value) in headers {
+        hasher.update(format!("\nh:{}:{}", name, value));
+    }
+    hasher.update(b"\n\n");
+    hasher.update(request.body.as_deref().unwrap_or(""));
+    format!("{:x}", hasher.finalize())
+}
+
+fn entry_path(cache_dir: &Path, key: &str) -> PathBuf {
+    cache_dir.join(format!("{}.json", key))
+}
+
+fn read_entry(cache_dir: &Path, key: &str) -> Option<CacheEntry> {
+    let content = fs::read_to_string(entry_path(cache_dir, key)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+// Write through a temporary file so a concurrent reader never sees half an entry
+fn write_entry(cache_dir: &Path, key: &str, entry: &CacheEntry) -> Result<(), String> {
+    fs::create_dir_all(cache_dir)
+        .map_err(|e| format!("Failed to create HTTP cache directory: {}", e))?;
+    let json = serde_json::to_string(entry)
+        .map_err(|e| format!("Failed to serialize cache entry: {}", e))?;
+
+    let temp_path = cache_dir.join(format!(".{}.tmp", key));
+    safe_file_operation(|| fs::write(&temp_path, json), "Failed to write cache entry")?;
+    safe_file_operation(|| fs::rename(&temp_path, entry_path(cache_dir, key)), "Failed to write cache entry")
+}
+
+// Whether a Cache-Control header value forbids storing the response
+fn forbids_storing(cache_control: Option<&String>) -> bool {
+    cache_control.map_or(false, |value| {
+        value.split(',').any(|directive| directive.trim().eq_ignore_ascii_case("no-store"))
+    })
+}
+
+/// Performs `request` through the cache in `cache_dir` according to `settings`.
+/// Only GET and HEAD are cached; other methods change things and always go out.
+async fn request_with_cache(
+    cache_dir: &Path,
+    settings: &HttpCacheSettings,
+    request: &HttpRequest,
+) -> Result<HttpResponse, String> {
+    let method = request.method.to_uppercase();
+    let cacheable = method == "GET" || method == "HEAD";
+    if !cacheable && settings.offline {
+        return Err(format!("Offline mode: {} requests are never cached", method));
+    }
+    if !cacheable || (!settings.enabled && !settings.offline) {
+        return perform_request(request).await;
+    }
+
+    let key = cache_key(request);
+    let now = chrono::Utc::now().timestamp();
+    let cached = read_entry(cache_dir, &key).filter(|entry| {
+        settings.offline || now - entry.stored_at < settings.ttl_seconds as i64
+    });
+
+    if let Some(entry) = cached {
+        println!("💾 HTTP CACHE: Hit for {} {}", entry.method, entry.url);
+        let mut response = entry.response;
+        response.from_cache = true;
+        return Ok(response);
+    }
+    if settings.offline {
+        return Err(format!("Offline mode: no cached response for {} {}", request.method.to_uppercase(), request.url));
+    }
+
+    let response = perform_request(request).await?;
+    let request_no_store = request.headers.iter()
+        .flatten()
+        .any(|(name, value)| name.eq_ignore_ascii_case("cache-control") && forbids_storing(Some(value)));
+    if response.success
+        && (200..300).contains(&response.status)
+        && !request_no_store
+        && !forbids_storing(response.headers.get("cache-control"))
+    {
+        let entry = CacheEntry {
+            method: request.method.to_uppercase(),
+            url: request.url.clone(),
+            stored_at: now,
+            response: response.clone(),
+        };
+        if let Err(e) = write_entry(cache_dir, &key, &entry) {
+            eprintln!("⚠️ HTTP CACHE: {}", e);
+        }
+    }
+    Ok(response)
+}
+
+/// `perform_request`, going through the response cache when it is enabled.
+pub(crate) async fn cached_request(app: &AppHandle, request: &HttpRequest) -> Result<HttpResponse, String> {
+    let settings = load_cache_settings(app)?;
+    request_with_cache(&get_cache_dir(app)?, &settings, request).await
+}
+
+#[tauri::command]
+pub async fn get_http_cache_status(app: AppHandle) -> Result<HttpCacheStatus, String> {
+    let settings = load_cache_settings(&app)?;
+    let mut entries = 0;
+    let mut size_bytes = 0;
+
+    if let Ok(dir_entries) = fs::read_dir(get_cache_dir(&app)?) {
+        for entry in dir_entries.flatten() {
+            if entry.path().extension().map_or(false, |extension| extension == "json") {
+                entries += 1;
+                size_bytes += entry.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+            }
+        }
+    }
+
+    Ok(HttpCacheStatus { settings, entries, size_bytes })
+}
+
+#[tauri::command]
+pub async fn save_http_cache_settings(app: AppHandle, settings: HttpCacheSettings) -> Result<(), String> {
+    let settings_file = get_settings_file(&app)?;
+    ensure_parent_dir(&settings_file)
+        .map_err(|e| format!("Failed to create HTTP directory: {}", e))?;
+
+    let json = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("Failed to serialize HTTP cache settings: {}", e))?;
+    safe_file_operation(
+        || fs::write(&settings_file, json),
+        "Failed to write HTTP cache settings"
+    )?;
+
+    println!("💾 HTTP CACHE: enabled={} ttl={}s offline={}", settings.enabled, settings.ttl_seconds, settings.offline);
+    Ok(())
+}
+
+/// Removes cached responses (only the expired ones when `expired_only`) and
+/// returns how many were removed.
+#[tauri::command]
+pub async fn clear_http_cache(app: AppHandle, expired_only: Option<bool>) -> Result<usize, String> {
+    let settings = load_cache_settings(&app)?;
+    let cache_dir = get_cache_dir(&app)?;
+    let now = chrono::Utc::now().timestamp();
+    let expired_only = expired_only.unwrap_or(false);
+
+    let dir_entries = match fs::read_dir(&cache_dir) {
+        Ok(dir_entries) => dir_entries,
+        Err(_) => return Ok(0),
+    };
+
+    let mut removed = 0;
+    for entry in dir_entries.flatten() {
+        let path = entry.path();
+        if expired_only {
+            let fresh = fs::read_to_string(&path).ok()
+                .and_then(|content| serde_json::from_str::<CacheEntry>(&content).ok())
+                .map_or(false, |cached| now - cached.stored_at < settings.ttl_seconds as i64);
+            if fresh {
+                continue;
+            }
+        }
+        if fs::remove_file(&path).is_ok() {
+            removed += 1;
+        }
+    }
+
+    println!("🧹 HTTP CACHE: Removed {} entries", removed);
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use crate::commands::test_server::*;
+
+    fn request(url: String) -> HttpRequest {
+        HttpRequest {
+            url,
+            method: "GET".to_string(),
+            headers: None,
+            query: None,
+            body: None,
+            timeout_seconds: Some(5),
+        }
+    }
+
+    fn settings(enabled: bool, ttl_seconds: u64, offline: bool) -> HttpCacheSettings {
+        HttpCacheSettings { enabled, ttl_seconds, offline }
+    }
+
+    #[test]
+    fn key_covers_method_url_headers_and_body() {
+        let mut a = request("https://example.com/".to_string());
+        a.headers = Some(HashMap::from([
+            ("Accept".to_string(), "text/html".to_string()),
+            ("X-Page".to_string(), "1".to_string()),
+        ]));
+        let mut b = request("https://example.com/".to_string());
+        b.method = "get".to_string();
+        b.headers = Some(HashMap::from([
+            ("x-page".to_string(), "1".to_string()),
+            ("accept".to_string(), "text/html".to_string()),
+        ]));
+        assert_eq!(cache_key(&a), cache_key(&b));
+
+        let mut with_body = b;
+        with_body.body = Some("x".to_string());
+        assert_ne!(cache_key(&a), cache_key(&with_body));
+
+        let mut post = request("https://example.com/".to_string());
+        post.method = "POST".to_string();
+        assert_ne!(cache_key(&request("https://example.com/".to_string())), cache_key(&post));
+        assert_ne!(cache_key(&request("https://example.com/a".to_string())), cache_key(&request("https://example.com/b".to_string())));
+    }
+
+    static HITS: AtomicUsize = AtomicUsize::new(0);
+
+    fn counted(_: &MockRequest) -> MockResponse {
+        HITS.fetch_add(1, Ordering::SeqCst);
+        respond(200, "text/html", b"<p>cached</p>".to_vec())
+    }
+
+    fn site(request: &MockRequest) -> MockResponse {
+        match request.target.as_str() {
+            "/missing" => respond(404, "text/plain", b"missing".to_vec()),
+            "/private" => {
+                let mut response = respond(200, "text/html", b"<p>secret</p>".to_vec());
+                response.headers.push(("Cache-Control", "private, no-store".to_string()));
+                response
+            }
+            _ => respond(200, "text/html", b"<p>page</p>".to_vec()),
+        }
+    }
+
+    #[tokio::test]
+    async fn responses_are_reused_until_they_expire_and_replayed_offline() {
+        let base = spawn_server(counted);
+        let cache = tempfile::tempdir().unwrap();
+        let page = request(format!("{}/page", base));
+        let hits_before = HITS.load(Ordering::SeqCst);
+        assert_eq!(hits_before, 0);
+
+        let first = request_with_cache(cache.path(), &settings(true, 60, false), &page).await.unwrap();
+        let second = request_with_cache(cache.path(), &settings(true, 60, false), &page).await.unwrap();
+        assert!(!first.from_cache);
+        assert!(second.from_cache);
+        assert_eq!(second.body, "<p>cached</p>");
+        assert_eq!(HITS.load(Ordering::SeqCst) - hits_before, 1);
+
+        // A zero TTL always refetches; offline mode replays even stale entries
+        request_with_cache(cache.path(), &settings(true, 0, false), &page).await.unwrap();
+        assert_eq!(HITS.load(Ordering::SeqCst) - hits_before, 2);
+        let offline = request_with_cache(cache.path(), &settings(false, 0, true), &page).await.unwrap();
+        assert!(offline.from_cache);
+        assert_eq!(HITS.load(Ordering::SeqCst) - hits_before, 2);
+    }
+
+    #[tokio::test]
+    async fn errors_are_not_cached_and_offline_misses_fail() {
+        let base = spawn_server(site);
+        let cache = tempfile::tempdir().unwrap();
+        let missing = request(format!("{}/missing", base));
+
+        let response = request_with_cache(cache.path(), &settings(true, 60, false), &missing).await.unwrap();
+        assert_eq!(response.status, 404);
+        assert!(fs::read_dir(cache.path()).unwrap().next().is_none());
+
+        let error = request_with_cache(cache.path(), &settings(true, 60, true), &missing).await.unwrap_err();
+        assert!(error.starts_with("Offline mode"));
+    }
+
+    #[tokio::test]
+    async fn only_storable_get_and_head_responses_are_cached() {
+        let base = spawn_server(site);
+        let cache = tempfile::tempdir().unwrap();
+        let enabled = settings(true, 60, false);
+
+        let mut post = request(format!("{}/page", base));
+        post.method = "POST".to_string();
+        request_with_cache(cache.path(), &enabled, &post).await.unwrap();
+        request_with_cache(cache.path(), &enabled, &request(format!("{}/private", base))).await.unwrap();
+        assert!(fs::read_dir(cache.path()).unwrap().next().is_none());
+
+        let error = request_with_cache(cache.path(), &settings(true, 60, true), &post).await.unwrap_err();
+        assert!(error.starts_with("Offline mode"));
+
+        let mut head = request(format!("{}/page", base));
+        head.method = "HEAD".to_string();
+        request_with_cache(cache.path(), &enabled, &head).await.unwrap();
+        assert!(request_with_cache(cache.path(), &enabled, &head).await.unwrap().from_cache);
+    }
+
+    #[tokio::test]
+    async fn disabled_cache_stores_nothing() {
+        let base = spawn_server(site);
+        let cache = tempfile::tempdir().unwrap();
+
+        let response = request_with_cache(cache.path(), &HttpCacheSettings::default(), &request(format!("{}/page", base))).await.unwrap();
+
+        assert!(!response.from_cache);
+        assert!(fs::read_dir(cache.path()).unwrap().next().is_none());
+    }
+}
@@ -0,0 +1,1183 @@
+// src-tauri/src/commands/http_collections.rs
+// Saved HTTP request collections per project, with variables, cookies and history
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::Url;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tauri::{AppHandle, State};
+use uuid::Uuid;
+use super::{
+    Node,
+    environment::load_execution_environment,
+    utils::*,
+    web_scraping::{perform_request_without_redirects, save_project_file, HttpRequest, HttpResponse},
+};
+
+const COLLECTIONS_DIR: &str = ".requests";
+const COLLECTIONS_FILE_NAME: &str = "collections.json";
+const COOKIES_FILE_NAME: &str = "cookies.json";
+const HISTORY_FILE_NAME: &str = "history.json";
+const MAX_HISTORY_ENTRIES: usize = 100;
+const HISTORY_BODY_LIMIT: usize = 256 * 1024;
+const MAX_VARIABLE_PASSES: usize = 5;
+const MAX_REDIRECTS: usize = 10;
+const HTTP_METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedRequest {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub method: String,
+    pub url: String, // May contain {{variables}}, like headers, query values and body
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub query: BTreeMap<String, String>,
+    pub body: Option<String>,
+    pub timeout_seconds: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestCollection {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>, // Defaults; the project environment overrides them
+    #[serde(default)]
+    pub requests: Vec<SavedRequest>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CollectionsStore {
+    #[serde(default)]
+    collections: Vec<RequestCollection>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredCookie {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    pub host_only: bool, // No Domain attribute: only sent back to the exact host
+    pub path: String,
+    pub expires: Option<i64>, // Unix seconds; None for session cookies
+    pub secure: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestHistoryEntry {
+    pub id: String,
+    pub collection_id: Option<String>,
+    pub request_id: Option<String>,
+    pub request_name: String,
+    pub method: String,
+    pub url: String, // Resolved, with secrets redacted
+    pub sent_at: i64, // Unix millis
+    pub response: HttpResponse, // Redacted, body cut at HISTORY_BODY_LIMIT
+    pub body_truncated: bool,
+    #[serde(default)]
+    pub body_redacted: bool, // Secrets were scrubbed, so the body is no longer what was received
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResponseDestination {
+    pub parent_id: Option<String>,
+    pub file_path: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SentRequest {
+    pub response: HttpResponse,
+    pub history_id: String,
+    pub saved_node: Option<Node>,
+}
+
+/// Serializes writers of collections, cookie jars and histories.
+#[derive(Default)]
+pub struct HttpCollections {
+    write_lock: Mutex<()>,
+}
+
+fn get_collections_file(app: &AppHandle, project_id: &str) -> Result<PathBuf, String> {
+    Ok(get_project_dir(app, project_id)?.join(COLLECTIONS_DIR).join(COLLECTIONS_FILE_NAME))
+}
+
+// Cookies and responses can hold credentials, so they stay in app data rather than the project
+fn get_project_http_file(app: &AppHandle, project_id: &str, file_name: &str) -> Result<PathBuf, String> {
+    Ok(get_http_dir(app)?.join(project_id).join(file_name))
+}
+
+fn load_json<T: DeserializeOwned + Default>(path: &Path) -> Result<T, String> {
+    if !path.exists() {
+        return Ok(T::default());
+    }
+    let content = safe_file_operation(
+        || fs::read_to_string(path),
+        "Failed to read HTTP data"
+    )?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+fn save_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    ensure_parent_dir(path)
+        .map_err(|e| format!("Failed to create directory: {}", e))?;
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize HTTP data: {}", e))?;
+    safe_file_operation(
+        || fs::write(path, json),
+        "Failed to write HTTP data"
+    )
+}
+
+fn load_collections(app: &AppHandle, project_id: &str) -> Result<CollectionsStore, String> {
+    load_json(&get_collections_file(app, project_id)?)
+}
+
+static PLACEHOLDER: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_.-]*)\s*\}\}").unwrap());
+
+/// Replaces `{{name}}` placeholders, repeating so variables may refer to others.
+/// Names without a value are collected in `missing`.
+fn substitute(
+    template: &str,
+    variables: &BTreeMap<String, String>,
+    missing: &mut BTreeSet<String>,
+) -> String {
+    let mut text = template.to_string();
+
+    for _ in 0..MAX_VARIABLE_PASSES {
+        if !PLACEHOLDER.is_match(&text) {
+            break;
+        }
+        text = PLACEHOLDER.replace_all(&text, |captures: &regex::Captures| {
+            let name = &captures[1];
+            match variables.get(name) {
+                Some(value) => value.clone(),
+                None => {
+                    missing.insert(name.to_string());
+                    captures[0].to_string()
+                }
+            }
+        }).to_string();
+        if !missing.is_empty() {
+            break;
+        }
+    }
+    text
+}
+
+fn resolve_request(
+    request: &SavedRequest,
+    variables: &BTreeMap<String, String>,
+) -> Result<HttpRequest, String> {
+    let mut missing = BTreeSet::new();
+    let mut resolve = |text: &str| substitute(text, variables, &mut missing);
+
+    let resolved = HttpRequest {
+        url: resolve(&request.url),
+        method: request.method.clone(),
+        headers: Some(request.headers.iter().map(|(name, value)| (name.clone(), resolve(value))).collect()),
+        query: Some(request.query.iter().map(|(name, value)| (name.clone(), resolve(value))).collect()),
+        body: request.body.as_deref().map(&mut resolve),
+        timeout_seconds: request.timeout_seconds,
+    };
+
+    if !missing.is_empty() {
+        return Err(format!(
+            "Undefined variables: {}",
+            missing.into_iter().collect::<Vec<_>>().join(", ")
+        ));
+    }
+    Ok(resolved)
+}
+
+// Cookie jar ------------------------------------------------------------------
+
+fn default_cookie_path(url: &Url) -> String {
+    match url.path().rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(index) => url.path()[..index].to_string(),
+    }
+}
+
+fn domain_matches(host: &str, domain: &str) -> bool {
+    host == domain || host.ends_with(&format!(".{}", domain))
+}
+
+fn path_matches(request_path: &str, cookie_path: &str) -> bool {
+    request_path == cookie_path
+        || (request_path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/') || request_path[cookie_path.len()..].starts_with('/')))
+}
+
+/// Parses a Set-Cookie header received from `url`. Cookies for a domain the
+/// host does not belong to, or for a bare top-level domain, are rejected.
+fn parse_set_cookie(header: &str, url: &Url, now: i64) -> Option<StoredCookie> {
+    let host = url.host_str()?.to_lowercase();
+    let mut parts = header.split(';');
+    let (name, value) = parts.next()?.split_once('=')?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+
+    let mut cookie = StoredCookie {
+        name: name.to_string(),
+        value: value.trim().trim_matches('"').to_string(),
+        domain: host.clone(),
+        host_only: true,
+        path: default_cookie_path(url),
+        expires: None,
+        secure: false,
+    };
+    let mut max_age = None;
+
+    for attribute in parts {
+        let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+        let value = value.trim();
+        match key.trim().to_lowercase().as_str() {
+            "domain" if !value.is_empty() => {
+                let domain = value.trim_start_matches('.').to_lowercase();
+                if !domain_matches(&host, &domain) {
+                    return None;
+                }
+                // Domain=com would share the cookie with every .com site; a
+                // dotless domain is only kept when it is the host itself
+                if !domain.trim_end_matches('.').contains('.') {
+                    if domain != host {
+                        return None;
+                    }
+                    continue;
+                }
+                cookie.domain = domain;
+                cookie.host_only = false;
+            }
+            "path" if value.starts_with('/') => cookie.path = value.to_string(),
+            "expires" => {
+                cookie.expires = chrono::DateTime::parse_from_rfc2822(&value.replace('-', " "))
+                    .ok()
+                    .map(|expires| expires.timestamp());
+            }
+            "max-age" => max_age = value.parse::<i64>().ok(),
+            "secure" => cookie.secure = true,
+            _ => {}
+        }
+    }
+
+    // Max-Age wins over Expires
+    if let Some(max_age) = max_age {
+        cookie.expires = Some(now + max_age);
+    }
+    Some(cookie)
+}
+
+/// Stores cookies from a response; expired ones delete their stored counterpart.
+fn update_cookie_jar(jar: &mut Vec<StoredCookie>, set_cookies: &[String], url: &Url, now: i64) {
+    for header in set_cookies {
+        let cookie = match parse_set_cookie(header, url, now) {
+            Some(cookie) => cookie,
+            None => continue,
+        };
+        jar.retain(|stored| {
+            !(stored.name == cookie.name && stored.domain == cookie.domain && stored.path == cookie.path)
+        });
+        if cookie.expires.map_or(true, |expires| expires > now) {
+            jar.push(cookie);
+        }
+    }
+    jar.retain(|cookie| cookie.expires.map_or(true, |expires| expires > now));
+}
+
+/// The Cookie header to send to `url`, most specific paths first.
+fn cookie_header(jar: &[StoredCookie], url: &Url, now: i64) -> Option<String> {
+    let host = url.host_str()?.to_lowercase();
+    let mut matching: Vec<&StoredCookie> = jar.iter()
+        .filter(|cookie| cookie.expires.map_or(true, |expires| expires > now))
+        .filter(|cookie| if cookie.host_only { host == cookie.domain } else { domain_matches(&host, &cookie.domain) })
+        .filter(|cookie| path_matches(url.path(), &cookie.path))
+        .filter(|cookie| !cookie.secure || url.scheme() == "https")
+        .collect();
+    if matching.is_empty() {
+        return None;
+    }
+
+    matching.sort_by_key(|cookie| std::cmp::Reverse(cookie.path.len()));
+    Some(matching.iter()
+        .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+        .collect::<Vec<_>>()
+        .join("; "))
+}
+
+// Import ----------------------------------------------------------------------
+
+fn request_name(method: &str, url: &str) -> String {
+    let short_url = url.split("://").nth(1).unwrap_or(url);
+    format!("{} {}", method, short_url)
+}
+
+fn new_request(method: &str, url: &str) -> SavedRequest {
+    SavedRequest {
+        id: Uuid::new_v4().to_string(),
+        name: request_name(method, url),
+        method: method.to_string(),
+        url: url.to_string(),
+        headers: BTreeMap::new(),
+        query: BTreeMap::new(),
+        body: None,
+        timeout_seconds: None,
+    }
+}
+
+/// Parses the `.http` format used by REST clients: requests separated by `###`,
+/// `@name = value` variables, `# @name` request names, headers, a blank line and a body.
+fn parse_http_file(text: &str) -> (Vec<SavedRequest>, BTreeMap<String, String>) {
+    let mut requests = Vec::new();
+    let mut variables = BTreeMap::new();
+
+    let mut blocks: Vec<(Option<String>, Vec<&str>)> = vec![(None, Vec::new())];
+    for line in text.lines() {
+        if let Some(title) = line.trim_start().strip_prefix("###") {
+            let title = title.trim();
+            blocks.push(((!title.is_empty()).then(|| title.to_string()), Vec::new()));
+        } else {
+            blocks.last_mut().unwrap().1.push(line);
+        }
+    }
+
+    for (title, lines) in blocks {
+        let mut name = title;
+        let mut request: Option<SavedRequest> = None;
+        let mut in_body = false;
+        let mut body_lines: Vec<&str> = Vec::new();
+
+        for line in lines {
+            let trimmed = line.trim();
+
+            if in_body {
+                // Response handlers and redirects are REST client features we don't run
+                if !trimmed.starts_with("> ") && !trimmed.starts_with(">> ") {
+                    body_lines.push(line);
+                }
+                continue;
+            }
+
+            if trimmed.starts_with('#') || trimmed.starts_with("//") {
+                let comment = trimmed.trim_start_matches(['#', '/']).trim();
+                if let Some(request_name) = comment.strip_prefix("@name") {
+                    name = Some(request_name.trim_start_matches([' ', '=']).trim().to_string());
+                }
+                continue;
+            }
+
+            match request.as_mut() {
+                None => {
+                    if trimmed.is_empty() {
+                        continue;
+                    }
+                    if let Some(definition) = trimmed.strip_prefix('@') {
+                        if let Some((key, value)) = definition.split_once('=') {
+                            variables.insert(key.trim().to_string(), value.trim().to_string());
+                        }
+                        continue;
+                    }
+
+                    let mut tokens = trimmed.split_whitespace();
+                    let first = tokens.next().unwrap_or("");
+                    let (method, url) = if HTTP_METHODS.contains(&first.to_uppercase().as_str()) {
+                        (first.to_uppercase(), tokens.next().unwrap_or("").to_string())
+                    } else {
+                        ("GET".to_string(), first.to_string())
+                    };
+                    request = Some(new_request(&method, &url));
+                }
+                Some(request) => {
+                    if trimmed.is_empty() {
+                        in_body = true;
+                    } else if trimmed.starts_with('?') || trimmed.starts_with('&') {
+                        // Query parameters continued on their own lines
+                        request.url.push_str(trimmed);
+                    } else if let Some((header, value)) = trimmed.split_once(':') {
+                        request.headers.insert(header.trim().to_string(), value.trim().to_string());
+                    }
+                }
+            }
+        }
+
+        if let Some(mut request) = request {
+            let body = body_lines.join("\n").trim().to_string();
+            if !body.is_empty() {
+                request.body = Some(body);
+            }
+            if let Some(name) = name.filter(|name| !name.is_empty()) {
+                request.name = name;
+            }
+            requests.push(request);
+        }
+    }
+
+    (requests, variables)
+}
+
+/// Splits shell text into words, honouring quotes, `$'…'` strings, backslash
+/// escapes and line continuations. Each word records whether it was quoted.
+fn shell_words(text: &str) -> Result<Vec<(String, bool)>, String> {
+    let mut words = Vec::new();
+    let mut chars = text.chars().peekable();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut in_word = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push((std::mem::take(&mut current), quoted));
+                    quoted = false;
+                    in_word = false;
+                }
+            }
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                Some('\r') if chars.peek() == Some(&'\n') => {
+                    chars.next();
+                }
+                Some(escaped) => {
+                    current.push(escaped);
+                    in_word = true;
+                }
+                None => {}
+            },
+            '\'' => {
+                in_word = true;
+                quoted = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => return Err("Unterminated single quote".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                quoted = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(escaped @ ('"' | '\\' | '$' | '`')) => current.push(escaped),
+                            Some('\n') => {}
+                            Some(other) => {
+                                current.push('\\');
+                                current.push(other);
+                            }
+                            None => return Err("Unterminated double quote".to_string()),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err("Unterminated double quote".to_string()),
+                    }
+                }
+            }
+            '$' if chars.peek() == Some(&'\'') => {
+                // Bash ANSI-C quoting, as produced by browsers' "Copy as cURL"
+                chars.next();
+                in_word = true;
+                quoted = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => current.push('\n'),
+                            Some('t') => current.push('\t'),
+                            Some('r') => current.push('\r'),
+                            Some(other) => current.push(other),
+                            None => return Err("Unterminated $' quote".to_string()),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err("Unterminated $' quote".to_string()),
+                    }
+                }
+            }
+            c => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push((current, quoted));
+    }
+    Ok(words)
+}
+
+// Split a short option like `-XPOST` into its flag and attached value
+fn split_option(arg: &str) -> (String, Option<String>) {
+    if let Some(long) = arg.strip_prefix("--") {
+        return match long.split_once('=') {
+            Some((option, value)) => (format!("--{}", option), Some(value.to_string())),
+            None => (arg.to_string(), None),
+        };
+    }
+    if arg.len() > 2 && arg.starts_with('-') && arg.is_char_boundary(2) {
+        return (arg[..2].to_string(), Some(arg[2..].to_string()));
+    }
+    (arg.to_string(), None)
+}
+
+fn parse_curl_command(args: &[String]) -> Result<SavedRequest, String> {
+    const VALUE_OPTIONS: &[&str] = &[
+        "-X", "--request", "-H", "--header", "-d", "--data", "--data-raw", "--data-binary",
+        "--data-ascii", "--data-urlencode", "--json", "-u", "--user", "-b", "--cookie",
+        "-A", "--user-agent", "-e", "--referer", "--url", "-m", "--max-time", "-F", "--form",
+        "-o", "--output", "-c", "--cookie-jar", "-x", "--proxy", "-w", "--write-out",
+        "--connect-timeout", "--retry", "--cacert", "--cert", "--key", "-T", "--upload-file",
+    ];
+
+    let mut method: Option<String> = None;
+    let mut url: Option<String> = None;
+    let mut headers: Vec<(String, String)> = Vec::new();
+    let mut data: Vec<String> = Vec::new();
+    let mut json = false;
+    let mut use_get = false;
+    let mut timeout_seconds = None;
+
+    let mut index = 0;
+    while index < args.len() {
+        let arg = &args[index];
+        index += 1;
+
+        if !arg.starts_with('-') || arg == "-" {
+            if url.is_none() {
+                url = Some(arg.clone());
+            }
+            continue;
+        }
+
+        let (option, attached) = split_option(arg);
+        let value = if VALUE_OPTIONS.contains(&option.as_str()) {
+            match attached {
+                Some(value) => Some(value),
+                None => {
+                    index += 1;
+                    Some(args.get(index - 1).cloned().ok_or_else(|| format!("{} needs a value", option))?)
+                }
+            }
+        } else {
+            None
+        };
+        let value = value.unwrap_or_default();
+
+        match option.as_str() {
+            "-X" | "--request" => method = Some(value.to_uppercase()),
+            "-H" | "--header" => {
+                if let Some((name, header_value)) = value.split_once(':') {
+                    headers.push((name.trim().to_string(), header_value.trim().to_string()));
+                }
+            }
+            "-d" | "--data" | "--data-raw" | "--data-binary" | "--data-ascii" | "--data-urlencode" => data.push(value),
+            "--json" => {
+                json = true;
+                data.push(value);
+            }
+            "-u" | "--user" => headers.push(("Authorization".to_string(), format!("Basic {}", BASE64.encode(value)))),
+            "-b" | "--cookie" if value.contains('=') => headers.push(("Cookie".to_string(), value)),
+            "-A" | "--user-agent" => headers.push(("User-Agent".to_string(), value)),
+            "-e" | "--referer" => headers.push(("Referer".to_string(), value)),
+            "--url" => url = Some(value),
+            "-m" | "--max-time" => timeout_seconds = value.parse::<f64>().ok().map(|seconds| seconds.ceil() as u64),
+            "-G" | "--get" => use_get = true,
+            "-I" | "--head" => method = Some("HEAD".to_string()),
+            "-F" | "--form" => return Err("Multipart form uploads (-F) are not supported".to_string()),
+            "-T" | "--upload-file" => return Err("File uploads (-T) are not supported".to_string()),
+            _ => {} // Output, TLS and verbosity flags don't change the request
+        }
+    }
+
+    let mut url = url.ok_or_else(|| "curl command has no URL".to_string())?;
+    if !url.contains("://") {
+        url = format!("http://{}", url);
+    }
+
+    let has_header = |headers: &[(String, String)], name: &str| {
+        headers.iter().any(|(header, _)| header.eq_ignore_ascii_case(name))
+    };
+    let data = if data.is_empty() { None } else { Some(data.join("&")) };
+
+    let method = if use_get {
+        if let Some(data) = &data {
+            url.push(if url.contains('?') { '&' } else { '?' });
+            url.push_str(data);
+        }
+        method.unwrap_or_else(|| "GET".to_string())
+    } else {
+        method.unwrap_or_else(|| if data.is_some() { "POST" } else { "GET" }.to_string())
+    };
+
+    let mut request = new_request(&method, &url);
+    if !use_get {
+        if json {
+            if !has_header(&headers, "Content-Type") {
+                headers.push(("Content-Type".to_string(), "application/json".to_string()));
+            }
+            if !has_header(&headers, "Accept") {
+                headers.push(("Accept".to_string(), "application/json".to_string()));
+            }
+        } else if data.is_some() && !has_header(&headers, "Content-Type") {
+            headers.push(("Content-Type".to_string(), "application/x-www-form-urlencoded".to_string()));
+        }
+        request.body = data;
+    }
+    request.headers = headers.into_iter().collect();
+    request.timeout_seconds = timeout_seconds;
+    Ok(request)
+}
+
+/// Parses one or more curl commands, as copied from a terminal or browser.
+fn parse_curl(text: &str) -> Result<Vec<SavedRequest>, String> {
+    let mut commands: Vec<Vec<String>> = Vec::new();
+    for (word, quoted) in shell_words(text)? {
+        if word == "curl" && !quoted {
+            commands.push(Vec::new());
+        } else if let Some(command) = commands.last_mut() {
+            command.push(word);
+        }
+    }
+
+    if commands.is_empty() {
+        return Err("No curl command found".to_string());
+    }
+    commands.iter().map(|args| parse_curl_command(args)).collect()
+}
+
+fn assign_ids(collection: &mut RequestCollection) {
+    if collection.id.is_empty() {
+        collection.id = Uuid::new_v4().to_string();
+    }
+    for request in &mut collection.requests {
+        if request.id.is_empty() {
+            request.id = Uuid::new_v4().to_string();
+        }
+    }
+}
+
+fn validate_collection(collection: &RequestCollection) -> Result<(), String> {
+    if collection.name.trim().is_empty() {
+        return Err("Collection name cannot be empty".to_string());
+    }
+    for request in &collection.requests {
+        if request.name.trim().is_empty() {
+            return Err("Request name cannot be empty".to_string());
+        }
+        if !HTTP_METHODS.contains(&request.method.to_uppercase().as_str()) {
+            return Err(format!("Unsupported HTTP method in {}: {}", request.name, request.method));
+        }
+    }
+    Ok(())
+}
+
+// Keep history small: cut long bodies on a character boundary
+fn truncate_body(response: &mut HttpResponse) -> bool {
+    if response.body.len() <= HISTORY_BODY_LIMIT {
+        return false;
+    }
+    let mut end = HISTORY_BODY_LIMIT;
+    while !response.body.is_char_boundary(end) {
+        end -= 1;
+    }
+    response.body.truncate(end);
+    true
+}
+
+fn decode_response_body(response: &HttpResponse) -> Result<Vec<u8>, String> {
+    if response.body_encoding == "base64" {
+        BASE64.decode(&response.body).map_err(|e| format!("Failed to decode response body: {}", e))
+    } else {
+        Ok(response.body.clone().into_bytes())
+    }
+}
+
+// The next request when `response` is a redirect: 303, and 301/302 after a POST, turn into a
+// body-less GET, and credentials the caller set are not carried to another host
+fn redirect_request(request: &HttpRequest, response: &HttpResponse) -> Option<HttpRequest> {
+    if !matches!(response.status, 301 | 302 | 303 | 307 | 308) {
+        return None;
+    }
+    let current = Url::parse(&request.url).ok()?;
+    let location = response.headers.get("location")?;
+    let next_url = current.join(location).ok()?;
+
+    let mut next = request.clone();
+    next.url = next_url.to_string();
+    next.query = None; // Already part of the URL that redirected
+    let method = request.method.to_uppercase();
+    if response.status == 303 || (matches!(response.status, 301 | 302) && method == "POST") {
+        next.method = if method == "HEAD" { method } else { "GET".to_string() };
+        next.body = None;
+    }
+    if next_url.host_str() != current.host_str() {
+        if let Some(headers) = next.headers.as_mut() {
+            headers.retain(|name, _| {
+                !["authorization", "cookie", "proxy-authorization"].contains(&name.to_lowercase().as_str())
+            });
+        }
+    }
+    Some(next)
+}
+
+#[tauri::command]
+pub async fn list_request_collections(
+    app: AppHandle,
+    project_id: String,
+) -> Result<Vec<RequestCollection>, String> {
+    Ok(load_collections(&app, &project_id)?.collections)
+}
+
+/// Creates the collection, or replaces the one with the same id.
+#[tauri::command]
+pub async fn save_request_collection(
+    app: AppHandle,
+    http: State<'_, HttpCollections>,
+    project_id: String,
+    mut collection: RequestCollection,
+) -> Result<RequestCollection, String> {
+    validate_collection(&collection)?;
+    assign_ids(&mut collection);
+
+    let _guard = http.write_lock.lock().unwrap();
+    let mut store = load_collections(&app, &project_id)?;
+    match store.collections.iter_mut().find(|existing| existing.id == collection.id) {
+        Some(existing) => *existing = collection.clone(),
+        None => store.collections.push(collection.clone()),
+    }
+    save_json(&get_collections_file(&app, &project_id)?, &store)?;
+
+    println!("🗂️ HTTP: Saved collection {} ({} requests)", collection.name, collection.requests.len());
+    Ok(collection)
+}
+
+#[tauri::command]
+pub async fn delete_request_collection(
+    app: AppHandle,
+    http: State<'_, HttpCollections>,
+    project_id: String,
+    collection_id: String,
+) -> Result<(), String> {
+    let _guard = http.write_lock.lock().unwrap();
+    let mut store = load_collections(&app, &project_id)?;
+    let before = store.collections.len();
+    store.collections.retain(|collection| collection.id != collection_id);
+    if store.collections.len() == before {
+        return Err(format!("Collection not found: {}", collection_id));
+    }
+    save_json(&get_collections_file(&app, &project_id)?, &store)
+}
+
+/// Imports `.http` or curl text into a collection (a new one unless `collection_id` is given).
+#[tauri::command]
+pub async fn import_http_requests(
+    app: AppHandle,
+    http: State<'_, HttpCollections>,
+    project_id: String,
+    text: String,
+    format: Option<String>, // "http" or "curl"; guessed when absent
+    collection_id: Option<String>,
+    collection_name: Option<String>,
+) -> Result<RequestCollection, String> {
+    let is_curl = match format.as_deref() {
+        Some("curl") => true,
+        Some("http") => false,
+        Some(other) => return Err(format!("Unknown import format: {}", other)),
+        None => text.trim_start().starts_with("curl"),
+    };
+    let (requests, variables) = if is_curl {
+        (parse_curl(&text)?, BTreeMap::new())
+    } else {
+        parse_http_file(&text)
+    };
+    if requests.is_empty() {
+        return Err("No requests found to import".to_string());
+    }
+
+    let _guard = http.write_lock.lock().unwrap();
+    let mut store = load_collections(&app, &project_id)?;
+    let collection = match collection_id {
+        Some(collection_id) => store.collections.iter_mut()
+            .find(|collection| collection.id == collection_id)
+            .ok_or_else(|| format!("Collection not found: {}", collection_id))?,
+        None => {
+            store.collections.push(RequestCollection {
+                id: Uuid::new_v4().to_string(),
+                name: collection_name.unwrap_or_else(|| "Imported requests".to_string()),
+                variables: BTreeMap::new(),
+                requests: Vec::new(),
+            });
+            store.collections.last_mut().unwrap()
+        }
+    };
+
+    println!("📥 HTTP: Imported {} requests into {}", requests.len(), collection.name);
+    for (key, value) in variables {
+        collection.variables.entry(key).or_insert(value);
+    }
+    collection.requests.extend(requests);
+    let imported = collection.clone();
+
+    save_json(&get_collections_file(&app, &project_id)?, &store)?;
+    Ok(imported)
+}
+
+/// Sends a saved request with variables resolved (collection defaults, then the
+/// project environment, then `variables`), carrying the project's cookie jar.
+#[tauri::command]
+pub async fn send_saved_request(
+    app: AppHandle,
+    http: State<'_, HttpCollections>,
+    project_id: String,
+    collection_id: String,
+    request_id: String,
+    variables: Option<BTreeMap<String, String>>,
+    save_to: Option<ResponseDestination>,
+) -> Result<SentRequest, String> {
+    let store = load_collections(&app, &project_id)?;
+    let collection = store.collections.iter()
+        .find(|collection| collection.id == collection_id)
+        .ok_or_else(|| format!("Collection not found: {}", collection_id))?;
+    let saved = collection.requests.iter()
+        .find(|request| request.id == request_id)
+        .ok_or_else(|| format!("Request not found: {}", request_id))?;
+
+    let environment = load_execution_environment(&app, &project_id)?;
+    let mut all_variables = collection.variables.clone();
+    all_variables.extend(environment.vars.iter().cloned());
+    all_variables.extend(variables.unwrap_or_default());
+    let mut request = resolve_request(saved, &all_variables)?;
+
+    Url::parse(&request.url).map_err(|e| format!("Invalid URL {}: {}", request.url, e))?;
+    let cookies_file = get_project_http_file(&app, &project_id, COOKIES_FILE_NAME)?;
+    let now = chrono::Utc::now().timestamp();
+    let custom_cookie = request.headers.as_ref()
+        .map_or(false, |headers| headers.keys().any(|name| name.eq_ignore_ascii_case("cookie")));
+
+    // Redirects are followed here so cookies set by every hop reach the jar and the hops after it
+    let (method, sent_url) = (request.method.to_uppercase(), request.url.clone());
+    let sent_at = chrono::Utc::now().timestamp_millis();
+    let mut jar: Vec<StoredCookie> = load_json(&cookies_file)?;
+    let mut set_cookies: Vec<(Vec<String>, Url)> = Vec::new();
+    let mut hops = 0;
+    let response = loop {
+        let hop_url = Url::parse(&request.url).map_err(|e| format!("Invalid URL {}: {}", request.url, e))?;
+        if !custom_cookie {
+            let headers = request.headers.get_or_insert_with(HashMap::new);
+            headers.retain(|name, _| !name.eq_ignore_ascii_case("cookie"));
+            if let Some(cookie) = cookie_header(&jar, &hop_url, now) {
+                headers.insert("Cookie".to_string(), cookie);
+            }
+        }
+
+        let response = perform_request_without_redirects(&request).await?;
+        if !response.set_cookies.is_empty() {
+            update_cookie_jar(&mut jar, &response.set_cookies, &hop_url, now);
+            set_cookies.push((response.set_cookies.clone(), hop_url));
+        }
+
+        match redirect_request(&request, &response) {
+            Some(next) if hops < MAX_REDIRECTS => {
+                hops += 1;
+                request = next;
+            }
+            _ => break response,
+        }
+    };
+
+    // Error pages are not what the destination asked for
+    let saved_node = match &save_to {
+        Some(destination) if response.success && (200..300).contains(&response.status) => Some(save_project_file(
+            &app,
+            &project_id,
+            destination.parent_id.as_deref(),
+            &destination.file_path,
+            &decode_response_body(&response)?,
+        )?),
+        _ => None,
+    };
+
+    let mut recorded = response.clone();
+    recorded.body = environment.redact(&recorded.body);
+    let body_redacted = recorded.body != response.body;
+    for value in recorded.headers.values_mut() {
+        *value = environment.redact(value);
+    }
+    let body_truncated = truncate_body(&mut recorded);
+    let entry = RequestHistoryEntry {
+        id: Uuid::new_v4().to_string(),
+        collection_id: Some(collection_id.clone()),
+        request_id: Some(request_id.clone()),
+        request_name: saved.name.clone(),
+        method: method.clone(),
+        url: environment.redact(&sent_url),
+        sent_at,
+        response: recorded,
+        body_truncated,
+        body_redacted,
+    };
+    let history_id = entry.id.clone();
+
+    {
+        let _guard = http.write_lock.lock().unwrap();
+        if !set_cookies.is_empty() {
+            // Reload so cookies stored by other requests meanwhile are kept
+            let mut jar: Vec<StoredCookie> = load_json(&cookies_file)?;
+            for (headers, hop_url) in &set_cookies {
+                update_cookie_jar(&mut jar, headers, hop_url, now);
+            }
+            save_json(&cookies_file, &jar)?;
+        }
+
+        let history_file = get_project_http_file(&app, &project_id, HISTORY_FILE_NAME)?;
+        let mut history: Vec<RequestHistoryEntry> = load_json(&history_file)?;
+        history.insert(0, entry);
+        history.truncate(MAX_HISTORY_ENTRIES);
+        save_json(&history_file, &history)?;
+    }
+
+    println!("📨 HTTP: {} {} -> {}", method, saved.name, response.status);
+    Ok(SentRequest { response, history_id, saved_node })
+}
+
+#[tauri::command]
+pub async fn list_request_history(
+    app: AppHandle,
+    project_id: String,
+    limit: Option<usize>,
+) -> Result<Vec<RequestHistoryEntry>, String> {
+    let mut history: Vec<RequestHistoryEntry> = load_json(&get_project_http_file(&app, &project_id, HISTORY_FILE_NAME)?)?;
+    history.truncate(limit.unwrap_or(MAX_HISTORY_ENTRIES));
+    Ok(history)
+}
+
+#[tauri::command]
+pub async fn clear_request_history(
+    app: AppHandle,
+    http: State<'_, HttpCollections>,
+    project_id: String,
+) -> Result<(), String> {
+    let _guard = http.write_lock.lock().unwrap();
+    save_json(&get_project_http_file(&app, &project_id, HISTORY_FILE_NAME)?, &Vec::<RequestHistoryEntry>::new())
+}
+
+/// Saves the body of a response from history as a project file.
+#[tauri::command]
+pub async fn save_history_response(
+    app: AppHandle,
+    project_id: String,
+    history_id: String,
+    destination: ResponseDestination,
+) -> Result<Node, String> {
+    let history: Vec<RequestHistoryEntry> = load_json(&get_project_http_file(&app, &project_id, HISTORY_FILE_NAME)?)?;
+    let entry = history.iter()
+        .find(|entry| entry.id == history_id)
+        .ok_or_else(|| format!("History entry not found: {}", history_id))?;
+    if entry.body_truncated {
+        return Err("This response was too large to keep in history; send the request again with a destination to save it".to_string());
+    }
+    if entry.body_redacted {
+        return Err("Secrets were removed from this response in history; send the request again with a destination to save it".to_string());
+    }
+
+    save_project_file(
+        &app,
+        &project_id,
+        destination.parent_id.as_deref(),
+        &destination.file_path,
+        &decode_response_body(&entry.response)?,
+    )
+}
+
+#[tauri::command]
+pub async fn list_request_cookies(
+    app: AppHandle,
+    project_id: String,
+) -> Result<Vec<StoredCookie>, String> {
+    let now = chrono::Utc::now().timestamp();
+    let jar: Vec<StoredCookie> = load_json(&get_project_http_file(&app, &project_id, COOKIES_FILE_NAME)?)?;
+    Ok(jar.into_iter().filter(|cookie| cookie.expires.map_or(true, |expires| expires > now)).collect())
+}
+
+#[tauri::command]
+pub async fn clear_request_cookies(
+    app: AppHandle,
+    http: State<'_, HttpCollections>,
+    project_id: String,
+) -> Result<(), String> {
+    let _guard = http.write_lock.lock().unwrap();
+    save_json(&get_project_http_file(&app, &project_id, COOKIES_FILE_NAME)?, &Vec::<StoredCookie>::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn variables_resolve_recursively_and_report_missing() {
+        let mut request = new_request("POST", "{{base_url}}/items");
+        request.headers.insert("Authorization".to_string(), "Bearer {{ token }}".to_string());
+        request.body = Some("{\"owner\": \"{{user}}\"}".to_string());
+        let vars = variables(&[("host", "api.example.com"), ("base_url", "https://{{host}}/v1"), ("token", "s3cret"), ("user", "ada")]);
+
+        let resolved = resolve_request(&request, &vars).unwrap();
+        assert_eq!(resolved.url, "https://api.example.com/v1/items");
+        assert_eq!(resolved.headers.unwrap()["Authorization"], "Bearer s3cret");
+        assert_eq!(resolved.body.as_deref(), Some("{\"owner\": \"ada\"}"));
+
+        let error = resolve_request(&request, &variables(&[("base_url", "x")])).unwrap_err();
+        assert_eq!(error, "Undefined variables: token, user");
+    }
+
+    #[test]
+    fn parses_http_files() {
+        let text = "@base = https://api.example.com\n\
+            @token = {{API_TOKEN}}\n\
+            \n\
+            ### List items\n\
+            GET {{base}}/items\n\
+            \t?page=2\n\
+            Accept: application/json\n\
+            \n\
+            ###\n\
+            # @name create\n\
+            POST {{base}}/items HTTP/1.1\n\
+            Content-Type: application/json\n\
+            Authorization: Bearer {{token}}\n\
+            \n\
+            {\n  \"name\": \"lamp\"\n}\n\
+            \n\
+            > {% client.global.set('id', response.body.id) %}\n\
+            ###\n\
+            https://example.com/health\n";
+
+        let (requests, variables) = parse_http_file(text);
+
+        assert_eq!(variables["base"], "https://api.example.com");
+        assert_eq!(variables["token"], "{{API_TOKEN}}");
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].name, "List items");
+        assert_eq!(requests[0].url, "{{base}}/items?page=2");
+        assert_eq!(requests[0].headers["Accept"], "application/json");
+        assert_eq!(requests[0].body, None);
+        assert_eq!(requests[1].name, "create");
+        assert_eq!(requests[1].method, "POST");
+        assert_eq!(requests[1].body.as_deref(), Some("{\n  \"name\": \"lamp\"\n}"));
+        assert_eq!(requests[2].method, "GET");
+        assert_eq!(requests[2].name, "GET example.com/health");
+    }
+
+    #[test]
+    fn parses_curl_commands() {
+        let text = "curl 'https://api.example.com/items?x=1' \\\n  -H 'Authorization: Bearer abc' \\\n  --data-raw $'{\"name\":\"it\\'s\"}' --compressed\n\
+            curl -XDELETE https://api.example.com/items/1 -u user:pass\n\
+            curl -G example.com/search -d q=lamp -d page=2\n\
+            curl --json '{\"a\":1}' https://api.example.com/json -m 2.5";
+
+        let requests = parse_curl(text).unwrap();
+
+        assert_eq!(requests.len(), 4);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].url, "https://api.example.com/items?x=1");
+        assert_eq!(requests[0].headers["Authorization"], "Bearer abc");
+        assert_eq!(requests[0].headers["Content-Type"], "application/x-www-form-urlencoded");
+        assert_eq!(requests[0].body.as_deref(), Some("{\"name\":\"it's\"}"));
+        assert_eq!(requests[1].method, "DELETE");
+        assert_eq!(requests[1].headers["Authorization"], format!("Basic {}", BASE64.encode("user:pass")));
+        assert_eq!(requests[2].method, "GET");
+        assert_eq!(requests[2].url, "http://example.com/search?q=lamp&page=2");
+        assert_eq!(requests[2].body, None);
+        assert_eq!(requests[3].headers["Content-Type"], "application/json");
+        assert_eq!(requests[3].timeout_seconds, Some(3));
+
+        assert!(parse_curl("curl -F file=@a.txt https://example.com").is_err());
+        assert!(parse_curl("curl 'https://example.com").is_err());
+    }
+
+    #[test]
+    fn redirects_rewrite_the_method_and_drop_credentials_across_hosts() {
+        let request = HttpRequest {
+            url: "https://api.example.com/login?next=1".to_string(),
+            method: "POST".to_string(),
+            headers: Some([("Authorization".to_string(), "Bearer t".to_string()), ("Accept".to_string(), "*/*".to_string())].into_iter().collect()),
+            query: None,
+            body: Some("user=me".to_string()),
+            timeout_seconds: None,
+        };
+        let response = |status: u16, location: &str| HttpResponse {
+            status,
+            headers: [("location".to_string(), location.to_string())].into_iter().collect(),
+            body: String::new(),
+            body_encoding: "utf8".to_string(),
+            body_size: 0,
+            content_type: None,
+            final_url: request.url.clone(),
+            timing: crate::commands::web_scraping::ResponseTiming { headers_ms: 0, total_ms: 0 },
+            success: true,
+            error: None,
+            from_cache: false,
+            set_cookies: Vec::new(),
+        };
+
+        let next = redirect_request(&request, &response(302, "/home")).unwrap();
+        assert_eq!(next.url, "https://api.example.com/home");
+        assert_eq!((next.method.as_str(), next.body), ("GET", None));
+        assert!(next.headers.unwrap().contains_key("Authorization"));
+
+        let next = redirect_request(&request, &response(307, "https://other.example/login")).unwrap();
+        assert_eq!((next.method.as_str(), next.body.as_deref()), ("POST", Some("user=me")));
+        let headers = next.headers.unwrap();
+        assert!(!headers.contains_key("Authorization"));
+        assert!(headers.contains_key("Accept"));
+
+        assert!(redirect_request(&request, &response(200, "/home")).is_none());
+    }
+
+    #[test]
+    fn cookie_jar_stores_matches_and_expires_cookies() {
+        let login = Url::parse("https://app.example.com/account/login").unwrap();
+        let now = 1_700_000_000;
+        let mut jar = Vec::new();
+
+        update_cookie_jar(&mut jar, &[
+            "session=abc; Path=/; Secure; HttpOnly".to_string(),
+            "theme=dark; Domain=.example.com; Path=/; Max-Age=60".to_string(),
+            "scoped=1".to_string(),
+            "evil=1; Domain=other.com".to_string(),
+            "wide=1; Domain=com".to_string(),
+            "wider=1; Domain=.com.".to_string(),
+        ], &login, now);
+
+        assert_eq!(jar.len(), 3);
+        let api = Url::parse("https://api.example.com/").unwrap();
+        assert_eq!(cookie_header(&jar, &api, now).as_deref(), Some("theme=dark"));
+        let account = Url::parse("https://app.example.com/account/settings").unwrap();
+        assert_eq!(cookie_header(&jar, &account, now).as_deref(), Some("scoped=1; session=abc; theme=dark"));
+        let insecure = Url::parse("http://app.example.com/").unwrap();
+        assert_eq!(cookie_header(&jar, &insecure, now).as_deref(), Some("theme=dark"));
+        assert_eq!(cookie_header(&jar, &api, now + 120), None);
+
+        let local = parse_set_cookie("dev=1; Domain=localhost", &Url::parse("http://localhost:8080/").unwrap(), now).unwrap();
+        assert!(local.host_only && local.domain == "localhost");
+
+        // An expired Set-Cookie deletes the stored cookie
+        update_cookie_jar(&mut jar, &["session=; Path=/; Expires=Thu, 01 Jan 1970 00:00:00 GMT".to_string()], &login, now);
+        assert!(jar.iter().all(|cookie| cookie.name != "session"));
+    }
+}
@@ -5,7 +5,7 @@ use std::fs;
 use std::path::PathBuf;
 use tauri::AppHandle;
 use uuid::Uuid;
-use super::{ImportResult, utils::*};
+use super::{ImportResult, file_types::detect_app_file_type, search_index::update_search_index, utils::*};
 
 #[tauri::command]
 pub async fn import_file(
@@ -85,7 +85,7 @@ pub async fn import_file(
     )?;
     
     let extension = get_file_extension(&final_dest_path);
-    let is_binary = is_binary_file(&final_dest_path);
+    let file_type = detect_app_file_type(&app, &final_dest_path);
     let file_id = Uuid::new_v4().to_string();
     let final_name = final_dest_path.file_name()
         .unwrap_or_else(|| std::ffi::OsStr::new(&file_name))
@@ -97,8 +97,10 @@ pub async fn import_file(
         .map(|p| p.to_string_lossy().to_string())
         .unwrap_or_else(|_| final_name.clone());
     
-    println!("📥 Imported file: {} -> {:?} (size: {} bytes, binary: {})", 
-             source_path, final_dest_path, metadata.len(), is_binary);
+    update_search_index(&app, &project_id, &[&final_dest_path]);
+    
+    println!("📥 Imported file: {} -> {:?} (size: {} bytes, type: {}, binary: {})", 
+             source_path, final_dest_path, metadata.len(), file_type.mime, file_type.is_binary);
     println!("📥 Relative path: {}", relative_file_path);
     
     Ok(ImportResult {
@@ -107,8 +109,10 @@ pub async fn import_file(
         r#type: "file".to_string(),
         extension,
         size: metadata.len(),
-        is_binary,
+        is_binary: file_type.is_binary,
         file_path: Some(relative_file_path),
+        mime_type: Some(file_type.mime),
+        category: Some(file_type.category),
     })
 }
 
@@ -175,6 +179,8 @@ pub async fn import_folder(
         .map(|p| p.to_string_lossy().to_string())
         .unwrap_or_else(|_| final_name.clone());
     
+    update_search_index(&app, &project_id, &[&final_dest_path]);
+    
     println!("📥 Imported folder: {} -> {:?} (total size: {} bytes)", 
              source_path, final_dest_path, total_size);
     println!("📥 Relative path: {}", relative_file_path);
@@ -187,6 +193,8 @@ pub async fn import_folder(
         size: total_size,
         is_binary: false,
         file_path: Some(relative_file_path),
+        mime_type: None,
+        category: None,
     })
 }
 
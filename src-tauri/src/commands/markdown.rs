@@ -0,0 +1,588 @@
+// src-tauri/src/commands/markdown.rs
+// Main-content extraction and HTML to Markdown / readable text conversion
+
+use std::path::Path;
+use regex::Regex;
+use reqwest::Url;
+use scraper::{ElementRef, Html, Node as HtmlNode, Selector};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use super::{Node, utils::*, web_scraping::{fetch_html, save_project_file}};
+
+// Never part of readable content
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "noscript", "template", "svg", "canvas", "iframe", "object", "embed",
+    "form", "button", "input", "select", "textarea", "nav", "aside", "footer", "dialog", "head",
+];
+const BLOCK_ELEMENTS: &[&str] = &[
+    "p", "div", "section", "article", "main", "header", "figure", "figcaption", "dl", "dt", "dd",
+    "address", "details", "summary",
+];
+const NOISE_PATTERN: &str = r"(?i)(^|[\s_-])(comments?|share|sharing|social|advert|ads?|promo|related|newsletter|cookies?|sidebar|breadcrumbs?|footer|navbar|nav|menu|popup|modal)($|[\s_-])";
+const CONTENT_PATTERN: &str = r"(?i)article|content|entry|main|post|story|text";
+const MIN_CONTENT_SCORE: f64 = 20.0;
+
+#[derive(Debug, Serialize)]
+pub struct ReadableContent {
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub markdown: String,
+    pub text: String,
+    pub word_count: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SaveReadableRequest {
+    pub url: String,
+    pub project_id: String,
+    pub parent_id: Option<String>,
+    pub file_path: Option<String>, // Defaults to the page title; ".md" is added when missing
+    pub selector: Option<String>, // Convert this element instead of the detected main content
+}
+
+struct Renderer {
+    base: Option<Url>,
+    plain: bool, // Readable text instead of Markdown
+    noise: Regex,
+    content: Regex,
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn text_length(element: ElementRef) -> usize {
+    element.text().map(|text| text.trim().len()).sum()
+}
+
+// Appends rendered content without doubling spaces or indenting after a line break
+fn push_inline(out: &mut String, piece: &str) {
+    let mut piece = piece;
+    if out.is_empty() || out.ends_with('\n') {
+        piece = piece.trim_start_matches(' ');
+    } else if out.ends_with(' ') && piece.starts_with(' ') && !piece.trim_start().starts_with('\n') {
+        piece = &piece[1..];
+    }
+    out.push_str(piece);
+}
+
+/// Drops blank-line runs and trailing spaces outside fenced code blocks.
+fn tidy(markdown: &str, keep_blank_lines: bool) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    let mut in_fence = false;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            lines.push(line.trim_end());
+            continue;
+        }
+        if in_fence {
+            lines.push(line);
+            continue;
+        }
+
+        let line = if line.ends_with("  ") && !line.trim().is_empty() {
+            line // Markdown hard break
+        } else {
+            line.trim_end()
+        };
+        if line.is_empty() && (!keep_blank_lines || lines.last().map_or(true, |last| last.is_empty())) {
+            continue;
+        }
+        lines.push(line);
+    }
+
+    lines.join("\n").trim().to_string()
+}
+
+impl Renderer {
+    fn new(base: Option<Url>, plain: bool) -> Self {
+        Renderer {
+            base,
+            plain,
+            noise: Regex::new(NOISE_PATTERN).unwrap(),
+            content: Regex::new(CONTENT_PATTERN).unwrap(),
+        }
+    }
+
+    fn resolve(&self, reference: &str) -> Option<String> {
+        let reference = reference.trim();
+        if reference.is_empty() || reference.starts_with('#') || reference.to_lowercase().starts_with("javascript:") {
+            return None;
+        }
+        match &self.base {
+            Some(base) => base.join(reference).ok().map(|url| url.to_string()),
+            None => Some(reference.to_string()),
+        }
+    }
+
+    fn is_skipped(&self, element: ElementRef) -> bool {
+        let value = element.value();
+        if SKIPPED_ELEMENTS.contains(&value.name())
+            || value.attr("hidden").is_some()
+            || value.attr("aria-hidden") == Some("true")
+        {
+            return true;
+        }
+        let style = value.attr("style").unwrap_or("").replace(' ', "").to_lowercase();
+        if style.contains("display:none") || style.contains("visibility:hidden") {
+            return true;
+        }
+        // Comment threads, share bars and the like inside the content
+        [value.attr("class"), value.attr("id")].iter()
+            .flatten()
+            .any(|names| self.noise.is_match(names) && !self.content.is_match(names))
+    }
+
+    fn children(&self, element: ElementRef) -> String {
+        let mut out = String::new();
+        for child in element.children() {
+            match child.value() {
+                HtmlNode::Text(text) => {
+                    // Whitespace runs collapse to one space, as a browser would show them
+                    let mut normalized = String::new();
+                    for c in text.chars() {
+                        if !c.is_whitespace() {
+                            normalized.push(c);
+                        } else if !normalized.ends_with(' ') {
+                            normalized.push(' ');
+                        }
+                    }
+                    push_inline(&mut out, &normalized);
+                }
+                HtmlNode::Element(_) => {
+                    if let Some(child) = ElementRef::wrap(child) {
+                        push_inline(&mut out, &self.element(child));
+                    }
+                }
+                _ => {}
+            }
+        }
+        out
+    }
+
+    fn block(&self, content: &str) -> String {
+        let content = content.trim();
+        if content.is_empty() {
+            String::new()
+        } else {
+            format!("\n\n{}\n\n", content)
+        }
+    }
+
+    fn element(&self, element: ElementRef) -> String {
+        if self.is_skipped(element) {
+            String::new()
+        } else {
+            self.render(element)
+        }
+    }
+
+    // The chosen root is rendered even when it looks like noise
+    fn render(&self, element: ElementRef) -> String {
+        let name = element.value().name();
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let text = collapse_whitespace(&self.children(element));
+                if self.plain {
+                    self.block(&text)
+                } else {
+                    let level = name[1..].parse::<usize>().unwrap_or(1);
+                    self.block(&format!("{} {}", "#".repeat(level), text))
+                }
+            }
+            "br" => if self.plain { "\n".to_string() } else { "  \n".to_string() },
+            "hr" => if self.plain { "\n\n".to_string() } else { "\n\n---\n\n".to_string() },
+            "pre" => self.code_block(element),
+            "code" => {
+                let code: String = element.text().collect();
+                if self.plain {
+                    code
+                } else if code.contains('`') {
+                    format!("`` {} ``", code)
+                } else {
+                    format!("`{}`", code)
+                }
+            }
+            "strong" | "b" => self.wrap_inline(element, "**"),
+            "em" | "i" => self.wrap_inline(element, "*"),
+            "del" | "s" | "strike" => self.wrap_inline(element, "~~"),
+            "a" => {
+                let text = collapse_whitespace(&self.children(element));
+                match element.value().attr("href").and_then(|href| self.resolve(href)) {
+                    Some(href) if !self.plain && !text.is_empty() => format!("[{}]({})", text, href),
+                    _ => text,
+                }
+            }
+            "img" => {
+                if self.plain {
+                    return String::new();
+                }
+                let alt = element.value().attr("alt").map(collapse_whitespace).unwrap_or_default();
+                match element.value().attr("src").and_then(|src| self.resolve(src)) {
+                    Some(src) => format!("![{}]({})", alt, src),
+                    None => String::new(),
+                }
+            }
+            "ul" | "ol" => self.list(element, name == "ol"),
+            "blockquote" => {
+                let content = tidy(&self.children(element), true);
+                let prefix = if self.plain { "" } else { "> " };
+                let quoted: Vec<String> = content.lines()
+                    .map(|line| if line.is_empty() { prefix.trim_end().to_string() } else { format!("{}{}", prefix, line) })
+                    .collect();
+                self.block(&quoted.join("\n"))
+            }
+            "table" => self.table(element),
+            "li" => self.block(&self.children(element)),
+            _ if BLOCK_ELEMENTS.contains(&name) => self.block(&self.children(element)),
+            _ => self.children(element),
+        }
+    }
+
+    fn wrap_inline(&self, element: ElementRef, marker: &str) -> String {
+        let content = self.children(element);
+        let trimmed = content.trim();
+        if self.plain || trimmed.is_empty() {
+            return content;
+        }
+        // Keep surrounding spaces outside the markers so emphasis still parses
+        let leading = if content.starts_with(' ') { " " } else { "" };
+        let trailing = if content.ends_with(' ') { " " } else { "" };
+        format!("{}{}{}{}{}", leading, marker, trimmed, marker, trailing)
+    }
+
+    fn code_block(&self, element: ElementRef) -> String {
+        let code: String = element.text().collect();
+        let code = code.trim_matches('\n').trim_end();
+        if self.plain {
+            return format!("\n\n{}\n\n", code);
+        }
+
+        // The language usually sits on the <code> child as language-xxx or lang-xxx
+        let code_child = element.children().filter_map(ElementRef::wrap).find(|child| child.value().name() == "code");
+        let language = [Some(element), code_child].iter()
+            .flatten()
+            .filter_map(|element| element.value().attr("class"))
+            .flat_map(|classes| classes.split_whitespace())
+            .find_map(|class| class.strip_prefix("language-").or_else(|| class.strip_prefix("lang-")))
+            .unwrap_or("");
+        let fence = if code.contains("```") { "~~~" } else { "```" };
+
+        format!("\n\n{}{}\n{}\n{}\n\n", fence, language, code, fence)
+    }
+
+    fn list(&self, element: ElementRef, ordered: bool) -> String {
+        let mut number = element.value().attr("start").and_then(|start| start.parse::<usize>().ok()).unwrap_or(1);
+        let mut items = Vec::new();
+
+        for item in element.children().filter_map(ElementRef::wrap) {
+            if item.value().name() != "li" || self.is_skipped(item) {
+                continue;
+            }
+            let marker = match (self.plain, ordered) {
+                (_, true) => format!("{}. ", number),
+                (true, false) => "• ".to_string(),
+                (false, false) => "- ".to_string(),
+            };
+            number += 1;
+
+            // Nested lists and paragraphs stay inside the item, indented under its marker
+            let content = tidy(&self.children(item), false);
+            let indent = " ".repeat(marker.chars().count());
+            let lines: Vec<String> = content.lines()
+                .enumerate()
+                .map(|(index, line)| if index == 0 { format!("{}{}", marker, line) } else { format!("{}{}", indent, line) })
+                .collect();
+            if !lines.is_empty() {
+                items.push(lines.join("\n"));
+            }
+        }
+
+        self.block(&items.join("\n"))
+    }
+
+    fn table(&self, element: ElementRef) -> String {
+        let row_selector = Selector::parse("tr").unwrap();
+        let rows: Vec<Vec<String>> = element.select(&row_selector)
+            .map(|row| {
+                row.children()
+                    .filter_map(ElementRef::wrap)
+                    .filter(|cell| matches!(cell.value().name(), "th" | "td"))
+                    .map(|cell| collapse_whitespace(&tidy(&self.children(cell), false)).replace('|', "\\|"))
+                    .collect::<Vec<String>>()
+            })
+            .filter(|cells| !cells.is_empty())
+            .collect();
+        if rows.is_empty() {
+            return String::new();
+        }
+
+        if self.plain {
+            let lines: Vec<String> = rows.iter().map(|cells| cells.join("\t")).collect();
+            return self.block(&lines.join("\n"));
+        }
+
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let line = |cells: &[String]| {
+            let mut cells = cells.to_vec();
+            cells.resize(columns, String::new());
+            format!("| {} |", cells.join(" | "))
+        };
+        let mut lines = vec![line(&rows[0]), format!("|{}", " --- |".repeat(columns))];
+        lines.extend(rows[1..].iter().map(|cells| line(cells)));
+        self.block(&lines.join("\n"))
+    }
+}
+
+/// Picks the element holding the page's main content: a single <article>,
+/// <main>, or else the container whose paragraphs score best.
+fn main_content(document: &Html) -> ElementRef<'_> {
+    let select = |css: &str| -> Vec<ElementRef> {
+        document.select(&Selector::parse(css).unwrap()).collect()
+    };
+
+    let articles = select("article");
+    if articles.len() == 1 && text_length(articles[0]) > 0 {
+        return articles[0];
+    }
+    if let Some(main) = select("main, [role=main]").into_iter().max_by_key(|main| text_length(*main)) {
+        return main;
+    }
+
+    // Score each paragraph's parent (and half to its grandparent) by text and commas
+    let mut scores: Vec<(ElementRef, f64)> = Vec::new();
+    fn add_score<'a>(scores: &mut Vec<(ElementRef<'a>, f64)>, element: ElementRef<'a>, score: f64) {
+        match scores.iter_mut().find(|(candidate, _)| candidate.id() == element.id()) {
+            Some((_, total)) => *total += score,
+            None => scores.push((element, score)),
+        }
+    }
+    for paragraph in select("p, pre, td") {
+        let text = paragraph.text().collect::<String>();
+        let length = text.trim().len();
+        if length < 25 {
+            continue;
+        }
+        let score = 1.0 + text.matches(',').count() as f64 + (length as f64 / 100.0).min(3.0);
+        let parent = paragraph.parent().and_then(ElementRef::wrap);
+        if let Some(parent) = parent {
+            add_score(&mut scores, parent, score);
+            if let Some(grandparent) = parent.parent().and_then(ElementRef::wrap) {
+                add_score(&mut scores, grandparent, score / 2.0);
+            }
+        }
+    }
+
+    let link_selector = Selector::parse("a").unwrap();
+    let best = scores.into_iter()
+        .map(|(element, score)| {
+            // Navigation-heavy containers are mostly link text
+            let total = text_length(element).max(1) as f64;
+            let links: usize = element.select(&link_selector).map(text_length).sum();
+            (element, score * (1.0 - links as f64 / total))
+        })
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+
+    match best {
+        Some((element, score)) if score >= MIN_CONTENT_SCORE => element,
+        _ => select("body").into_iter().next().unwrap_or_else(|| document.root_element()),
+    }
+}
+
+fn page_title(document: &Html) -> Option<String> {
+    ["title", "h1"].iter()
+        .filter_map(|css| document.select(&Selector::parse(css).unwrap()).next())
+        .map(|element| collapse_whitespace(&element.text().collect::<String>()))
+        .find(|title| !title.is_empty())
+}
+
+/// Converts the main content of a page (or the first match of `selector`) to
+/// Markdown and readable text. Relative links resolve against `page_url`.
+fn convert_html(html_content: &str, page_url: Option<&str>, selector: Option<&str>) -> Result<ReadableContent, String> {
+    let document = Html::parse_document(html_content);
+    let root = match selector {
+        Some(css) => {
+            let selector = Selector::parse(css).map_err(|e| format!("Invalid CSS selector: {}", e))?;
+            document.select(&selector).next()
+                .ok_or_else(|| format!("No element matches {}", css))?
+        }
+        None => main_content(&document),
+    };
+    let base = page_url.and_then(|url| Url::parse(url).ok());
+
+    let markdown = tidy(&Renderer::new(base.clone(), false).render(root), true);
+    let text = tidy(&Renderer::new(base, true).render(root), true);
+    Ok(ReadableContent {
+        title: page_title(&document),
+        url: page_url.map(str::to_string),
+        word_count: text.split_whitespace().count(),
+        markdown,
+        text,
+    })
+}
+
+fn note_file_name(title: Option<&str>) -> String {
+    let slug: String = title.unwrap_or("page")
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-");
+    let slug: String = slug.chars().take(80).collect();
+    format!("{}.md", if slug.is_empty() { "page" } else { &slug })
+}
+
+#[tauri::command]
+pub async fn convert_html_to_markdown(
+    html: String,
+    base_url: Option<String>,
+    selector: Option<String>,
+) -> Result<ReadableContent, String> {
+    convert_html(&html, base_url.as_deref(), selector.as_deref())
+}
+
+/// Fetches a page and saves its readable content as a Markdown note.
+#[tauri::command]
+pub async fn save_webpage_as_markdown(app: AppHandle, request: SaveReadableRequest) -> Result<Node, String> {
+    println!("📝 Saving readable page: {}", request.url);
+    let (html_content, final_url) = fetch_html(&app, &request.url).await?;
+    let content = convert_html(&html_content, Some(&final_url), request.selector.as_deref())?;
+
+    let file_path = match request.file_path {
+        Some(file_path) => match get_file_extension(Path::new(&file_path)).as_deref() {
+            Some("md") | Some("markdown") => file_path,
+            None => format!("{}.md", file_path),
+            Some(_) => return Err("Readable pages are saved as .md files".to_string()),
+        },
+        None => note_file_name(content.title.as_deref()),
+    };
+
+    let mut note = String::new();
+    if let Some(title) = &content.title {
+        if !content.markdown.starts_with("# ") {
+            note.push_str(&format!("# {}\n\n", title));
+        }
+    }
+    note.push_str(&format!("> Source: <{}> (saved {})\n\n", final_url, chrono::Local::now().format("%Y-%m-%d %H:%M")));
+    note.push_str(&content.markdown);
+    note.push('\n');
+
+    save_project_file(&app, &request.project_id, request.parent_id.as_deref(), &file_path, note.as_bytes())
+}
+
+/// Markdown or readable text of a fetched page, for `scrape_webpage`'s format option.
+pub(crate) fn readable_format(
+    html_content: &str,
+    page_url: &str,
+    selector: Option<&str>,
+    format: &str,
+) -> Result<String, String> {
+    let content = convert_html(html_content, Some(page_url), selector)?;
+    match format {
+        "markdown" => Ok(content.markdown),
+        "text" => Ok(content.text),
+        other => Err(format!("Unknown format: {}", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARTICLE: &str = r#"<html><head><title>Field notes</title><style>p { color: red }</style></head><body>
+        <nav><a href="/">Home</a><a href="/about">About</a></nav>
+        <article>
+            <h1>Soil  samples</h1>
+            <p>We took <strong>twelve</strong> samples, see <a href="/data/soil.csv">the data</a>.<br>Second line.</p>
+            <script>track()</script>
+            <ul><li>Clay <em>mostly</em><ul><li>wet</li></ul></li><li>Sand</li></ul>
+            <ol start="3"><li>Dry</li></ol>
+            <pre><code class="language-python">import pandas
+
+df = pandas.read_csv("soil.csv")</code></pre>
+            <blockquote><p>Measure twice.</p></blockquote>
+            <table><tr><th>Site</th><th>pH</th></tr><tr><td>A|1</td><td>6.5</td></tr></table>
+            <div class="post-footer"><p>Filed under soil.</p></div>
+            <div class="share-buttons"><a href="https://social.example/share">Share</a></div>
+            <img src="img/site.jpg" alt="Site A">
+        </article>
+        <footer>© 2024</footer>
+    </body></html>"#;
+
+    #[test]
+    fn article_converts_to_markdown() {
+        let content = convert_html(ARTICLE, Some("https://example.com/notes/soil"), None).unwrap();
+
+        assert_eq!(content.title.as_deref(), Some("Field notes"));
+        assert_eq!(content.markdown, [
+            "# Soil samples",
+            "",
+            "We took **twelve** samples, see [the data](https://example.com/data/soil.csv).  ",
+            "Second line.",
+            "",
+            "- Clay *mostly*",
+            "  - wet",
+            "- Sand",
+            "",
+            "3. Dry",
+            "",
+            "```python",
+            "import pandas",
+            "",
+            "df = pandas.read_csv(\"soil.csv\")",
+            "```",
+            "",
+            "> Measure twice.",
+            "",
+            "| Site | pH |",
+            "| --- | --- |",
+            "| A\\|1 | 6.5 |",
+            "",
+            "Filed under soil.",
+            "",
+            "![Site A](https://example.com/notes/img/site.jpg)",
+        ].join("\n"));
+    }
+
+    #[test]
+    fn readable_text_drops_markup() {
+        let content = convert_html(ARTICLE, None, None).unwrap();
+
+        assert!(content.text.starts_with("Soil samples\n\nWe took twelve samples, see the data.\nSecond line."));
+        assert!(content.text.contains("• Clay mostly\n  • wet"));
+        assert!(!content.text.contains("track()"));
+        assert!(!content.text.contains("Share"));
+        assert_eq!(content.word_count, content.text.split_whitespace().count());
+    }
+
+    #[test]
+    fn main_content_is_found_without_article_markup() {
+        let paragraph = "This paragraph has plenty of words, commas, and sentences, so it scores well as content.";
+        let html = format!(
+            r#"<body><div id="menu"><p><a href="/a">A very long navigation link text here</a></p></div>
+               <div class="wrapper"><div class="post">{p}<p>{p}</p><p>{p}</p><p>{p}</p></div></div></body>"#,
+            p = format!("<p>{}</p>", paragraph)
+        );
+
+        let content = convert_html(&html, None, None).unwrap();
+
+        assert!(content.markdown.starts_with(paragraph));
+        assert!(!content.markdown.contains("navigation"));
+    }
+
+    #[test]
+    fn selector_overrides_detection_and_names_are_slugged() {
+        let content = convert_html(ARTICLE, None, Some("blockquote")).unwrap();
+        assert_eq!(content.markdown, "> Measure twice.");
+        let content = convert_html(ARTICLE, None, Some("nav")).unwrap();
+        assert_eq!(content.markdown, "[Home](/)[About](/about)");
+        assert!(convert_html(ARTICLE, None, Some("video")).is_err());
+
+        assert_eq!(note_file_name(Some("Soil: samples / 2024?")), "Soil-samples-2024.md");
+        assert_eq!(note_file_name(None), "page.md");
+    }
+}
@@ -11,6 +11,32 @@ pub mod dialogs;
 pub mod execution;
 pub mod utilities;
 pub mod sync;
+pub mod sessions;
+pub mod queue;
+pub mod pipelines;
+pub mod history;
+pub mod artifacts;
+pub mod runners;
+pub mod scheduler;
+pub mod environment;
+pub mod web_scraping;
+pub mod extraction;
+pub mod crawler;
+pub mod http_collections;
+pub mod http_cache;
+pub mod markdown;
+pub mod search;
+pub mod search_index;
+pub mod undo_journal;
+pub mod replace;
+pub mod file_finder;
+pub mod text_encoding;
+pub mod file_signatures;
+pub mod hex_viewer;
+pub mod file_types;
+
+#[cfg(test)]
+mod test_server;
 
 // Re-export all commands for easy access in main.rs
 pub use project_management::*;
@@ -20,6 +46,26 @@ pub use import_export::*;
 pub use dialogs::*;
 pub use execution::*;
 pub use utilities::*;
+pub use sessions::*;
+pub use queue::*;
+pub use pipelines::*;
+pub use history::*;
+pub use runners::*;
+pub use scheduler::*;
+pub use environment::*;
+pub use web_scraping::*;
+pub use extraction::*;
+pub use crawler::*;
+pub use http_collections::*;
+pub use http_cache::*;
+pub use markdown::*;
+pub use search::*;
+pub use search_index::*;
+pub use undo_journal::*;
+pub use replace::*;
+pub use file_finder::*;
+pub use hex_viewer::*;
+pub use file_types::*;
 
 // FIXED: Use glob import for sync module to get the __cmd__ macros
 pub use sync::*;
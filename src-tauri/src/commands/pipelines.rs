@@ -0,0 +1,579 @@
+// src-tauri/src/commands/pipelines.rs
+// DAG pipelines: ordered script steps declared in a project's pipeline.json
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::io;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+use super::{ExecutionResult, execution::{execute_and_record, ExecutionRequest}, utils::*};
+
+pub const PIPELINE_FILE_NAME: &str = "pipeline.json";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PipelineStep {
+    pub id: String,
+    pub file: String, // Script path relative to the project root
+    #[serde(default)]
+    pub inputs: Vec<String>,
+    #[serde(default)]
+    pub outputs: Vec<String>,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PipelineDefinition {
+    pub name: Option<String>,
+    pub steps: Vec<PipelineStep>,
+}
+
+// Last successful run of each step, used to skip unchanged work
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct PipelineState {
+    steps: HashMap<String, StepRunRecord>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct StepRunRecord {
+    fingerprint: HashMap<String, String>,
+    finished_at: i64,
+    duration_ms: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PipelineStepStatus {
+    pub id: String,
+    pub file: String,
+    pub status: String, // "pending", "up_to_date", "success", "failed", "skipped" or "blocked"
+    pub reason: Option<String>,
+    pub last_success_at: Option<i64>,
+    pub result: Option<ExecutionResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PipelineInfo {
+    pub name: Option<String>,
+    pub order: Vec<String>,
+    pub steps: Vec<PipelineStepStatus>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PipelineRunResult {
+    pub success: bool,
+    pub executed: usize,
+    pub skipped: usize,
+    pub steps: Vec<PipelineStepStatus>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PipelineProgressEvent {
+    pub project_id: String,
+    pub step_id: String,
+    pub status: String,
+    pub index: usize,
+    pub total: usize,
+}
+
+fn load_pipeline(project_dir: &Path) -> Result<PipelineDefinition, String> {
+    let pipeline_file = project_dir.join(PIPELINE_FILE_NAME);
+    if !pipeline_file.exists() {
+        return Err(format!("No {} found in project", PIPELINE_FILE_NAME));
+    }
+
+    let content = safe_file_operation(
+        || fs::read_to_string(&pipeline_file),
+        "Failed to read pipeline file"
+    )?;
+
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid pipeline file: {}", e))
+}
+
+fn get_pipeline_state_file(app: &AppHandle, project_id: &str) -> Result<std::path::PathBuf, String> {
+    Ok(get_app_data_dir(app)?.join("pipelines").join(format!("{}.json", project_id)))
+}
+
+fn load_pipeline_state(app: &AppHandle, project_id: &str) -> PipelineState {
+    get_pipeline_state_file(app, project_id)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_pipeline_state(app: &AppHandle, project_id: &str, state: &PipelineState) -> Result<(), String> {
+    let state_file = get_pipeline_state_file(app, project_id)?;
+    ensure_parent_dir(&state_file)
+        .map_err(|e| format!("Failed to create pipeline state directory: {}", e))?;
+
+    let json = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize pipeline state: {}", e))?;
+
+    safe_file_operation(
+        || fs::write(&state_file, json),
+        "Failed to write pipeline state"
+    )
+}
+
+// Step id -> ids of the steps it waits for
+type StepDependencies = HashMap<String, Vec<String>>;
+
+/// Orders steps so every step runs after its dependencies. A step depends on the
+/// steps it lists in `depends_on` and on any step that produces one of its inputs.
+fn resolve_order(pipeline: &PipelineDefinition) -> Result<(Vec<String>, StepDependencies), String> {
+    let mut step_ids = HashSet::new();
+    for step in &pipeline.steps {
+        if !step_ids.insert(step.id.as_str()) {
+            return Err(format!("Duplicate pipeline step id: {}", step.id));
+        }
+        // Scripts, inputs and outputs must all stay inside the project
+        for path in std::iter::once(&step.file).chain(&step.inputs).chain(&step.outputs) {
+            resolve_project_path(Path::new(""), path)
+                .map_err(|e| format!("Step '{}': {}", step.id, e))?;
+        }
+    }
+
+    let mut producers: HashMap<String, &str> = HashMap::new();
+    for step in &pipeline.steps {
+        for output in &step.outputs {
+            if let Some(other) = producers.insert(normalize_path(output), &step.id) {
+                return Err(format!("Output '{}' is produced by both '{}' and '{}'", output, other, step.id));
+            }
+        }
+    }
+
+    let mut dependencies: StepDependencies = HashMap::new();
+    for step in &pipeline.steps {
+        let mut deps: Vec<String> = Vec::new();
+        for dep in &step.depends_on {
+            if !step_ids.contains(dep.as_str()) {
+                return Err(format!("Step '{}' depends on unknown step '{}'", step.id, dep));
+            }
+            deps.push(dep.clone());
+        }
+        for input in &step.inputs {
+            if let Some(producer) = producers.get(&normalize_path(input)) {
+                if *producer != step.id {
+                    deps.push(producer.to_string());
+                }
+            }
+        }
+        deps.sort();
+        deps.dedup();
+        dependencies.insert(step.id.clone(), deps);
+    }
+
+    // Kahn's algorithm, seeded in declaration order so the result is stable
+    let mut remaining: HashMap<&str, usize> = dependencies.iter()
+        .map(|(id, deps)| (id.as_str(), deps.len()))
+        .collect();
+    let mut ready: VecDeque<&str> = pipeline.steps.iter()
+        .map(|step| step.id.as_str())
+        .filter(|id| remaining[id] == 0)
+        .collect();
+    let mut order = Vec::new();
+
+    while let Some(id) = ready.pop_front() {
+        order.push(id.to_string());
+        for step in &pipeline.steps {
+            if dependencies[&step.id].iter().any(|dep| dep == id) {
+                let count = remaining.get_mut(step.id.as_str()).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    ready.push_back(&step.id);
+                }
+            }
+        }
+    }
+
+    if order.len() != pipeline.steps.len() {
+        let cyclic: Vec<&str> = pipeline.steps.iter()
+            .map(|step| step.id.as_str())
+            .filter(|id| !order.iter().any(|done| done == id))
+            .collect();
+        return Err(format!("Pipeline has a dependency cycle between: {}", cyclic.join(", ")));
+    }
+
+    Ok((order, dependencies))
+}
+
+fn normalize_path(path: &str) -> String {
+    path.replace('\\', "/").trim_start_matches("./").to_string()
+}
+
+// File count, size and content hash of a file, or of everything below a
+// directory. Content rather than mtime, so touching or re-copying an input
+// without changing it doesn't rerun the step.
+fn describe_path(path: &Path) -> String {
+    fn walk(path: &Path, relative: &str, count: &mut u64, size: &mut u64, hasher: &mut Sha256) {
+        if let Ok(metadata) = fs::metadata(path) {
+            if metadata.is_dir() {
+                if let Ok(entries) = fs::read_dir(path) {
+                    // Sorted so the hash doesn't depend on directory listing order
+                    let mut names: Vec<String> = entries.flatten()
+                        .map(|entry| entry.file_name().to_string_lossy().to_string())
+                        .collect();
+                    names.sort();
+                    for name in names {
+                        walk(&path.join(&name), &format!("{}/{}", relative, name), count, size, hasher);
+                    }
+                }
+            } else {
+                *count += 1;
+                *size += metadata.len();
+                hasher.update(relative.as_bytes());
+                hasher.update([0]);
+                if let Ok(mut file) = fs::File::open(path) {
+                    let _ = io::copy(&mut file, hasher);
+                }
+            }
+        }
+    }
+
+    if !path.exists() {
+        return "missing".to_string();
+    }
+
+    let (mut count, mut size, mut hasher) = (0, 0, Sha256::new());
+    walk(path, "", &mut count, &mut size, &mut hasher);
+    let hash: String = hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("{}:{}:{}", count, size, hash)
+}
+
+fn step_fingerprint(project_dir: &Path, step: &PipelineStep) -> HashMap<String, String> {
+    std::iter::once(&step.file)
+        .chain(step.inputs.iter())
+        .map(|path| (normalize_path(path), describe_path(&project_dir.join(path))))
+        .collect()
+}
+
+// Why a step needs to run, or None if its last successful run is still current
+fn stale_reason(
+    project_dir: &Path,
+    step: &PipelineStep,
+    record: Option<&StepRunRecord>,
+    fingerprint: &HashMap<String, String>,
+) -> Option<String> {
+    let record = match record {
+        Some(record) => record,
+        None => return Some("Never run successfully".to_string()),
+    };
+
+    if let Some(output) = step.outputs.iter().find(|output| !project_dir.join(output).exists()) {
+        return Some(format!("Output '{}' is missing", output));
+    }
+
+    let mut changed: Vec<&String> = fingerprint.iter()
+        .filter(|(path, description)| record.fingerprint.get(*path) != Some(*description))
+        .map(|(path, _)| path)
+        .collect();
+    if changed.is_empty() {
+        return None;
+    }
+
+    changed.sort();
+    Some(format!("Changed since last run: {}", changed.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(", ")))
+}
+
+#[tauri::command]
+pub async fn get_pipeline(
+    app: AppHandle,
+    project_id: String,
+) -> Result<PipelineInfo, String> {
+    let project_dir = get_project_dir(&app, &project_id)?;
+    let pipeline = load_pipeline(&project_dir)?;
+    let (order, _) = resolve_order(&pipeline)?;
+    let state = load_pipeline_state(&app, &project_id);
+
+    let steps = order.iter()
+        .filter_map(|id| pipeline.steps.iter().find(|step| &step.id == id))
+        .map(|step| {
+            let record = state.steps.get(&step.id);
+            let fingerprint = step_fingerprint(&project_dir, step);
+            let reason = stale_reason(&project_dir, step, record, &fingerprint);
+            PipelineStepStatus {
+                id: step.id.clone(),
+                file: step.file.clone(),
+                status: if reason.is_some() { "pending" } else { "up_to_date" }.to_string(),
+                reason,
+                last_success_at: record.map(|record| record.finished_at),
+                result: None,
+            }
+        })
+        .collect();
+
+    Ok(PipelineInfo {
+        name: pipeline.name,
+        order,
+        steps,
+    })
+}
+
+#[tauri::command]
+pub async fn save_pipeline(
+    app: AppHandle,
+    project_id: String,
+    pipeline: PipelineDefinition,
+) -> Result<(), String> {
+    // Refuse definitions that could never run
+    resolve_order(&pipeline)?;
+
+    let project_dir = get_project_dir(&app, &project_id)?;
+    let json = serde_json::to_string_pretty(&pipeline)
+        .map_err(|e| format!("Failed to serialize pipeline: {}", e))?;
+
+    safe_file_operation(
+        || fs::create_dir_all(&project_dir),
+        "Failed to create project directory"
+    )?;
+    safe_file_operation(
+        || fs::write(project_dir.join(PIPELINE_FILE_NAME), json),
+        "Failed to write pipeline file"
+    )?;
+
+    println!("💾 PIPELINE: Saved {} steps for project {}", pipeline.steps.len(), project_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn run_pipeline(
+    app: AppHandle,
+    project_id: String,
+    force: Option<bool>,
+) -> Result<PipelineRunResult, String> {
+    execute_pipeline(&app, &project_id, force.unwrap_or(false), "pipeline").await
+}
+
+/// Runs the stale steps of a project's pipeline; `trigger` is recorded in execution history.
+pub(crate) async fn execute_pipeline(
+    app: &AppHandle,
+    project_id: &str,
+    force: bool,
+    trigger: &str,
+) -> Result<PipelineRunResult, String> {
+    let project_id = project_id.to_string();
+    let project_dir = get_project_dir(app, &project_id)?;
+    let pipeline = load_pipeline(&project_dir)?;
+    let (order, dependencies) = resolve_order(&pipeline)?;
+    let mut state = load_pipeline_state(app, &project_id);
+    let start_time = std::time::Instant::now();
+
+    println!("🔗 PIPELINE: Running {} steps for project {}", order.len(), project_id);
+
+    let mut statuses: Vec<PipelineStepStatus> = Vec::new();
+    let mut executed: HashSet<String> = HashSet::new();
+    let mut failed: HashSet<String> = HashSet::new();
+
+    for (index, step_id) in order.iter().enumerate() {
+        let step = pipeline.steps.iter().find(|step| &step.id == step_id).unwrap();
+        let deps = &dependencies[step_id];
+        let emit = |status: &str| {
+            let _ = app.emit_all("pipeline-progress", PipelineProgressEvent {
+                project_id: project_id.clone(),
+                step_id: step_id.clone(),
+                status: status.to_string(),
+                index,
+                total: order.len(),
+            });
+        };
+
+        let mut status = PipelineStepStatus {
+            id: step.id.clone(),
+            file: step.file.clone(),
+            status: String::new(),
+            reason: None,
+            last_success_at: state.steps.get(step_id).map(|record| record.finished_at),
+            result: None,
+        };
+
+        if let Some(dep) = deps.iter().find(|dep| failed.contains(*dep)) {
+            status.status = "blocked".to_string();
+            status.reason = Some(format!("Upstream step '{}' did not succeed", dep));
+            failed.insert(step_id.clone());
+            emit(&status.status);
+            statuses.push(status);
+            continue;
+        }
+
+        let fingerprint = step_fingerprint(&project_dir, step);
+        let reason = if force {
+            Some("Forced run".to_string())
+        } else if let Some(dep) = deps.iter().find(|dep| executed.contains(*dep)) {
+            Some(format!("Upstream step '{}' ran", dep))
+        } else {
+            stale_reason(&project_dir, step, state.steps.get(step_id), &fingerprint)
+        };
+
+        let reason = match reason {
+            Some(reason) => reason,
+            None => {
+                println!("⏭️ PIPELINE: {} is up to date", step_id);
+                status.status = "up_to_date".to_string();
+                emit(&status.status);
+                statuses.push(status);
+                continue;
+            }
+        };
+
+        println!("▶️ PIPELINE: Running {} ({})", step_id, reason);
+        emit("running");
+
+        let outcome = execute_and_record(app, ExecutionRequest {
+            node_id: None,
+            project_id: project_id.clone(),
+            file_path: step.file.clone(),
+            trigger: trigger.to_string(),
+            database: None,
+        }).await;
+
+        status.reason = Some(reason);
+        match outcome {
+            Ok(result) if result.success => {
+                // Fingerprint again so the step's own writes to its inputs don't count as changes
+                state.steps.insert(step_id.clone(), StepRunRecord {
+                    fingerprint: step_fingerprint(&project_dir, step),
+                    finished_at: chrono::Utc::now().timestamp(),
+                    duration_ms: result.duration_ms,
+                });
+                save_pipeline_state(app, &project_id, &state)?;
+
+                status.status = "success".to_string();
+                status.last_success_at = state.steps.get(step_id).map(|record| record.finished_at);
+                status.result = Some(result);
+                executed.insert(step_id.clone());
+            }
+            Ok(result) => {
+                status.status = "failed".to_string();
+                status.result = Some(result);
+                failed.insert(step_id.clone());
+            }
+            Err(e) => {
+                status.status = "failed".to_string();
+                status.reason = Some(e);
+                failed.insert(step_id.clone());
+            }
+        }
+
+        emit(&status.status);
+        statuses.push(status);
+    }
+
+    let duration = start_time.elapsed().as_millis() as u64;
+    let skipped = statuses.iter().filter(|status| status.status == "up_to_date").count();
+    let ran = statuses.iter()
+        .filter(|status| status.status == "success" || status.status == "failed")
+        .count();
+
+    println!("🏁 PIPELINE: {} executed, {} up to date, {} failed/blocked in {}ms",
+             executed.len(), skipped, failed.len(), duration);
+
+    Ok(PipelineRunResult {
+        success: failed.is_empty(),
+        executed: ran,
+        skipped,
+        steps: statuses,
+        duration_ms: duration,
+    })
+}
+
+#[tauri::command]
+pub async fn reset_pipeline_state(
+    app: AppHandle,
+    project_id: String,
+) -> Result<(), String> {
+    let state_file = get_pipeline_state_file(&app, &project_id)?;
+    if state_file.exists() {
+        safe_file_operation(
+            || fs::remove_file(&state_file),
+            "Failed to remove pipeline state"
+        )?;
+    }
+
+    println!("🧹 PIPELINE: Reset run state for project {}", project_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(id: &str, inputs: &[&str], outputs: &[&str], depends_on: &[&str]) -> PipelineStep {
+        let strings = |items: &[&str]| items.iter().map(|item| item.to_string()).collect();
+        PipelineStep {
+            id: id.to_string(),
+            file: format!("{}.py", id),
+            inputs: strings(inputs),
+            outputs: strings(outputs),
+            depends_on: strings(depends_on),
+        }
+    }
+
+    fn pipeline(steps: Vec<PipelineStep>) -> PipelineDefinition {
+        PipelineDefinition { name: None, steps }
+    }
+
+    #[test]
+    fn steps_run_after_producers_and_declared_dependencies() {
+        let (order, dependencies) = resolve_order(&pipeline(vec![
+            step("report", &["./data/clean.csv"], &["report.html"], &["notify"]),
+            step("clean", &["data/raw.csv"], &["data/clean.csv"], &[]),
+            step("notify", &[], &[], &[]),
+        ])).unwrap();
+        assert_eq!(order, vec!["clean", "notify", "report"]);
+        assert_eq!(dependencies["report"], vec!["clean", "notify"]);
+    }
+
+    #[test]
+    fn invalid_pipelines_are_rejected() {
+        let cycle = resolve_order(&pipeline(vec![
+            step("a", &["b.csv"], &["a.csv"], &[]),
+            step("b", &["a.csv"], &["b.csv"], &[]),
+            step("c", &[], &[], &[]),
+        ])).unwrap_err();
+        assert_eq!(cycle, "Pipeline has a dependency cycle between: a, b");
+
+        let missing = resolve_order(&pipeline(vec![step("a", &[], &[], &["ghost"])])).unwrap_err();
+        assert_eq!(missing, "Step 'a' depends on unknown step 'ghost'");
+
+        let duplicate = resolve_order(&pipeline(vec![step("a", &[], &[], &[]), step("a", &[], &[], &[])])).unwrap_err();
+        assert_eq!(duplicate, "Duplicate pipeline step id: a");
+
+        let clash = resolve_order(&pipeline(vec![step("a", &[], &["out.csv"], &[]), step("b", &[], &["out.csv"], &[])])).unwrap_err();
+        assert!(clash.starts_with("Output 'out.csv' is produced by both"));
+
+        let escape = resolve_order(&pipeline(vec![step("a", &["../other/secrets.csv"], &[], &[])])).unwrap_err();
+        assert!(escape.starts_with("Step 'a': Path must stay inside the project"));
+        assert!(resolve_order(&pipeline(vec![step("a", &[], &["/tmp/out.csv"], &[])])).is_err());
+    }
+
+    #[test]
+    fn steps_are_stale_until_run_and_when_inputs_or_outputs_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let clean = step("clean", &["data"], &["clean.csv"], &[]);
+        fs::write(dir.path().join("clean.py"), "print(1)").unwrap();
+        fs::create_dir(dir.path().join("data")).unwrap();
+        fs::write(dir.path().join("data/raw.csv"), "a,b\n").unwrap();
+        fs::write(dir.path().join("clean.csv"), "a\n").unwrap();
+
+        let fingerprint = step_fingerprint(dir.path(), &clean);
+        assert_eq!(stale_reason(dir.path(), &clean, None, &fingerprint).unwrap(), "Never run successfully");
+
+        let record = StepRunRecord { fingerprint, finished_at: 0, duration_ms: 0 };
+        let check = || stale_reason(dir.path(), &clean, Some(&record), &step_fingerprint(dir.path(), &clean));
+        assert_eq!(check(), None);
+
+        // Rewriting an input with the same bytes is not a change; new bytes of the same size are
+        fs::write(dir.path().join("data/raw.csv"), "a,b\n").unwrap();
+        assert_eq!(check(), None);
+        fs::write(dir.path().join("data/raw.csv"), "c,d\n").unwrap();
+        assert_eq!(check().unwrap(), "Changed since last run: data");
+        fs::write(dir.path().join("data/raw.csv"), "a,b\n").unwrap();
+
+        fs::remove_file(dir.path().join("clean.csv")).unwrap();
+        assert_eq!(check().unwrap(), "Output 'clean.csv' is missing");
+    }
+}
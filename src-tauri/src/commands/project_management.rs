@@ -46,6 +46,7 @@ pub async fn save_projects(app: AppHandle, data: ProjectData) -> Result<(), Stri
     let json = serde_json::to_string_pretty(&data)
         .map_err(|e| format!("Failed to serialize projects: {}", e))?;
     
+    let _guard = lock_project_data(&app);
     safe_file_operation(
         || fs::write(&projects_file, json),
         "Failed to write projects file"
@@ -0,0 +1,496 @@
+// src-tauri/src/commands/queue.rs
+// Backend execution queue: runs project files as persisted jobs
+
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+use uuid::Uuid;
+use super::{
+    ExecutionResult,
+    execution::{execute_and_record, ExecutionRequest},
+    runners::{find_runner, load_runner_settings},
+    utils::*,
+};
+
+const DEFAULT_MAX_PARALLEL: usize = 4;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QueueItem {
+    pub node_id: String,
+    pub project_id: String,
+    pub file_path: String,
+    pub name: String,
+    #[serde(default)]
+    pub database: Option<String>, // For .sql files, like ExecutionRequest::database
+    pub status: String, // "pending", "running", "success", "failed", "skipped" or "cancelled"
+    pub result: Option<ExecutionResult>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QueueJob {
+    pub id: String,
+    pub mode: String, // "sequential" or "parallel"
+    pub stop_on_failure: bool,
+    pub max_parallel: usize,
+    pub status: String, // "queued", "running", "completed", "failed" or "cancelled"
+    pub items: Vec<QueueItem>,
+    pub created_at: i64,
+    pub started_at: Option<i64>,
+    pub finished_at: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QueueItemRequest {
+    pub node_id: String,
+    pub project_id: String,
+    pub file_path: String,
+    pub name: Option<String>,
+    pub database: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct QueueProgressEvent {
+    pub job_id: String,
+    pub job_status: String,
+    pub item_index: Option<usize>,
+    pub node_id: Option<String>,
+    pub item_status: Option<String>,
+    pub completed: usize,
+    pub total: usize,
+}
+
+#[derive(Default)]
+struct QueueState {
+    jobs: Vec<QueueJob>,
+    worker_active: bool,
+}
+
+/// Shared queue state; jobs are mirrored to `execution_queue.json` on every change.
+#[derive(Default)]
+pub struct ExecutionQueue {
+    state: Mutex<QueueState>,
+}
+
+impl QueueJob {
+    fn is_finished(&self) -> bool {
+        matches!(self.status.as_str(), "completed" | "failed" | "cancelled")
+    }
+
+    fn completed_items(&self) -> usize {
+        self.items.iter()
+            .filter(|item| matches!(item.status.as_str(), "success" | "failed" | "skipped" | "cancelled"))
+            .count()
+    }
+}
+
+fn persist_jobs(app: &AppHandle, jobs: &[QueueJob]) {
+    let result = get_execution_queue_file(app).and_then(|queue_file| {
+        let json = serde_json::to_string_pretty(jobs)
+            .map_err(|e| format!("Failed to serialize execution queue: {}", e))?;
+        safe_file_operation(
+            || fs::write(&queue_file, json),
+            "Failed to write execution queue"
+        )
+    });
+
+    if let Err(e) = result {
+        eprintln!("⚠️ QUEUE: {}", e);
+    }
+}
+
+fn emit_progress(app: &AppHandle, job: &QueueJob, item_index: Option<usize>) {
+    let item = item_index.and_then(|index| job.items.get(index));
+    let _ = app.emit_all("execution-queue-progress", QueueProgressEvent {
+        job_id: job.id.clone(),
+        job_status: job.status.clone(),
+        item_index,
+        node_id: item.map(|item| item.node_id.clone()),
+        item_status: item.map(|item| item.status.clone()),
+        completed: job.completed_items(),
+        total: job.items.len(),
+    });
+}
+
+// Apply a change to a job, then persist the queue and notify the frontend
+fn update_job<F>(app: &AppHandle, job_id: &str, item_index: Option<usize>, change: F)
+where
+    F: FnOnce(&mut QueueJob),
+{
+    let queue = app.state::<ExecutionQueue>();
+    let mut state = queue.state.lock().unwrap();
+
+    if let Some(job) = state.jobs.iter_mut().find(|job| job.id == job_id) {
+        change(job);
+        emit_progress(app, job, item_index);
+    }
+    persist_jobs(app, &state.jobs);
+}
+
+fn is_job_cancelled(app: &AppHandle, job_id: &str) -> bool {
+    let queue = app.state::<ExecutionQueue>();
+    let state = queue.state.lock().unwrap();
+    state.jobs.iter()
+        .find(|job| job.id == job_id)
+        .map(|job| job.status == "cancelled")
+        .unwrap_or(true)
+}
+
+fn skip_item(app: &AppHandle, job_id: &str, index: usize) {
+    update_job(app, job_id, Some(index), |job| {
+        job.items[index].status = "skipped".to_string();
+    });
+}
+
+// Run one queue item and record its outcome. Returns whether it succeeded.
+async fn run_item(app: &AppHandle, job_id: &str, index: usize, item: QueueItem) -> bool {
+    update_job(app, job_id, Some(index), |job| {
+        job.items[index].status = "running".to_string();
+    });
+
+    println!("📋 QUEUE: Running {} ({})", item.name, item.file_path);
+
+    let outcome = execute_and_record(app, ExecutionRequest {
+        node_id: Some(item.node_id.clone()),
+        project_id: item.project_id.clone(),
+        file_path: item.file_path.clone(),
+        trigger: "queue".to_string(),
+        database: item.database.clone(),
+    }).await;
+
+    let success = matches!(&outcome, Ok(result) if result.success);
+    update_job(app, job_id, Some(index), |job| {
+        let entry = &mut job.items[index];
+        entry.status = if success { "success" } else { "failed" }.to_string();
+        match outcome {
+            Ok(result) => entry.result = Some(result),
+            Err(e) => entry.error = Some(e),
+        }
+    });
+
+    success
+}
+
+async fn run_job(app: &AppHandle, job: QueueJob) {
+    println!("🎯 QUEUE: Starting job {} ({} items, {})", job.id, job.items.len(), job.mode);
+
+    // Items that already finished before a restart keep their results
+    let pending: Vec<(usize, QueueItem)> = job.items.iter()
+        .cloned()
+        .enumerate()
+        .filter(|(_, item)| item.status == "pending")
+        .collect();
+
+    if job.mode == "parallel" {
+        let semaphore = Arc::new(tokio::sync::Semaphore::new(job.max_parallel.max(1)));
+        let stop = Arc::new(AtomicBool::new(false));
+        let mut handles = Vec::new();
+
+        for (index, item) in pending {
+            let app = app.clone();
+            let job_id = job.id.clone();
+            let semaphore = semaphore.clone();
+            let stop = stop.clone();
+            let stop_on_failure = job.stop_on_failure;
+
+            handles.push(tauri::async_runtime::spawn(async move {
+                let _permit = semaphore.acquire_owned().await;
+                if stop.load(Ordering::SeqCst) || is_job_cancelled(&app, &job_id) {
+                    skip_item(&app, &job_id, index);
+                    return;
+                }
+                if !run_item(&app, &job_id, index, item).await && stop_on_failure {
+                    stop.store(true, Ordering::SeqCst);
+                }
+            }));
+        }
+
+        for handle in handles {
+            let _ = handle.await;
+        }
+    } else {
+        let mut stopped = false;
+        for (index, item) in pending {
+            if stopped || is_job_cancelled(app, &job.id) {
+                skip_item(app, &job.id, index);
+                continue;
+            }
+            if !run_item(app, &job.id, index, item).await && job.stop_on_failure {
+                stopped = true;
+            }
+        }
+    }
+
+    update_job(app, &job.id, None, |job| {
+        if job.status != "cancelled" {
+            let any_failed = job.items.iter().any(|item| item.status == "failed");
+            job.status = if any_failed { "failed" } else { "completed" }.to_string();
+        }
+        job.finished_at = Some(chrono::Utc::now().timestamp());
+        println!("🏁 QUEUE: Job {} finished: {}", job.id, job.status);
+    });
+}
+
+async fn run_worker(app: AppHandle) {
+    loop {
+        let next = {
+            let queue = app.state::<ExecutionQueue>();
+            let mut state = queue.state.lock().unwrap();
+            let next = state.jobs.iter_mut()
+                .find(|job| job.status == "queued")
+                .map(|job| {
+                    job.status = "running".to_string();
+                    job.started_at.get_or_insert(chrono::Utc::now().timestamp());
+                    job.clone()
+                });
+            if next.is_none() {
+                state.worker_active = false;
+            }
+            persist_jobs(&app, &state.jobs);
+            next
+        };
+
+        match next {
+            Some(job) => {
+                emit_progress(&app, &job, None);
+                run_job(&app, job).await;
+            }
+            None => break,
+        }
+    }
+}
+
+fn start_worker(app: &AppHandle) {
+    let queue = app.state::<ExecutionQueue>();
+    {
+        let mut state = queue.state.lock().unwrap();
+        if state.worker_active {
+            return;
+        }
+        state.worker_active = true;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        run_worker(app).await;
+    });
+}
+
+/// Reload persisted jobs on startup and resume any that were interrupted.
+// Work that was running when the app closed is queued again. A job cancelled
+// while an item was still running never got to finish its items, so they are
+// cancelled now.
+fn recover_jobs(jobs: &mut [QueueJob], now: i64) {
+    for job in jobs.iter_mut() {
+        match job.status.as_str() {
+            "running" => {
+                job.status = "queued".to_string();
+                for item in job.items.iter_mut().filter(|item| item.status == "running") {
+                    item.status = "pending".to_string();
+                }
+            }
+            "cancelled" => {
+                for item in job.items.iter_mut().filter(|item| matches!(item.status.as_str(), "pending" | "running")) {
+                    item.status = "cancelled".to_string();
+                }
+                job.finished_at.get_or_insert(now);
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn restore_execution_queue(app: &AppHandle) {
+    let queue_file = match get_execution_queue_file(app) {
+        Ok(path) if path.exists() => path,
+        _ => return,
+    };
+
+    let mut jobs: Vec<QueueJob> = match fs::read_to_string(&queue_file)
+        .map_err(|e| e.to_string())
+        .and_then(|content| serde_json::from_str(&content).map_err(|e| e.to_string()))
+    {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            eprintln!("⚠️ QUEUE: Failed to load execution queue: {}", e);
+            return;
+        }
+    };
+
+    recover_jobs(&mut jobs, chrono::Utc::now().timestamp());
+
+    let resumable = jobs.iter().filter(|job| job.status == "queued").count();
+    println!("📋 QUEUE: Restored {} jobs ({} to resume)", jobs.len(), resumable);
+
+    app.state::<ExecutionQueue>().state.lock().unwrap().jobs = jobs;
+    if resumable > 0 {
+        start_worker(app);
+    }
+}
+
+#[tauri::command]
+pub async fn enqueue_execution_job(
+    app: AppHandle,
+    queue: State<'_, ExecutionQueue>,
+    items: Vec<QueueItemRequest>,
+    mode: Option<String>,
+    stop_on_failure: Option<bool>,
+    max_parallel: Option<usize>,
+) -> Result<QueueJob, String> {
+    if items.is_empty() {
+        return Err("No files to execute".to_string());
+    }
+
+    let mode = mode.unwrap_or_else(|| "sequential".to_string());
+    if mode != "sequential" && mode != "parallel" {
+        return Err(format!("Unsupported queue mode: {}", mode));
+    }
+
+    let runner_settings = load_runner_settings(&app)?;
+    let mut queue_items = Vec::new();
+    for request in items {
+        let extension = get_file_extension(std::path::Path::new(&request.file_path)).unwrap_or_default();
+        let runner = find_runner(&runner_settings, &extension)
+            .ok_or_else(|| format!("Unsupported file type: {}", request.file_path))?;
+        // Fail now rather than when the item's turn comes
+        if runner.needs_database && request.database.is_none() && runner.default_database.is_none() {
+            return Err(format!("Choose a database file to run {}", request.file_path));
+        }
+
+        let name = request.name.unwrap_or_else(|| request.file_path.clone());
+        queue_items.push(QueueItem {
+            node_id: request.node_id,
+            project_id: request.project_id,
+            file_path: request.file_path,
+            name,
+            database: request.database,
+            status: "pending".to_string(),
+            result: None,
+            error: None,
+        });
+    }
+
+    let job = QueueJob {
+        id: Uuid::new_v4().to_string(),
+        mode,
+        stop_on_failure: stop_on_failure.unwrap_or(true),
+        max_parallel: max_parallel.unwrap_or(DEFAULT_MAX_PARALLEL),
+        status: "queued".to_string(),
+        items: queue_items,
+        created_at: chrono::Utc::now().timestamp(),
+        started_at: None,
+        finished_at: None,
+    };
+
+    {
+        let mut state = queue.state.lock().unwrap();
+        state.jobs.push(job.clone());
+        persist_jobs(&app, &state.jobs);
+    }
+
+    println!("📋 QUEUE: Enqueued job {} with {} items", job.id, job.items.len());
+    emit_progress(&app, &job, None);
+    start_worker(&app);
+
+    Ok(job)
+}
+
+#[tauri::command]
+pub async fn get_execution_queue(
+    queue: State<'_, ExecutionQueue>,
+) -> Result<Vec<QueueJob>, String> {
+    Ok(queue.state.lock().unwrap().jobs.clone())
+}
+
+// Running items are allowed to finish; everything still pending is skipped
+#[tauri::command]
+pub async fn cancel_execution_job(
+    app: AppHandle,
+    queue: State<'_, ExecutionQueue>,
+    job_id: String,
+) -> Result<QueueJob, String> {
+    let mut state = queue.state.lock().unwrap();
+    let job = state.jobs.iter_mut()
+        .find(|job| job.id == job_id)
+        .ok_or_else(|| "Job not found".to_string())?;
+
+    if job.is_finished() {
+        return Err("Job has already finished".to_string());
+    }
+
+    let was_queued = job.status == "queued";
+    job.status = "cancelled".to_string();
+    if was_queued {
+        for item in job.items.iter_mut() {
+            item.status = "skipped".to_string();
+        }
+        job.finished_at = Some(chrono::Utc::now().timestamp());
+    }
+
+    let job = job.clone();
+    emit_progress(&app, &job, None);
+    persist_jobs(&app, &state.jobs);
+
+    println!("⏹️ QUEUE: Cancelled job {}", job_id);
+    Ok(job)
+}
+
+#[tauri::command]
+pub async fn clear_finished_execution_jobs(
+    app: AppHandle,
+    queue: State<'_, ExecutionQueue>,
+) -> Result<usize, String> {
+    let mut state = queue.state.lock().unwrap();
+    let before = state.jobs.len();
+    state.jobs.retain(|job| !job.is_finished());
+    let removed = before - state.jobs.len();
+    persist_jobs(&app, &state.jobs);
+
+    println!("🧹 QUEUE: Cleared {} finished jobs", removed);
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(status: &str, item_statuses: &[&str]) -> QueueJob {
+        QueueJob {
+            id: "job".to_string(),
+            mode: "sequential".to_string(),
+            stop_on_failure: false,
+            max_parallel: 1,
+            status: status.to_string(),
+            items: item_statuses.iter().map(|status| QueueItem {
+                node_id: "node".to_string(),
+                project_id: "project".to_string(),
+                file_path: "run.py".to_string(),
+                name: "run.py".to_string(),
+                database: None,
+                status: status.to_string(),
+                result: None,
+                error: None,
+            }).collect(),
+            created_at: 1,
+            started_at: Some(2),
+            finished_at: None,
+        }
+    }
+
+    fn statuses(job: &QueueJob) -> Vec<&str> {
+        job.items.iter().map(|item| item.status.as_str()).collect()
+    }
+
+    #[test]
+    fn restored_jobs_resume_or_finish_their_cancellation() {
+        let mut jobs = vec![job("running", &["success", "running", "pending"]), job("cancelled", &["success", "running", "pending"])];
+        recover_jobs(&mut jobs, 10);
+
+        assert_eq!(jobs[0].status, "queued");
+        assert_eq!(statuses(&jobs[0]), vec!["success", "pending", "pending"]);
+        assert_eq!(statuses(&jobs[1]), vec!["success", "cancelled", "cancelled"]);
+        assert_eq!((jobs[1].finished_at, jobs[1].completed_items()), (Some(10), 3));
+    }
+}
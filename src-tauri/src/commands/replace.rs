@@ -0,0 +1,450 @@
+// src-tauri/src/commands/replace.rs
+// Project-wide find and replace: per-file diff preview, then atomic apply of selected hunks
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use super::{
+    file_types::{detect_file_type, file_type_config, FileTypeConfig},
+    search::{build_matcher, search_targets, walk_project_files, ContentSearchRequest, GlobFilter, SearchTarget, DEFAULT_MAX_FILE_SIZE},
+    undo_journal::{record_edits, register_edited_files, replace_files_atomically, sha256_hex, JournalEdit},
+    utils::*,
+};
+
+const MAX_PREVIEW_HUNKS: usize = 10_000;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReplaceRequest {
+    pub search: ContentSearchRequest, // Same spec as search_content
+    pub replacement: String, // In regex mode $1 and ${name} expand capture groups
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReplaceHunk {
+    pub index: usize, // Stable for the same file content and request
+    pub line: usize,
+    pub column: usize,
+    pub original: String,
+    pub replacement: String,
+    pub before: String, // The whole line, before and after this hunk alone
+    pub after: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileReplacePreview {
+    pub project_id: String,
+    pub node_id: Option<String>,
+    pub path: String,
+    pub version: String, // Content hash; apply refuses the file if it changed
+    pub hunks: Vec<ReplaceHunk>,
+    pub diff: String, // Unified diff with every hunk applied
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReplacePreview {
+    pub files: Vec<FileReplacePreview>,
+    pub total_hunks: usize,
+    pub truncated: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SelectedHunks {
+    pub project_id: String,
+    pub path: String,
+    pub version: String,
+    pub hunks: Vec<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApplyReplaceRequest {
+    pub search: ContentSearchRequest,
+    pub replacement: String,
+    pub files: Vec<SelectedHunks>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApplyReplaceResult {
+    pub batch_id: Option<String>, // Undo journal batch; None when nothing changed
+    pub files_changed: usize,
+    pub hunks_applied: usize,
+}
+
+struct PlannedHunk {
+    start: usize, // Byte range of the match in the file
+    end: usize,
+    line_start: usize, // Byte range of the line, without its terminator
+    line_end: usize,
+    line: usize,
+    column: usize,
+    replacement: String,
+}
+
+// Planned file change: (full path, new content, current content, project, relative path)
+type FileChange = (PathBuf, Vec<u8>, Vec<u8>, String, String);
+
+/// Text files only, and only when they are valid UTF-8 so a rewrite cannot mangle them.
+fn read_editable(path: &Path, max_size: u64, file_types: &FileTypeConfig) -> Option<(Vec<u8>, String)> {
+    if detect_file_type(path, file_types).is_binary || fs::metadata(path).map(|meta| meta.len() > max_size).unwrap_or(true) {
+        return None;
+    }
+    let bytes = fs::read(path).ok()?;
+    if is_content_binary(&bytes) {
+        return None;
+    }
+    let text = String::from_utf8(bytes.clone()).ok()?;
+    Some((bytes, text))
+}
+
+/// Matches line by line, like search_content, keeping line endings untouched.
+fn plan_hunks(content: &str, matcher: &Regex, replacement: &str, expand: bool) -> Vec<PlannedHunk> {
+    let mut hunks = Vec::new();
+    let mut offset = 0;
+
+    for (index, segment) in content.split_inclusive('\n').enumerate() {
+        let body = segment.trim_end_matches('\n').trim_end_matches('\r');
+        for captures in matcher.captures_iter(body) {
+            let found = captures.get(0).unwrap();
+            if found.start() == found.end() {
+                continue;
+            }
+            let mut expanded = String::new();
+            if expand {
+                captures.expand(replacement, &mut expanded);
+            } else {
+                expanded.push_str(replacement);
+            }
+            hunks.push(PlannedHunk {
+                start: offset + found.start(),
+                end: offset + found.end(),
+                line_start: offset,
+                line_end: offset + body.len(),
+                line: index + 1,
+                column: body[..found.start()].chars().count() + 1,
+                replacement: expanded,
+            });
+        }
+        offset += segment.len();
+    }
+    hunks
+}
+
+fn apply_hunks<'a, I>(content: &str, hunks: I) -> String
+where
+    I: IntoIterator<Item = &'a PlannedHunk>,
+{
+    let mut out = String::with_capacity(content.len());
+    let mut position = 0;
+    for hunk in hunks {
+        out.push_str(&content[position..hunk.start]);
+        out.push_str(&hunk.replacement);
+        position = hunk.end;
+    }
+    out.push_str(&content[position..]);
+    out
+}
+
+fn unified_diff(path: &str, content: &str, hunks: &[PlannedHunk]) -> String {
+    let mut diff = format!("--- a/{}\n+++ b/{}\n", path, path);
+    let mut index = 0;
+    while index < hunks.len() {
+        // Hunks on the same line are shown as one changed line
+        let line_hunks: Vec<&PlannedHunk> = hunks[index..].iter().take_while(|hunk| hunk.line == hunks[index].line).collect();
+        let first = line_hunks[0];
+        let before = &content[first.line_start..first.line_end];
+        let mut after = String::new();
+        let mut position = first.line_start;
+        for hunk in &line_hunks {
+            after.push_str(&content[position..hunk.start]);
+            after.push_str(&hunk.replacement);
+            position = hunk.end;
+        }
+        after.push_str(&content[position..first.line_end]);
+        diff.push_str(&format!("@@ -{} +{} @@\n-{}\n+{}\n", first.line, first.line, before, after));
+        index += line_hunks.len();
+    }
+    diff
+}
+
+fn preview_file(target: &SearchTarget, relative_path: &str, bytes: &[u8], content: &str, hunks: &[PlannedHunk]) -> FileReplacePreview {
+    let hunk_previews = hunks.iter().enumerate().map(|(index, hunk)| {
+        let before = &content[hunk.line_start..hunk.line_end];
+        let (start, end) = (hunk.start - hunk.line_start, hunk.end - hunk.line_start);
+        ReplaceHunk {
+            index,
+            line: hunk.line,
+            column: hunk.column,
+            original: content[hunk.start..hunk.end].to_string(),
+            replacement: hunk.replacement.clone(),
+            before: before.to_string(),
+            after: format!("{}{}{}", &before[..start], hunk.replacement, &before[end..]),
+        }
+    }).collect();
+
+    FileReplacePreview {
+        project_id: target.project_id.clone(),
+        node_id: target.node_ids.get(relative_path).cloned(),
+        path: relative_path.to_string(),
+        version: sha256_hex(bytes),
+        hunks: hunk_previews,
+        diff: unified_diff(relative_path, content, hunks),
+    }
+}
+
+fn build_preview(request: &ReplaceRequest, targets: &[SearchTarget]) -> Result<ReplacePreview, String> {
+    let matcher = build_matcher(&request.search)?;
+    let filter = GlobFilter::new(&request.search.include, &request.search.exclude)?;
+    let max_file_size = request.search.max_file_size.unwrap_or(DEFAULT_MAX_FILE_SIZE);
+    let mut preview = ReplacePreview { files: Vec::new(), total_hunks: 0, truncated: false };
+
+    for target in targets {
+        if !target.root.exists() {
+            continue;
+        }
+        walk_project_files(&target.root, &filter, &mut |path, relative_path| {
+            let (bytes, content) = match read_editable(path, max_file_size, &target.file_types) {
+                Some(file) => file,
+                None => return true,
+            };
+            let hunks = plan_hunks(&content, &matcher, &request.replacement, request.search.regex);
+            if hunks.is_empty() {
+                return true;
+            }
+            if preview.total_hunks + hunks.len() > MAX_PREVIEW_HUNKS {
+                preview.truncated = true;
+                return false;
+            }
+            preview.total_hunks += hunks.len();
+            preview.files.push(preview_file(target, relative_path, &bytes, &content, &hunks));
+            true
+        })?;
+        if preview.truncated {
+            break;
+        }
+    }
+    Ok(preview)
+}
+
+/// Works out the new content of every selected file. Fails without touching
+/// anything when a file changed since the preview or a hunk no longer exists.
+fn plan_apply<F>(request: &ApplyReplaceRequest, file_types: &FileTypeConfig, project_dir: F) -> Result<(Vec<FileChange>, usize), String>
+where
+    F: Fn(&str) -> Result<PathBuf, String>,
+{
+    let matcher = build_matcher(&request.search)?;
+    let mut changes = Vec::new();
+    let mut conflicts = Vec::new();
+    let mut hunks_applied = 0;
+
+    for selection in &request.files {
+        let selected: BTreeSet<usize> = selection.hunks.iter().cloned().collect();
+        if selected.is_empty() {
+            continue;
+        }
+        let full_path = resolve_project_path(&project_dir(&selection.project_id)?, &selection.path)?;
+        let (bytes, content) = match read_editable(&full_path, u64::MAX, file_types) {
+            Some(file) if sha256_hex(&file.0) == selection.version => file,
+            _ => {
+                conflicts.push(selection.path.clone());
+                continue;
+            }
+        };
+
+        let hunks = plan_hunks(&content, &matcher, &request.replacement, request.search.regex);
+        if let Some(missing) = selected.iter().find(|&&index| index >= hunks.len()) {
+            return Err(format!("Hunk {} does not exist in {}", missing, selection.path));
+        }
+        let updated = apply_hunks(&content, selected.iter().map(|&index| &hunks[index]));
+        if updated != content {
+            hunks_applied += selected.len();
+            changes.push((full_path, updated.into_bytes(), bytes, selection.project_id.clone(), selection.path.replace('\\', "/")));
+        }
+    }
+
+    if !conflicts.is_empty() {
+        return Err(format!("Files changed since the preview: {}", conflicts.join(", ")));
+    }
+    Ok((changes, hunks_applied))
+}
+
+#[tauri::command]
+pub async fn preview_replace(
+    app: AppHandle,
+    request: ReplaceRequest,
+) -> Result<ReplacePreview, String> {
+    let targets = search_targets(
+        &load_project_data(&app)?,
+        &app,
+        request.search.project_id.as_deref(),
+        request.search.client_id.as_deref(),
+    )?;
+    let preview = tauri::async_runtime::spawn_blocking(move || build_preview(&request, &targets))
+        .await
+        .map_err(|e| format!("Replace preview failed: {}", e))??;
+
+    println!("🔁 REPLACE: Preview has {} hunks in {} files", preview.total_hunks, preview.files.len());
+    Ok(preview)
+}
+
+#[tauri::command]
+pub async fn apply_replace(
+    app: AppHandle,
+    request: ApplyReplaceRequest,
+) -> Result<ApplyReplaceResult, String> {
+    tauri::async_runtime::spawn_blocking(move || apply_replace_blocking(&app, &request))
+        .await
+        .map_err(|e| format!("Replace failed: {}", e))?
+}
+
+// Plans, writes and journals the replacement; reads and rewrites every target file
+fn apply_replace_blocking(app: &AppHandle, request: &ApplyReplaceRequest) -> Result<ApplyReplaceResult, String> {
+    let (changes, hunks_applied) = plan_apply(request, &file_type_config(app), |project_id| get_project_dir(app, project_id))?;
+    if changes.is_empty() {
+        return Ok(ApplyReplaceResult { batch_id: None, files_changed: 0, hunks_applied: 0 });
+    }
+
+    let files: Vec<(PathBuf, Vec<u8>, Vec<u8>)> = changes.iter()
+        .map(|(path, updated, original, _, _)| (path.clone(), updated.clone(), original.clone()))
+        .collect();
+    replace_files_atomically(&files)?;
+
+    let edits: Vec<JournalEdit> = changes.iter()
+        .map(|(_, updated, original, project_id, path)| JournalEdit {
+            project_id,
+            file_path: path,
+            before: original,
+            after: updated,
+        })
+        .collect();
+    let description = format!("Replace '{}' with '{}'", request.search.query, request.replacement);
+    let batch_id = match record_edits(app, "replace", &description, &edits) {
+        Ok(batch_id) => batch_id,
+        Err(e) => {
+            // An edit that cannot be undone is not applied
+            let reverted: Vec<(PathBuf, Vec<u8>, Vec<u8>)> = files.into_iter()
+                .map(|(path, updated, original)| (path, original, updated))
+                .collect();
+            replace_files_atomically(&reverted)?;
+            return Err(format!("Failed to record the edit, nothing was changed: {}", e));
+        }
+    };
+
+    let mut paths_by_project: HashMap<String, Vec<String>> = HashMap::new();
+    for (_, _, _, project_id, path) in &changes {
+        paths_by_project.entry(project_id.clone()).or_default().push(path.clone());
+    }
+    register_edited_files(app, &paths_by_project);
+
+    println!("✅ REPLACE: Applied {} hunks in {} files (batch {})", hunks_applied, changes.len(), batch_id);
+    Ok(ApplyReplaceResult {
+        batch_id: Some(batch_id),
+        files_changed: changes.len(),
+        hunks_applied,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn spec(query: &str, regex: bool) -> ContentSearchRequest {
+        ContentSearchRequest {
+            query: query.to_string(),
+            project_id: Some("p1".to_string()),
+            client_id: None,
+            regex,
+            case_sensitive: true,
+            whole_word: false,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            context_lines: None,
+            max_results: None,
+            max_file_size: None,
+            search_id: None,
+        }
+    }
+
+    fn project() -> (tempfile::TempDir, SearchTarget) {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("load.py"), "cfg['db_host']\r\nprint(cfg['db_host'], cfg['db_port'])\r\n").unwrap();
+        fs::write(dir.path().join("notes.md"), "No keys here\n").unwrap();
+        fs::write(dir.path().join("latin1.txt"), b"cfg['db_host'] caf\xe9").unwrap();
+        let target = SearchTarget {
+            project_id: "p1".to_string(),
+            root: dir.path().to_path_buf(),
+            node_ids: HashMap::new(),
+            file_types: Arc::new(FileTypeConfig::default()),
+        };
+        (dir, target)
+    }
+
+    fn replace_request() -> ReplaceRequest {
+        ReplaceRequest {
+            search: spec(r"cfg\['db_(\w+)'\]", true),
+            replacement: "settings.database.$1".to_string(),
+        }
+    }
+
+    #[test]
+    fn preview_expands_capture_groups_per_hunk() {
+        let (_dir, target) = project();
+
+        let preview = build_preview(&replace_request(), std::slice::from_ref(&target)).unwrap();
+
+        // Files that are not valid UTF-8 are never offered for rewriting
+        assert_eq!(preview.files.len(), 1);
+        assert_eq!(preview.total_hunks, 3);
+        let file = &preview.files[0];
+        assert_eq!(file.path, "load.py");
+        let hunks: Vec<(usize, usize, &str)> = file.hunks.iter().map(|h| (h.line, h.column, h.replacement.as_str())).collect();
+        assert_eq!(hunks, vec![
+            (1, 1, "settings.database.host"),
+            (2, 7, "settings.database.host"),
+            (2, 23, "settings.database.port"),
+        ]);
+        assert_eq!(file.hunks[2].after, "print(cfg['db_host'], settings.database.port)");
+        assert!(file.diff.contains("@@ -2 +2 @@\n-print(cfg['db_host'], cfg['db_port'])\n+print(settings.database.host, settings.database.port)\n"));
+    }
+
+    #[test]
+    fn apply_writes_only_selected_hunks_and_refuses_changed_files() {
+        let (dir, target) = project();
+        let preview = build_preview(&replace_request(), std::slice::from_ref(&target)).unwrap();
+        let project_dir = |_: &str| Ok(dir.path().to_path_buf());
+
+        let mut apply = ApplyReplaceRequest {
+            search: replace_request().search,
+            replacement: replace_request().replacement,
+            files: vec![SelectedHunks {
+                project_id: "p1".to_string(),
+                path: "load.py".to_string(),
+                version: preview.files[0].version.clone(),
+                hunks: vec![0, 2],
+            }],
+        };
+        let (changes, applied) = plan_apply(&apply, &FileTypeConfig::default(), project_dir).unwrap();
+        assert_eq!(applied, 2);
+        assert_eq!(
+            String::from_utf8(changes[0].1.clone()).unwrap(),
+            "settings.database.host\r\nprint(cfg['db_host'], settings.database.port)\r\n"
+        );
+
+        apply.files[0].hunks = vec![7];
+        assert!(plan_apply(&apply, &FileTypeConfig::default(), project_dir).unwrap_err().contains("Hunk 7"));
+
+        fs::write(dir.path().join("load.py"), "cfg['db_host']\n").unwrap();
+        apply.files[0].hunks = vec![0];
+        let error = plan_apply(&apply, &FileTypeConfig::default(), project_dir).unwrap_err();
+        assert_eq!(error, "Files changed since the preview: load.py");
+    }
+
+    #[test]
+    fn literal_replacements_are_not_expanded() {
+        let matcher = build_matcher(&spec("$price", false)).unwrap();
+        let hunks = plan_hunks("cost = $price", &matcher, "$1.00", false);
+        assert_eq!(apply_hunks("cost = $price", &hunks), "cost = $1.00");
+    }
+}
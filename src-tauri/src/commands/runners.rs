@@ -0,0 +1,577 @@
+// src-tauri/src/commands/runners.rs
+// Extension-keyed runner registry shared by every way a project file is executed
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use super::{
+    ExecutionResult,
+    environment::{load_execution_environment, ExecutionEnvironment},
+    execution::{ExecutionRequest, ScriptRun},
+    utils::*,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+// How long output may keep arriving after the script exits. Background processes
+// it started can hold the pipes open indefinitely.
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RunnerConfig {
+    pub id: String,
+    pub name: String,
+    pub extensions: Vec<String>, // Lowercase, without the dot
+    pub commands: Vec<String>, // Tried in order until one starts
+    pub args: Vec<String>, // "{file}" and "{database}" are substituted
+    #[serde(default)]
+    pub stdin_from_file: bool, // Pipe the file to stdin instead of passing it as an argument
+    #[serde(default)]
+    pub needs_database: bool,
+    #[serde(default)]
+    pub default_database: Option<String>, // Relative to the project unless absolute
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RunnerSettings {
+    #[serde(default)]
+    pub default_timeout_secs: Option<u64>,
+    pub runners: Vec<RunnerConfig>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RunnerAvailability {
+    pub id: String,
+    pub available: bool,
+    pub command: Option<String>, // First command that could be started
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ExecutionOutputEvent {
+    pub run_id: String,
+    pub node_id: Option<String>,
+    pub project_id: String,
+    pub file_path: String,
+    pub trigger: String,
+    pub stream: String, // "started", "stdout" or "stderr"
+    pub line: String,
+}
+
+/// Cancel flags for runs that are currently executing, keyed by run id.
+#[derive(Default)]
+pub struct RunningExecutions {
+    runs: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+fn runner(
+    id: &str,
+    name: &str,
+    extensions: &[&str],
+    commands: &[&str],
+    args: &[&str],
+) -> RunnerConfig {
+    let to_strings = |values: &[&str]| values.iter().map(|value| value.to_string()).collect();
+    RunnerConfig {
+        id: id.to_string(),
+        name: name.to_string(),
+        extensions: to_strings(extensions),
+        commands: to_strings(commands),
+        args: to_strings(args),
+        stdin_from_file: false,
+        needs_database: false,
+        default_database: None,
+        timeout_secs: None,
+        enabled: true,
+    }
+}
+
+impl Default for RunnerSettings {
+    fn default() -> Self {
+        let mut sqlite = runner("sqlite", "SQLite", &["sql"], &["sqlite3"], &["-batch", "-bail", "{database}"]);
+        sqlite.stdin_from_file = true;
+        sqlite.needs_database = true;
+
+        RunnerSettings {
+            default_timeout_secs: None,
+            runners: vec![
+                runner("python", "Python", &["py"], &["python3", "python", "py"], &["{file}"]),
+                runner(
+                    "jupyter",
+                    "Jupyter",
+                    &["ipynb"],
+                    &["jupyter"],
+                    &["nbconvert", "--to", "notebook", "--execute", "--inplace", "{file}"],
+                ),
+                runner("node", "Node.js", &["js", "mjs", "cjs"], &["node"], &["{file}"]),
+                runner("shell", "Shell", &["sh"], &["bash", "sh"], &["{file}"]),
+                runner("r", "R", &["r"], &["Rscript"], &["{file}"]),
+                sqlite,
+            ],
+        }
+    }
+}
+
+pub(crate) fn load_runner_settings(app: &AppHandle) -> Result<RunnerSettings, String> {
+    let settings_file = get_runner_settings_file(app)?;
+    if !settings_file.exists() {
+        return Ok(RunnerSettings::default());
+    }
+
+    let content = safe_file_operation(
+        || fs::read_to_string(&settings_file),
+        "Failed to read runner settings"
+    )?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse runner settings: {}", e))
+}
+
+fn validate_settings(settings: &RunnerSettings) -> Result<(), String> {
+    let mut seen_extensions: HashMap<String, &str> = HashMap::new();
+
+    for runner in &settings.runners {
+        if runner.id.trim().is_empty() {
+            return Err("Runner id cannot be empty".to_string());
+        }
+        if runner.commands.iter().all(|command| command.trim().is_empty()) {
+            return Err(format!("Runner '{}' needs at least one command", runner.id));
+        }
+        if !runner.stdin_from_file && !runner.args.iter().any(|arg| arg.contains("{file}")) {
+            return Err(format!("Runner '{}' must pass {{file}} or read the file from stdin", runner.id));
+        }
+        if !runner.enabled {
+            continue;
+        }
+        for extension in &runner.extensions {
+            let extension = extension.trim_start_matches('.').to_lowercase();
+            if let Some(other) = seen_extensions.insert(extension.clone(), &runner.id) {
+                return Err(format!("Extension .{} is claimed by both '{}' and '{}'", extension, other, runner.id));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn find_runner<'a>(settings: &'a RunnerSettings, extension: &str) -> Option<&'a RunnerConfig> {
+    settings.runners.iter()
+        .filter(|runner| runner.enabled)
+        .find(|runner| runner.extensions.iter().any(|ext| ext.trim_start_matches('.').eq_ignore_ascii_case(extension)))
+}
+
+fn resolve_database(project_dir: &Path, database: &str) -> PathBuf {
+    let path = Path::new(database);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        project_dir.join(path)
+    }
+}
+
+// Blocking: run a project file with the runner registered for its extension
+pub(crate) fn run_with_registry(
+    app: &AppHandle,
+    run_id: &str,
+    request: &ExecutionRequest,
+    project_dir: &Path,
+) -> Result<ScriptRun, String> {
+    let full_path = resolve_project_path(project_dir, &request.file_path)?;
+    let extension = get_file_extension(&full_path).unwrap_or_default();
+
+    let settings = load_runner_settings(app)?;
+    let runner = find_runner(&settings, &extension)
+        .ok_or_else(|| format!("No runner configured for .{} files", extension))?;
+
+    if !full_path.exists() {
+        return Err(format!("{} file not found", runner.name));
+    }
+
+    let database = request.database.as_ref()
+        .or(runner.default_database.as_ref())
+        .map(|database| resolve_database(project_dir, database));
+    if runner.needs_database && database.is_none() {
+        return Err(format!("Choose a database file to run {} scripts", runner.name));
+    }
+
+    let file_arg = full_path.to_string_lossy().to_string();
+    let database_arg = database.map(|path| path.to_string_lossy().to_string()).unwrap_or_default();
+    let args: Vec<String> = runner.args.iter()
+        .map(|arg| arg.replace("{file}", &file_arg).replace("{database}", &database_arg))
+        .collect();
+    let timeout = runner.timeout_secs.or(settings.default_timeout_secs).map(Duration::from_secs);
+    let environment = load_execution_environment(app, &request.project_id)?;
+
+    let start_time = Instant::now();
+    println!("▶️ RUNNER: Executing {:?} with {}", full_path, runner.name);
+
+    let mut last_error = String::new();
+    for command in runner.commands.iter().filter(|command| !command.trim().is_empty()) {
+        let spawned = in_own_process_group(&mut Command::new(command))
+            .args(&args)
+            .current_dir(project_dir)
+            .envs(environment.vars.iter().map(|(key, value)| (key, value)))
+            .stdin(if runner.stdin_from_file { Stdio::piped() } else { Stdio::null() })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn();
+
+        match spawned {
+            Ok(mut child) => {
+                if runner.stdin_from_file {
+                    let script = safe_file_operation(
+                        || fs::read(&full_path),
+                        "Failed to read script"
+                    )?;
+                    let mut stdin = child.stdin.take().unwrap();
+                    // Dropping stdin at the end of the thread closes the pipe
+                    std::thread::spawn(move || {
+                        let _ = stdin.write_all(&script);
+                    });
+                }
+
+                emit_output(app, run_id, request, "started", &format!("{} {}", command, args.join(" ")));
+                let result = supervise_child(app, run_id, request, &environment, child, start_time, timeout)?;
+                println!("✅ RUNNER: {} finished in {}ms", runner.name, result.duration_ms);
+                return Ok(ScriptRun {
+                    interpreter: command.clone(),
+                    args,
+                    result,
+                });
+            }
+            Err(e) => {
+                last_error = format!("{}: {}", command, e);
+                continue;
+            }
+        }
+    }
+
+    Err(format!("Failed to execute {}. Last error: {}", runner.name, last_error))
+}
+
+fn emit_output(app: &AppHandle, run_id: &str, request: &ExecutionRequest, stream: &str, line: &str) {
+    let _ = app.emit_all("execution-output", ExecutionOutputEvent {
+        run_id: run_id.to_string(),
+        node_id: request.node_id.clone(),
+        project_id: request.project_id.clone(),
+        file_path: request.file_path.clone(),
+        trigger: request.trigger.clone(),
+        stream: stream.to_string(),
+        line: line.to_string(),
+    });
+}
+
+// Forward each line of a pipe as an event and pass a copy on for the result;
+// secret values are scrubbed before a line leaves this thread
+fn stream_lines<R: Read + Send + 'static>(
+    app: &AppHandle,
+    run_id: &str,
+    request: &ExecutionRequest,
+    environment: &ExecutionEnvironment,
+    stream: &'static str,
+    reader: R,
+) -> Receiver<String> {
+    let app = app.clone();
+    let run_id = run_id.to_string();
+    let request = request.clone();
+    let environment = environment.clone();
+    let (sender, receiver) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        for line in BufReader::new(reader).lines().map_while(Result::ok) {
+            let line = environment.redact(&line);
+            emit_output(&app, &run_id, &request, stream, &line);
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+/// Lines from a stream until its pipe closes or the deadline passes. Returns
+/// whether the pipe closed, i.e. the output is complete.
+fn collect_lines(receiver: &Receiver<String>, deadline: Instant) -> (Vec<String>, bool) {
+    let mut lines = Vec::new();
+    loop {
+        match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(line) => lines.push(line),
+            Err(RecvTimeoutError::Disconnected) => return (lines, true),
+            Err(RecvTimeoutError::Timeout) => return (lines, false),
+        }
+    }
+}
+
+// Starts the script as the leader of a new process group, which everything it
+// starts inherits, so kill_process_tree can reach grandchildren too
+#[cfg(unix)]
+fn in_own_process_group(command: &mut Command) -> &mut Command {
+    use std::os::unix::process::CommandExt;
+    // setpgid is async-signal-safe, as pre_exec requires
+    unsafe {
+        command.pre_exec(|| {
+            if libc::setpgid(0, 0) == 0 {
+                Ok(())
+            } else {
+                Err(std::io::Error::last_os_error())
+            }
+        })
+    }
+}
+
+#[cfg(not(unix))]
+fn in_own_process_group(command: &mut Command) -> &mut Command {
+    command
+}
+
+// Kills the script and the processes it started (R or Node workers, shell
+// pipelines), which would otherwise keep running and hold its output open
+#[cfg(unix)]
+fn kill_process_tree(child: &mut Child) {
+    // The negative pid addresses the whole group started by in_own_process_group
+    if unsafe { libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL) } != 0 {
+        eprintln!("⚠️ RUNNER: Failed to stop process group {}: {}", child.id(), std::io::Error::last_os_error());
+    }
+    let _ = child.kill();
+}
+
+#[cfg(windows)]
+fn kill_process_tree(child: &mut Child) {
+    let pid = child.id().to_string();
+    let killer = Command::new("taskkill").args(["/PID", &pid, "/T", "/F"]).stdout(Stdio::null()).stderr(Stdio::null()).status();
+    if let Err(e) = killer {
+        eprintln!("⚠️ RUNNER: Failed to stop child processes of {}: {}", pid, e);
+    }
+    let _ = child.kill();
+}
+
+// Wait for a child while streaming its output, honoring cancel requests and the timeout
+fn supervise_child(
+    app: &AppHandle,
+    run_id: &str,
+    request: &ExecutionRequest,
+    environment: &ExecutionEnvironment,
+    mut child: Child,
+    start_time: Instant,
+    timeout: Option<Duration>,
+) -> Result<ExecutionResult, String> {
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    let running = app.state::<RunningExecutions>();
+    running.runs.lock().unwrap().insert(run_id.to_string(), cancel_flag.clone());
+
+    let stdout_lines = stream_lines(app, run_id, request, environment, "stdout", child.stdout.take().unwrap());
+    let stderr_lines = stream_lines(app, run_id, request, environment, "stderr", child.stderr.take().unwrap());
+
+    let mut cancelled = false;
+    let mut timed_out = false;
+    let waited = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Ok(status),
+            Ok(None) => {
+                if cancel_flag.load(Ordering::SeqCst) {
+                    cancelled = true;
+                } else if timeout.map_or(false, |limit| start_time.elapsed() >= limit) {
+                    timed_out = true;
+                }
+
+                if cancelled || timed_out {
+                    kill_process_tree(&mut child);
+                    break child.wait();
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            }
+            Err(e) => break Err(e),
+        }
+    };
+
+    running.runs.lock().unwrap().remove(run_id);
+    let exit_status = waited.map_err(|e| e.to_string())?;
+
+    let deadline = Instant::now() + OUTPUT_DRAIN_TIMEOUT;
+    let (stdout_lines, stdout_complete) = collect_lines(&stdout_lines, deadline);
+    let (mut stderr_lines, stderr_complete) = collect_lines(&stderr_lines, deadline);
+    let duration = start_time.elapsed().as_millis() as u64;
+
+    if !(stdout_complete && stderr_complete) {
+        stderr_lines.push("⚠️ Output was cut off: a process started by the script is still holding it open".to_string());
+    }
+
+    if cancelled {
+        stderr_lines.push("⏹️ Execution cancelled".to_string());
+    } else if timed_out {
+        stderr_lines.push(format!("⏱️ Execution timed out after {}s", timeout.unwrap_or_default().as_secs()));
+    }
+
+    Ok(ExecutionResult {
+        success: exit_status.success() && !cancelled && !timed_out,
+        stdout: stdout_lines.join("\n"),
+        stderr: stderr_lines.join("\n"),
+        exit_code: exit_status.code(),
+        duration_ms: duration,
+        run_id: None,
+        created_files: Vec::new(),
+        modified_files: Vec::new(),
+        new_nodes: Vec::new(),
+        files_overlapped: false,
+        cancelled,
+        timed_out,
+    })
+}
+
+#[tauri::command]
+pub async fn get_runner_settings(app: AppHandle) -> Result<RunnerSettings, String> {
+    load_runner_settings(&app)
+}
+
+#[tauri::command]
+pub async fn save_runner_settings(
+    app: AppHandle,
+    settings: RunnerSettings,
+) -> Result<RunnerSettings, String> {
+    validate_settings(&settings)?;
+
+    let settings_file = get_runner_settings_file(&app)?;
+    ensure_parent_dir(&settings_file)
+        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    let json = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("Failed to serialize runner settings: {}", e))?;
+    safe_file_operation(
+        || fs::write(&settings_file, json),
+        "Failed to save runner settings"
+    )?;
+
+    println!("⚙️ RUNNER: Saved {} runner definitions", settings.runners.len());
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn reset_runner_settings(app: AppHandle) -> Result<RunnerSettings, String> {
+    let settings_file = get_runner_settings_file(&app)?;
+    if settings_file.exists() {
+        safe_file_operation(
+            || fs::remove_file(&settings_file),
+            "Failed to reset runner settings"
+        )?;
+    }
+    Ok(RunnerSettings::default())
+}
+
+// Reports which runners have an interpreter on the PATH
+#[tauri::command]
+pub async fn check_runner_availability(app: AppHandle) -> Result<Vec<RunnerAvailability>, String> {
+    let settings = load_runner_settings(&app)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        settings.runners.iter()
+            .map(|runner| {
+                let command = runner.commands.iter().find(|command| {
+                    Command::new(command.as_str())
+                        .arg("--version")
+                        .stdin(Stdio::null())
+                        .stdout(Stdio::null())
+                        .stderr(Stdio::null())
+                        .status()
+                        .is_ok()
+                });
+                RunnerAvailability {
+                    id: runner.id.clone(),
+                    available: command.is_some(),
+                    command: command.cloned(),
+                }
+            })
+            .collect()
+    })
+        .await
+        .map_err(|e| format!("Runner check failed: {}", e))
+}
+
+#[tauri::command]
+pub async fn cancel_execution(
+    running: tauri::State<'_, RunningExecutions>,
+    run_id: String,
+) -> Result<bool, String> {
+    let runs = running.runs.lock().unwrap();
+    match runs.get(&run_id) {
+        Some(flag) => {
+            flag.store(true, Ordering::SeqCst);
+            println!("⏹️ RUNNER: Cancelling run {}", run_id);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_collection_stops_at_the_deadline() {
+        let (sender, receiver) = mpsc::channel();
+        sender.send("first".to_string()).unwrap();
+        // The sender stays alive, like a pipe held open by a background process
+        let (lines, complete) = collect_lines(&receiver, Instant::now() + Duration::from_millis(50));
+        assert_eq!((lines, complete), (vec!["first".to_string()], false));
+
+        sender.send("last".to_string()).unwrap();
+        drop(sender);
+        let (lines, complete) = collect_lines(&receiver, Instant::now() + Duration::from_secs(5));
+        assert_eq!((lines, complete), (vec!["last".to_string()], true));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn killing_a_run_takes_down_the_processes_it_started() {
+        let mut child = in_own_process_group(&mut Command::new("sh"))
+            .args(["-c", "sleep 30 | sleep 30"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut stdout = child.stdout.take().unwrap();
+        std::thread::sleep(Duration::from_millis(200));
+
+        let start = Instant::now();
+        kill_process_tree(&mut child);
+        child.wait().unwrap();
+        // The pipe only closes once no sleep holds it any more
+        let mut rest = Vec::new();
+        stdout.read_to_end(&mut rest).unwrap();
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn killing_a_run_takes_down_its_grandchildren() {
+        // The subshell is the child; the sleep it starts is a grandchild
+        let mut child = in_own_process_group(&mut Command::new("sh"))
+            .args(["-c", "(sleep 30 & echo $!; wait) & wait"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut first_line = String::new();
+        BufReader::new(child.stdout.take().unwrap()).read_line(&mut first_line).unwrap();
+        let grandchild: libc::pid_t = first_line.trim().parse().unwrap();
+
+        kill_process_tree(&mut child);
+        child.wait().unwrap();
+        // Orphaned, it is reaped by init once it dies
+        let start = Instant::now();
+        while unsafe { libc::kill(grandchild, 0) } == 0 && start.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        assert_ne!(unsafe { libc::kill(grandchild, 0) }, 0, "grandchild {} survived the cancel", grandchild);
+    }
+}
@@ -0,0 +1,716 @@
+// src-tauri/src/commands/scheduler.rs
+// Cron-style schedules for files, run configurations and pipelines
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::Duration;
+use chrono::{Local, TimeZone};
+use cron::Schedule as CronSchedule;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+use uuid::Uuid;
+use super::{
+    ExecutionResult,
+    execution::{execute_and_record, ExecutionRequest},
+    pipelines::execute_pipeline,
+    runners::{find_runner, load_runner_settings},
+    utils::*,
+};
+
+const TICK_INTERVAL: Duration = Duration::from_secs(20);
+const ON_TIME_GRACE_SECS: i64 = 120; // A run this late still counts as on time
+const MAX_CATCH_UP_RUNS: usize = 10;
+const MAX_COUNTED_MISSED_RUNS: usize = 1000; // Beyond this the total is reported as a lower bound
+const MAX_RECENT_RUNS: usize = 20;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScheduledFile {
+    pub project_id: String,
+    pub file_path: String,
+    #[serde(default)]
+    pub node_id: Option<String>,
+    #[serde(default)]
+    pub database: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScheduleTarget {
+    File(ScheduledFile),
+    RunConfiguration {
+        files: Vec<ScheduledFile>,
+        #[serde(default)]
+        stop_on_failure: bool,
+    },
+    Pipeline {
+        project_id: String,
+        #[serde(default)]
+        force: bool,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScheduleRun {
+    pub scheduled_for: i64,
+    pub started_at: i64,
+    pub finished_at: i64,
+    pub status: String, // "success", "failed" or "skipped"
+    pub message: Option<String>,
+    #[serde(default)]
+    pub record_ids: Vec<String>, // Execution history records produced by this run
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Schedule {
+    pub id: String,
+    pub name: String,
+    pub cron: String,
+    pub target: ScheduleTarget,
+    pub enabled: bool,
+    pub catch_up: String, // "skip", "run_once" or "run_all"
+    pub created_at: i64,
+    pub next_run_at: Option<i64>,
+    pub last_run_at: Option<i64>,
+    #[serde(default)]
+    pub recent_runs: Vec<ScheduleRun>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ScheduleRunEvent {
+    pub schedule_id: String,
+    pub status: String, // "running", "success", "failed" or "skipped"
+    pub scheduled_for: i64,
+    pub message: Option<String>,
+}
+
+#[derive(Default)]
+struct SchedulerState {
+    schedules: Vec<Schedule>,
+    running: HashSet<String>,
+    loop_active: bool,
+}
+
+/// Shared scheduler state; schedules are mirrored to `schedules.json` on every change.
+#[derive(Default)]
+pub struct Scheduler {
+    state: Mutex<SchedulerState>,
+}
+
+// Accepts standard 5-field cron as well as the 6/7-field form with seconds (and years)
+fn parse_cron(expression: &str) -> Result<CronSchedule, String> {
+    let expression = expression.trim();
+    let normalized = if expression.split_whitespace().count() == 5 {
+        format!("0 {}", expression)
+    } else {
+        expression.to_string()
+    };
+
+    CronSchedule::from_str(&normalized)
+        .map_err(|e| format!("Invalid cron expression '{}': {}", expression, e))
+}
+
+// Next occurrence strictly after `timestamp`, evaluated in local time
+fn next_after(cron: &CronSchedule, timestamp: i64) -> Option<i64> {
+    let after = Local.timestamp_opt(timestamp, 0).single()?;
+    cron.after(&after).next().map(|time| time.timestamp())
+}
+
+/// The newest missed occurrences from `first_due` up to `now`, oldest first and
+/// at most MAX_CATCH_UP_RUNS of them, with the total number missed. Counting
+/// stops at MAX_COUNTED_MISSED_RUNS, flagged by the last value, so a per-second
+/// schedule left off for months costs no more than that.
+fn missed_occurrences(cron: &CronSchedule, first_due: i64, now: i64) -> (Vec<i64>, usize, bool) {
+    let end = match Local.timestamp_opt(now + 1, 0).single() {
+        Some(end) => end,
+        None => return (Vec::new(), 0, false),
+    };
+    // Walked back from now, so the newest come first
+    let occurrences = cron.after(&end).rev()
+        .map(|time| time.timestamp())
+        .skip_while(|time| *time > now)
+        .take_while(|time| *time > first_due);
+
+    let mut recent = Vec::with_capacity(MAX_CATCH_UP_RUNS);
+    let mut total = 0;
+    for time in occurrences {
+        if total == MAX_COUNTED_MISSED_RUNS {
+            recent.reverse();
+            return (recent, total, true);
+        }
+        if recent.len() < MAX_CATCH_UP_RUNS {
+            recent.push(time);
+        }
+        total += 1;
+    }
+    // The stored due time counts even if the expression has changed since
+    if first_due <= now {
+        if recent.len() < MAX_CATCH_UP_RUNS {
+            recent.push(first_due);
+        }
+        total += 1;
+    }
+    recent.reverse();
+    (recent, total, false)
+}
+
+fn validate_catch_up(policy: &str) -> Result<(), String> {
+    match policy {
+        "skip" | "run_once" | "run_all" => Ok(()),
+        other => Err(format!("Unsupported catch-up policy: {}", other)),
+    }
+}
+
+fn validate_target(app: &AppHandle, target: &ScheduleTarget) -> Result<(), String> {
+    let check_file = |file: &ScheduledFile| -> Result<(), String> {
+        let full_path = get_project_dir(app, &file.project_id)?.join(&file.file_path);
+        if !full_path.exists() {
+            return Err(format!("File not found: {}", file.file_path));
+        }
+        let extension = get_file_extension(&full_path).unwrap_or_default();
+        if find_runner(&load_runner_settings(app)?, &extension).is_none() {
+            return Err(format!("Unsupported file type: {}", file.file_path));
+        }
+        Ok(())
+    };
+
+    match target {
+        ScheduleTarget::File(file) => check_file(file),
+        ScheduleTarget::RunConfiguration { files, .. } => {
+            if files.is_empty() {
+                return Err("Run configuration has no files".to_string());
+            }
+            files.iter().try_for_each(check_file)
+        }
+        ScheduleTarget::Pipeline { project_id, .. } => {
+            if !get_project_dir(app, project_id)?.exists() {
+                return Err("Project directory not found".to_string());
+            }
+            Ok(())
+        }
+    }
+}
+
+fn persist_schedules(app: &AppHandle, schedules: &[Schedule]) {
+    let result = get_schedules_file(app).and_then(|schedules_file| {
+        let json = serde_json::to_string_pretty(schedules)
+            .map_err(|e| format!("Failed to serialize schedules: {}", e))?;
+        safe_file_operation(
+            || fs::write(&schedules_file, json),
+            "Failed to write schedules"
+        )
+    });
+
+    if let Err(e) = result {
+        eprintln!("⚠️ SCHEDULER: {}", e);
+    }
+}
+
+fn emit_run(app: &AppHandle, schedule_id: &str, status: &str, scheduled_for: i64, message: Option<String>) {
+    let _ = app.emit_all("schedule-run", ScheduleRunEvent {
+        schedule_id: schedule_id.to_string(),
+        status: status.to_string(),
+        scheduled_for,
+        message,
+    });
+}
+
+// Apply a change to a schedule, then persist
+fn update_schedule<F>(app: &AppHandle, schedule_id: &str, change: F) -> Option<Schedule>
+where
+    F: FnOnce(&mut Schedule),
+{
+    let scheduler = app.state::<Scheduler>();
+    let mut state = scheduler.state.lock().unwrap();
+
+    let updated = state.schedules.iter_mut()
+        .find(|schedule| schedule.id == schedule_id)
+        .map(|schedule| {
+            change(schedule);
+            schedule.clone()
+        });
+    persist_schedules(app, &state.schedules);
+    updated
+}
+
+fn push_run(schedule: &mut Schedule, run: ScheduleRun) {
+    schedule.recent_runs.push(run);
+    if schedule.recent_runs.len() > MAX_RECENT_RUNS {
+        let excess = schedule.recent_runs.len() - MAX_RECENT_RUNS;
+        schedule.recent_runs.drain(..excess);
+    }
+}
+
+// Which of the missed occurrences (newest last) to fire, per the schedule's catch-up policy
+fn select_runs(policy: &str, missed: &[i64], now: i64) -> Vec<i64> {
+    let latest = match missed.last() {
+        Some(latest) => *latest,
+        None => return Vec::new(),
+    };
+
+    match policy {
+        "skip" if now - latest > ON_TIME_GRACE_SECS => Vec::new(),
+        "run_all" => missed.iter().rev().take(MAX_CATCH_UP_RUNS).rev().cloned().collect(),
+        _ => vec![latest],
+    }
+}
+
+async fn run_file(app: &AppHandle, file: &ScheduledFile) -> Result<ExecutionResult, String> {
+    execute_and_record(app, ExecutionRequest {
+        node_id: file.node_id.clone(),
+        project_id: file.project_id.clone(),
+        file_path: file.file_path.clone(),
+        trigger: "schedule".to_string(),
+        database: file.database.clone(),
+    }).await
+}
+
+// Returns (status, message, history record ids)
+async fn execute_target(app: &AppHandle, target: &ScheduleTarget) -> (String, Option<String>, Vec<String>) {
+    let describe = |result: &Result<ExecutionResult, String>, file_path: &str| match result {
+        Ok(result) if result.success => None,
+        Ok(result) => Some(format!("{} exited with code {}", file_path,
+                                   result.exit_code.map_or("unknown".to_string(), |code| code.to_string()))),
+        Err(e) => Some(format!("{}: {}", file_path, e)),
+    };
+
+    match target {
+        ScheduleTarget::File(file) => {
+            let outcome = run_file(app, file).await;
+            let record_ids = outcome.as_ref().ok().and_then(|result| result.run_id.clone()).into_iter().collect();
+            match describe(&outcome, &file.file_path) {
+                None => ("success".to_string(), None, record_ids),
+                Some(message) => ("failed".to_string(), Some(message), record_ids),
+            }
+        }
+        ScheduleTarget::RunConfiguration { files, stop_on_failure } => {
+            let mut record_ids = Vec::new();
+            let mut failures = Vec::new();
+
+            for file in files {
+                let outcome = run_file(app, file).await;
+                if let Some(run_id) = outcome.as_ref().ok().and_then(|result| result.run_id.clone()) {
+                    record_ids.push(run_id);
+                }
+                if let Some(message) = describe(&outcome, &file.file_path) {
+                    failures.push(message);
+                    if *stop_on_failure {
+                        break;
+                    }
+                }
+            }
+
+            if failures.is_empty() {
+                ("success".to_string(), None, record_ids)
+            } else {
+                ("failed".to_string(), Some(failures.join("; ")), record_ids)
+            }
+        }
+        ScheduleTarget::Pipeline { project_id, force } => {
+            match execute_pipeline(app, project_id, *force, "schedule").await {
+                Ok(result) => {
+                    let record_ids = result.steps.iter()
+                        .filter_map(|step| step.result.as_ref().and_then(|result| result.run_id.clone()))
+                        .collect();
+                    let message = Some(format!("{} steps executed, {} up to date", result.executed, result.skipped));
+                    let status = if result.success { "success" } else { "failed" };
+                    (status.to_string(), message, record_ids)
+                }
+                Err(e) => ("failed".to_string(), Some(e), Vec::new()),
+            }
+        }
+    }
+}
+
+async fn run_schedule(app: AppHandle, schedule: Schedule, fire_times: Vec<i64>) {
+    for scheduled_for in fire_times {
+        println!("⏰ SCHEDULER: Running '{}' (due {})", schedule.name, scheduled_for);
+        emit_run(&app, &schedule.id, "running", scheduled_for, None);
+
+        let started_at = chrono::Utc::now().timestamp();
+        let (status, message, record_ids) = execute_target(&app, &schedule.target).await;
+        let finished_at = chrono::Utc::now().timestamp();
+
+        let run = ScheduleRun {
+            scheduled_for,
+            started_at,
+            finished_at,
+            status: status.clone(),
+            message: message.clone(),
+            record_ids,
+        };
+        update_schedule(&app, &schedule.id, |schedule| {
+            schedule.last_run_at = Some(started_at);
+            push_run(schedule, run);
+        });
+        emit_run(&app, &schedule.id, &status, scheduled_for, message);
+    }
+
+    let scheduler = app.state::<Scheduler>();
+    scheduler.state.lock().unwrap().running.remove(&schedule.id);
+}
+
+fn start_run(app: &AppHandle, schedule: Schedule, fire_times: Vec<i64>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        run_schedule(app, schedule, fire_times).await;
+    });
+}
+
+// A due schedule's missed occurrences, worked out before taking the scheduler lock
+struct CatchUp {
+    cron: String, // The expression and due time it was worked out from
+    first_due: i64,
+    missed: Result<(Vec<i64>, usize, bool), String>,
+    next_run_at: Option<i64>,
+}
+
+fn plan_catch_up(cron: &str, first_due: i64, now: i64) -> CatchUp {
+    let parsed = parse_cron(cron);
+    CatchUp {
+        cron: cron.to_string(),
+        first_due,
+        next_run_at: parsed.as_ref().ok().and_then(|parsed| next_after(parsed, now)),
+        missed: parsed.map(|parsed| missed_occurrences(&parsed, first_due, now)),
+    }
+}
+
+// Advance every due schedule and start the runs its catch-up policy allows
+fn tick(app: &AppHandle) {
+    let now = chrono::Utc::now().timestamp();
+    let scheduler = app.state::<Scheduler>();
+    let mut due = Vec::new();
+    let mut skipped_events = Vec::new();
+
+    let pending: Vec<(String, String, i64)> = scheduler.state.lock().unwrap().schedules.iter()
+        .filter(|schedule| schedule.enabled)
+        .filter_map(|schedule| match schedule.next_run_at {
+            Some(next) if next <= now => Some((schedule.id.clone(), schedule.cron.clone(), next)),
+            _ => None,
+        })
+        .collect();
+    if pending.is_empty() {
+        return;
+    }
+    let catch_ups: HashMap<String, CatchUp> = pending.into_iter()
+        .map(|(id, cron, first_due)| (id, plan_catch_up(&cron, first_due, now)))
+        .collect();
+
+    {
+        let mut state = scheduler.state.lock().unwrap();
+        let SchedulerState { schedules, running, .. } = &mut *state;
+
+        for schedule in schedules.iter_mut() {
+            if !schedule.enabled || running.contains(&schedule.id) {
+                continue;
+            }
+            // A schedule edited meanwhile is left for the next tick
+            let catch_up = match catch_ups.get(&schedule.id) {
+                Some(catch_up) if catch_up.cron == schedule.cron && schedule.next_run_at == Some(catch_up.first_due) => catch_up,
+                _ => continue,
+            };
+            let first_due = catch_up.first_due;
+            let (missed, missed_total, more) = match &catch_up.missed {
+                Ok(missed) => missed,
+                Err(e) => {
+                    eprintln!("⚠️ SCHEDULER: {} ({})", e, schedule.name);
+                    schedule.next_run_at = None;
+                    continue;
+                }
+            };
+            schedule.next_run_at = catch_up.next_run_at;
+
+            let fire_times = select_runs(&schedule.catch_up, missed, now);
+            let skipped = missed_total - fire_times.len();
+            if skipped > 0 {
+                let message = format!("Skipped {}{} missed run{}", if *more { "at least " } else { "" }, skipped, if skipped == 1 { "" } else { "s" });
+                println!("⏭️ SCHEDULER: '{}': {}", schedule.name, message);
+                push_run(schedule, ScheduleRun {
+                    scheduled_for: first_due,
+                    started_at: now,
+                    finished_at: now,
+                    status: "skipped".to_string(),
+                    message: Some(message.clone()),
+                    record_ids: Vec::new(),
+                });
+                skipped_events.push((schedule.id.clone(), first_due, message));
+            }
+
+            if !fire_times.is_empty() {
+                running.insert(schedule.id.clone());
+                due.push((schedule.clone(), fire_times));
+            }
+        }
+
+        persist_schedules(app, &state.schedules);
+    }
+
+    for (schedule_id, scheduled_for, message) in skipped_events {
+        emit_run(app, &schedule_id, "skipped", scheduled_for, Some(message));
+    }
+    for (schedule, fire_times) in due {
+        start_run(app, schedule, fire_times);
+    }
+}
+
+fn start_scheduler_loop(app: &AppHandle) {
+    let scheduler = app.state::<Scheduler>();
+    {
+        let mut state = scheduler.state.lock().unwrap();
+        if state.loop_active {
+            return;
+        }
+        state.loop_active = true;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tick(&app);
+            tokio::time::sleep(TICK_INTERVAL).await;
+        }
+    });
+}
+
+/// Reload persisted schedules on startup and start firing them; runs missed while
+/// the app was closed are handled by each schedule's catch-up policy on the first tick.
+pub fn restore_schedules(app: &AppHandle) {
+    let schedules: Vec<Schedule> = match get_schedules_file(app) {
+        Ok(path) if path.exists() => match fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| serde_json::from_str(&content).map_err(|e| e.to_string()))
+        {
+            Ok(schedules) => schedules,
+            Err(e) => {
+                eprintln!("⚠️ SCHEDULER: Failed to load schedules: {}", e);
+                Vec::new()
+            }
+        },
+        _ => Vec::new(),
+    };
+
+    let enabled = schedules.iter().filter(|schedule| schedule.enabled).count();
+    println!("⏰ SCHEDULER: Restored {} schedules ({} enabled)", schedules.len(), enabled);
+
+    app.state::<Scheduler>().state.lock().unwrap().schedules = schedules;
+    start_scheduler_loop(app);
+}
+
+#[tauri::command]
+pub async fn create_schedule(
+    app: AppHandle,
+    scheduler: State<'_, Scheduler>,
+    name: Option<String>,
+    cron: String,
+    target: ScheduleTarget,
+    catch_up: Option<String>,
+    enabled: Option<bool>,
+) -> Result<Schedule, String> {
+    let parsed = parse_cron(&cron)?;
+    let catch_up = catch_up.unwrap_or_else(|| "run_once".to_string());
+    validate_catch_up(&catch_up)?;
+    validate_target(&app, &target)?;
+
+    let now = chrono::Utc::now().timestamp();
+    let name = name.filter(|name| !name.trim().is_empty()).unwrap_or_else(|| match &target {
+        ScheduleTarget::File(file) => file.file_path.clone(),
+        ScheduleTarget::RunConfiguration { files, .. } => format!("{} files", files.len()),
+        ScheduleTarget::Pipeline { .. } => "Pipeline".to_string(),
+    });
+
+    let schedule = Schedule {
+        id: Uuid::new_v4().to_string(),
+        name,
+        cron: cron.trim().to_string(),
+        target,
+        enabled: enabled.unwrap_or(true),
+        catch_up,
+        created_at: now,
+        next_run_at: next_after(&parsed, now),
+        last_run_at: None,
+        recent_runs: Vec::new(),
+    };
+
+    {
+        let mut state = scheduler.state.lock().unwrap();
+        state.schedules.push(schedule.clone());
+        persist_schedules(&app, &state.schedules);
+    }
+
+    println!("⏰ SCHEDULER: Created '{}' ({})", schedule.name, schedule.cron);
+    start_scheduler_loop(&app);
+    Ok(schedule)
+}
+
+#[tauri::command]
+pub async fn list_schedules(
+    scheduler: State<'_, Scheduler>,
+) -> Result<Vec<Schedule>, String> {
+    Ok(scheduler.state.lock().unwrap().schedules.clone())
+}
+
+#[tauri::command]
+pub async fn pause_schedule(
+    app: AppHandle,
+    schedule_id: String,
+) -> Result<Schedule, String> {
+    update_schedule(&app, &schedule_id, |schedule| {
+        schedule.enabled = false;
+        println!("⏸️ SCHEDULER: Paused '{}'", schedule.name);
+    }).ok_or_else(|| "Schedule not found".to_string())
+}
+
+// Runs missed while paused are not caught up; the schedule resumes from now
+#[tauri::command]
+pub async fn resume_schedule(
+    app: AppHandle,
+    schedule_id: String,
+) -> Result<Schedule, String> {
+    let now = chrono::Utc::now().timestamp();
+    let mut error = None;
+
+    let schedule = update_schedule(&app, &schedule_id, |schedule| {
+        match parse_cron(&schedule.cron) {
+            Ok(cron) => {
+                schedule.enabled = true;
+                schedule.next_run_at = next_after(&cron, now);
+                println!("▶️ SCHEDULER: Resumed '{}'", schedule.name);
+            }
+            Err(e) => error = Some(e),
+        }
+    }).ok_or_else(|| "Schedule not found".to_string())?;
+
+    match error {
+        Some(e) => Err(e),
+        None => Ok(schedule),
+    }
+}
+
+#[tauri::command]
+pub async fn delete_schedule(
+    app: AppHandle,
+    scheduler: State<'_, Scheduler>,
+    schedule_id: String,
+) -> Result<(), String> {
+    let mut state = scheduler.state.lock().unwrap();
+    let before = state.schedules.len();
+    state.schedules.retain(|schedule| schedule.id != schedule_id);
+
+    if state.schedules.len() == before {
+        return Err("Schedule not found".to_string());
+    }
+
+    persist_schedules(&app, &state.schedules);
+    println!("🗑️ SCHEDULER: Deleted schedule {}", schedule_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn run_schedule_now(
+    app: AppHandle,
+    scheduler: State<'_, Scheduler>,
+    schedule_id: String,
+) -> Result<(), String> {
+    let schedule = {
+        let mut state = scheduler.state.lock().unwrap();
+        let schedule = state.schedules.iter()
+            .find(|schedule| schedule.id == schedule_id)
+            .cloned()
+            .ok_or_else(|| "Schedule not found".to_string())?;
+
+        if !state.running.insert(schedule_id.clone()) {
+            return Err("Schedule is already running".to_string());
+        }
+        schedule
+    };
+
+    start_run(&app, schedule, vec![chrono::Utc::now().timestamp()]);
+    Ok(())
+}
+
+// Upcoming run times for a cron expression, for validating input in the UI
+#[tauri::command]
+pub async fn preview_schedule(
+    cron: String,
+    count: Option<usize>,
+) -> Result<Vec<i64>, String> {
+    let parsed = parse_cron(&cron)?;
+    Ok(parsed.upcoming(Local)
+        .take(count.unwrap_or(5))
+        .map(|time| time.timestamp())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> i64 {
+        Local.with_ymd_and_hms(year, month, day, hour, minute, 0).single().unwrap().timestamp()
+    }
+
+    fn upcoming(expression: &str, from: i64, count: usize) -> Vec<i64> {
+        let cron = parse_cron(expression).unwrap();
+        let mut times = Vec::new();
+        let mut time = from;
+        while times.len() < count {
+            time = next_after(&cron, time).unwrap();
+            times.push(time);
+        }
+        times
+    }
+
+    #[test]
+    fn cron_fields_accept_ranges_steps_and_day_names() {
+        // Steps; 2024-01-01 is a Monday
+        assert_eq!(upcoming("*/20 * * * *", local(2024, 1, 1, 8, 5), 3),
+                   vec![local(2024, 1, 1, 8, 20), local(2024, 1, 1, 8, 40), local(2024, 1, 1, 9, 0)]);
+        // Ranges
+        assert_eq!(upcoming("30 9-10 * * *", local(2024, 1, 1, 10, 0), 2),
+                   vec![local(2024, 1, 1, 10, 30), local(2024, 1, 2, 9, 30)]);
+        // Day of week, Friday evening to Monday
+        assert_eq!(upcoming("0 9 * * Mon-Fri", local(2024, 1, 5, 18, 0), 1), vec![local(2024, 1, 8, 9, 0)]);
+        // Day of month, and the seconds form
+        assert_eq!(upcoming("0 0 1 * *", local(2024, 1, 15, 0, 0), 2), vec![local(2024, 2, 1, 0, 0), local(2024, 3, 1, 0, 0)]);
+        assert_eq!(upcoming("0 15 6 29 2 *", local(2024, 1, 1, 0, 0), 1), vec![local(2024, 2, 29, 6, 15)]);
+
+        assert!(parse_cron("61 * * * *").is_err());
+        assert!(parse_cron("every day").is_err());
+    }
+
+    #[test]
+    fn long_downtime_catches_up_on_the_newest_occurrences() {
+        let cron = parse_cron("0 * * * *").unwrap();
+        let first_due = local(2024, 1, 1, 0, 0);
+        let now = local(2024, 1, 3, 0, 30); // Two days of hourly runs missed
+
+        let (missed, total, more) = missed_occurrences(&cron, first_due, now);
+        assert_eq!((total, more), (49, false));
+        assert_eq!(missed.len(), MAX_CATCH_UP_RUNS);
+        assert_eq!(*missed.last().unwrap(), local(2024, 1, 3, 0, 0));
+        assert_eq!(missed[0], local(2024, 1, 2, 15, 0));
+
+        assert_eq!(select_runs("run_once", &missed, now), vec![local(2024, 1, 3, 0, 0)]);
+        assert_eq!(select_runs("run_all", &missed, now), missed);
+        // The newest run was half an hour ago, too late to count as on time
+        assert!(select_runs("skip", &missed, now).is_empty());
+        assert_eq!(select_runs("skip", &missed, local(2024, 1, 3, 0, 1)), vec![local(2024, 1, 3, 0, 0)]);
+
+        let (missed, total, more) = missed_occurrences(&cron, first_due, first_due);
+        assert_eq!((missed, total, more), (vec![first_due], 1, false));
+    }
+
+    #[test]
+    fn counting_missed_runs_is_bounded() {
+        // A per-second schedule left off for a year
+        let cron = parse_cron("* * * * * *").unwrap();
+        let now = local(2025, 1, 1, 0, 0);
+
+        let (missed, total, more) = missed_occurrences(&cron, local(2024, 1, 1, 0, 0), now);
+        assert_eq!((total, more), (MAX_COUNTED_MISSED_RUNS, true));
+        assert_eq!(missed.len(), MAX_CATCH_UP_RUNS);
+        assert_eq!(*missed.last().unwrap(), now);
+        assert_eq!(missed[0], now - MAX_CATCH_UP_RUNS as i64 + 1);
+    }
+}
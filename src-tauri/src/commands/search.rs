@@ -0,0 +1,598 @@
+// src-tauri/src/commands/search.rs
+// Full-text content search over the text files of a project or a client's projects
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+use uuid::Uuid;
+use super::{
+    ProjectData,
+    file_types::{detect_file_type, file_type_config, FileTypeConfig},
+    text_encoding::decode_text,
+    utils::*,
+};
+
+const DEFAULT_MAX_RESULTS: usize = 5000;
+pub(crate) const DEFAULT_MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+const MAX_CONTEXT_LINES: usize = 10;
+const MAX_SNIPPET_CHARS: usize = 240;
+const BATCH_SIZE: usize = 100;
+const BATCH_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ContentSearchRequest {
+    pub query: String,
+    pub project_id: Option<String>, // Exactly one of project_id or client_id
+    pub client_id: Option<String>,
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    #[serde(default)]
+    pub whole_word: bool,
+    #[serde(default)]
+    pub include: Vec<String>, // Globs; patterns without "/" match the file name
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    pub context_lines: Option<usize>,
+    pub max_results: Option<usize>,
+    pub max_file_size: Option<u64>,
+    pub search_id: Option<String>, // Lets the caller cancel before the command returns
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchMatch {
+    pub project_id: String,
+    pub node_id: Option<String>,
+    pub path: String, // Relative to the project root
+    pub line: usize, // 1-based
+    pub column: usize, // 1-based, in characters
+    pub match_text: String,
+    pub snippet: String,
+    pub context_before: Vec<String>,
+    pub context_after: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResultsEvent {
+    pub search_id: String,
+    pub matches: Vec<SearchMatch>,
+    pub files_searched: usize,
+}
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct SearchSummary {
+    pub search_id: String,
+    pub projects_searched: usize,
+    pub files_searched: usize,
+    pub files_matched: usize,
+    pub files_skipped: usize, // Binary or over the size limit
+    pub total_matches: usize,
+    pub truncated: bool,
+    pub cancelled: bool,
+    pub duration_ms: u64,
+}
+
+#[derive(Default)]
+pub struct ActiveSearches {
+    searches: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+/// Include/exclude globs over project-relative paths. Supports `*`, `**`,
+/// `?`, `[...]` and `{a,b}`; a pattern without `/` matches the file name.
+pub(crate) struct GlobFilter {
+    include: Vec<(Regex, bool)>,
+    exclude: Vec<(Regex, bool)>,
+}
+
+fn glob_to_regex(glob: &str) -> Result<Regex, String> {
+    let mut pattern = String::from("^");
+    let chars: Vec<char> = glob.trim_start_matches("./").chars().collect();
+    let mut in_braces = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                i += 1;
+                if chars.get(i + 1) == Some(&'/') {
+                    // "**/" matches zero or more directories
+                    i += 1;
+                    pattern.push_str("(?:.*/)?");
+                } else {
+                    pattern.push_str(".*");
+                }
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            '[' => match chars[i..].iter().position(|&c| c == ']') {
+                Some(end) if end > 1 => {
+                    let class: String = chars[i + 1..i + end].iter().collect();
+                    let class = class.strip_prefix('!').map(|rest| format!("^{}", rest)).unwrap_or(class);
+                    pattern.push_str(&format!("[{}]", class.replace('\\', "\\\\")));
+                    i += end;
+                }
+                _ => pattern.push_str("\\["),
+            },
+            '{' if !in_braces => {
+                in_braces = true;
+                pattern.push_str("(?:");
+            }
+            '}' if in_braces => {
+                in_braces = false;
+                pattern.push(')');
+            }
+            ',' if in_braces => pattern.push('|'),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+    if in_braces {
+        return Err(format!("Unclosed '{{' in glob '{}'", glob));
+    }
+    pattern.push('$');
+
+    Regex::new(&pattern).map_err(|e| format!("Invalid glob '{}': {}", glob, e))
+}
+
+impl GlobFilter {
+    pub(crate) fn new(include: &[String], exclude: &[String]) -> Result<Self, String> {
+        let compile = |globs: &[String]| -> Result<Vec<(Regex, bool)>, String> {
+            globs.iter()
+                .map(|glob| glob.trim())
+                .filter(|glob| !glob.is_empty())
+                .map(|glob| Ok((glob_to_regex(glob)?, glob.contains('/'))))
+                .collect()
+        };
+        Ok(GlobFilter {
+            include: compile(include)?,
+            exclude: compile(exclude)?,
+        })
+    }
+
+    fn matches_any(patterns: &[(Regex, bool)], path: &str) -> bool {
+        let name = path.rsplit('/').next().unwrap_or(path);
+        patterns.iter().any(|(pattern, full_path)| {
+            if *full_path { pattern.is_match(path) } else { pattern.is_match(name) }
+        })
+    }
+
+    pub(crate) fn allows(&self, relative_path: &str) -> bool {
+        (self.include.is_empty() || Self::matches_any(&self.include, relative_path))
+            && !Self::matches_any(&self.exclude, relative_path)
+    }
+
+    // Directories are only pruned by exclude patterns; includes apply to files
+    fn allows_dir(&self, relative_path: &str) -> bool {
+        !Self::matches_any(&self.exclude, relative_path)
+    }
+}
+
+/// Calls `visit` with every file under `root` that the filter allows, skipping
+/// hidden and special entries like sync does. Stops early when `visit` returns false.
+pub(crate) fn walk_project_files<F>(root: &Path, filter: &GlobFilter, visit: &mut F) -> Result<bool, String>
+where
+    F: FnMut(&Path, &str) -> bool,
+{
+    fn walk<F>(dir: &Path, root: &Path, filter: &GlobFilter, visit: &mut F) -> Result<bool, String>
+    where
+        F: FnMut(&Path, &str) -> bool,
+    {
+        let mut entries: Vec<PathBuf> = safe_file_operation(|| fs::read_dir(dir), "Failed to read directory")?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .collect();
+        entries.sort();
+
+        for path in entries {
+            let name = path.file_name().and_then(|name| name.to_str()).unwrap_or("");
+            if name.starts_with('.') || name.starts_with("__") {
+                continue;
+            }
+            let relative_path = path.strip_prefix(root)
+                .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+                .unwrap_or_default();
+
+            if path.is_dir() {
+                if filter.allows_dir(&relative_path) && !walk(&path, root, filter, visit)? {
+                    return Ok(false);
+                }
+            } else if path.is_file() && filter.allows(&relative_path) && !visit(&path, &relative_path) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    walk(root, root, filter, visit)
+}
+
+/// Reads a file as text, or None when it is binary or larger than `max_size`.
+pub(crate) fn read_text_file(path: &Path, max_size: u64, file_types: &FileTypeConfig) -> Option<String> {
+    if detect_file_type(path, file_types).is_binary || fs::metadata(path).map(|meta| meta.len() > max_size).unwrap_or(true) {
+        return None;
+    }
+    let content = fs::read(path).ok()?;
+    decode_text(&content).map(|(text, _)| text)
+}
+
+pub(crate) fn build_matcher(request: &ContentSearchRequest) -> Result<Regex, String> {
+    if request.query.is_empty() {
+        return Err("Search query cannot be empty".to_string());
+    }
+    let mut pattern = if request.regex {
+        request.query.clone()
+    } else {
+        regex::escape(&request.query)
+    };
+    if request.whole_word {
+        pattern = format!(r"\b(?:{})\b", pattern);
+    }
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!request.case_sensitive)
+        .build()
+        .map_err(|e| format!("Invalid search pattern: {}", e))
+}
+
+// Long lines are cut to a window around the match
+fn snippet_for(line: &str, start: usize, end: usize) -> String {
+    let line = line.trim_end_matches('\r');
+    if line.chars().count() <= MAX_SNIPPET_CHARS {
+        return line.trim().to_string();
+    }
+    let match_chars = line[start..end.min(line.len())].chars().count();
+    let before = line[..start].chars().count();
+    let skip = before.saturating_sub(MAX_SNIPPET_CHARS.saturating_sub(match_chars) / 2);
+    let window: String = line.chars().skip(skip).take(MAX_SNIPPET_CHARS).collect();
+    let prefix = if skip > 0 { "…" } else { "" };
+    let suffix = if skip + MAX_SNIPPET_CHARS < line.chars().count() { "…" } else { "" };
+    format!("{}{}{}", prefix, window.trim(), suffix)
+}
+
+pub(crate) struct SearchTarget {
+    pub(crate) project_id: String,
+    pub(crate) root: PathBuf,
+    pub(crate) node_ids: HashMap<String, String>, // file_path -> node id
+    pub(crate) file_types: Arc<FileTypeConfig>,
+}
+
+/// Searches each target's files, passing matches to `on_batch` in batches.
+fn run_search<F>(
+    request: &ContentSearchRequest,
+    targets: &[SearchTarget],
+    cancel: &AtomicBool,
+    summary: &mut SearchSummary,
+    mut on_batch: F,
+) -> Result<(), String>
+where
+    F: FnMut(Vec<SearchMatch>, usize),
+{
+    let matcher = build_matcher(request)?;
+    let filter = GlobFilter::new(&request.include, &request.exclude)?;
+    let max_results = request.max_results.unwrap_or(DEFAULT_MAX_RESULTS).max(1);
+    let max_file_size = request.max_file_size.unwrap_or(DEFAULT_MAX_FILE_SIZE);
+    let context_lines = request.context_lines.unwrap_or(0).min(MAX_CONTEXT_LINES);
+
+    let mut batch = Vec::new();
+    let mut last_batch = Instant::now();
+
+    for target in targets {
+        if !target.root.exists() {
+            continue;
+        }
+        summary.projects_searched += 1;
+
+        let completed = walk_project_files(&target.root, &filter, &mut |path, relative_path| {
+            if cancel.load(Ordering::SeqCst) {
+                summary.cancelled = true;
+                return false;
+            }
+            let content = match read_text_file(path, max_file_size, &target.file_types) {
+                Some(content) => content,
+                None => {
+                    summary.files_skipped += 1;
+                    return true;
+                }
+            };
+            summary.files_searched += 1;
+
+            let lines: Vec<&str> = content.lines().collect();
+            let mut file_matched = false;
+            for (index, line) in lines.iter().enumerate() {
+                for found in matcher.find_iter(line) {
+                    if found.start() == found.end() {
+                        continue; // Empty regex matches are not useful results
+                    }
+                    if summary.total_matches >= max_results {
+                        summary.truncated = true;
+                        return false;
+                    }
+                    file_matched = true;
+                    summary.total_matches += 1;
+
+                    let context = |range: std::ops::Range<usize>| -> Vec<String> {
+                        range.map(|line| lines[line].trim_end_matches('\r').to_string()).collect()
+                    };
+                    batch.push(SearchMatch {
+                        project_id: target.project_id.clone(),
+                        node_id: target.node_ids.get(relative_path).cloned(),
+                        path: relative_path.to_string(),
+                        line: index + 1,
+                        column: line[..found.start()].chars().count() + 1,
+                        match_text: found.as_str().to_string(),
+                        snippet: snippet_for(line, found.start(), found.end()),
+                        context_before: context(index.saturating_sub(context_lines)..index),
+                        context_after: context(index + 1..(index + 1 + context_lines).min(lines.len())),
+                    });
+                }
+            }
+            if file_matched {
+                summary.files_matched += 1;
+            }
+
+            if batch.len() >= BATCH_SIZE || (!batch.is_empty() && last_batch.elapsed() >= BATCH_INTERVAL) {
+                on_batch(std::mem::take(&mut batch), summary.files_searched);
+                last_batch = Instant::now();
+            }
+            true
+        })?;
+
+        if !completed {
+            break;
+        }
+    }
+
+    if !batch.is_empty() {
+        on_batch(batch, summary.files_searched);
+    }
+    Ok(())
+}
+
+/// The projects to search: one project, or every project of a client.
+pub(crate) fn search_targets(
+    data: &ProjectData,
+    app: &AppHandle,
+    project_id: Option<&str>,
+    client_id: Option<&str>,
+) -> Result<Vec<SearchTarget>, String> {
+    let project_ids: Vec<String> = match (project_id, client_id) {
+        (Some(project_id), None) => {
+            if !data.projects.iter().any(|project| project.id == project_id) {
+                return Err(format!("Project not found: {}", project_id));
+            }
+            vec![project_id.to_string()]
+        }
+        (None, Some(client_id)) => {
+            let client = data.clients.iter()
+                .find(|client| client.id == client_id)
+                .ok_or_else(|| format!("Client not found: {}", client_id))?;
+            data.projects.iter()
+                .filter(|project| client.projects.contains(&project.id) || project.client_id.as_deref() == Some(client_id))
+                .map(|project| project.id.clone())
+                .collect()
+        }
+        _ => return Err("Search either a project_id or a client_id".to_string()),
+    };
+
+    let file_types = file_type_config(app);
+    project_ids.into_iter()
+        .map(|project_id| {
+            let node_ids = data.nodes.iter()
+                .filter(|node| node.project_id == project_id && node.r#type == "file")
+                .filter_map(|node| node.file_path.as_ref().map(|path| (path.replace('\\', "/"), node.id.clone())))
+                .collect();
+            Ok(SearchTarget {
+                root: get_project_dir(app, &project_id)?,
+                project_id,
+                node_ids,
+                file_types: file_types.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Searches file contents, emitting "search-results" batches as matches are
+/// found and returning a summary when the walk is done.
+#[tauri::command]
+pub async fn search_content(
+    app: AppHandle,
+    searches: State<'_, ActiveSearches>,
+    request: ContentSearchRequest,
+) -> Result<SearchSummary, String> {
+    // Reject bad patterns and scopes before starting the walk
+    build_matcher(&request)?;
+    GlobFilter::new(&request.include, &request.exclude)?;
+    let targets = search_targets(&load_project_data(&app)?, &app, request.project_id.as_deref(), request.client_id.as_deref())?;
+
+    let search_id = request.search_id.clone().unwrap_or_else(|| Uuid::new_v4().to_string());
+    let cancel = Arc::new(AtomicBool::new(false));
+    {
+        let mut active = searches.searches.lock().unwrap();
+        if active.contains_key(&search_id) {
+            return Err(format!("Search {} is already running", search_id));
+        }
+        active.insert(search_id.clone(), cancel.clone());
+    }
+    println!("🔎 SEARCH: '{}' across {} project(s)", request.query, targets.len());
+
+    let started = Instant::now();
+    let worker_app = app.clone();
+    let worker_id = search_id.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let mut summary = SearchSummary { search_id: worker_id.clone(), ..Default::default() };
+        run_search(&request, &targets, &cancel, &mut summary, |matches, files_searched| {
+            let event = SearchResultsEvent { search_id: worker_id.clone(), matches, files_searched };
+            if let Err(e) = worker_app.emit_all("search-results", event) {
+                eprintln!("⚠️ SEARCH: Failed to emit results: {}", e);
+            }
+        }).map(|_| summary)
+    }).await;
+
+    app.state::<ActiveSearches>().searches.lock().unwrap().remove(&search_id);
+    let mut summary = result.map_err(|e| format!("Search task failed: {}", e))??;
+    summary.duration_ms = started.elapsed().as_millis() as u64;
+
+    println!("✅ SEARCH: {} matches in {} of {} files ({} ms)",
+             summary.total_matches, summary.files_matched, summary.files_searched, summary.duration_ms);
+    Ok(summary)
+}
+
+#[tauri::command]
+pub async fn cancel_search(
+    searches: State<'_, ActiveSearches>,
+    search_id: String,
+) -> Result<(), String> {
+    let active = searches.searches.lock().unwrap();
+    let cancel = active.get(&search_id)
+        .ok_or_else(|| format!("No running search: {}", search_id))?;
+    cancel.store(true, Ordering::SeqCst);
+    println!("🛑 SEARCH: Cancel requested for {}", search_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(query: &str) -> ContentSearchRequest {
+        ContentSearchRequest {
+            query: query.to_string(),
+            project_id: Some("p1".to_string()),
+            client_id: None,
+            regex: false,
+            case_sensitive: false,
+            whole_word: false,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            context_lines: None,
+            max_results: None,
+            max_file_size: None,
+            search_id: None,
+        }
+    }
+
+    fn project() -> (tempfile::TempDir, SearchTarget) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src/nested")).unwrap();
+        fs::create_dir_all(root.join(".hidden")).unwrap();
+        fs::write(root.join("notes.md"), "Soil report\nthe soil is Soily\n").unwrap();
+        fs::write(root.join("src/main.py"), "import soil\nprint(soil.ph)  # soil\n").unwrap();
+        fs::write(root.join("src/nested/data.csv"), "site,soil\nA,clay\n").unwrap();
+        fs::write(root.join("image.png"), "soil").unwrap();
+        fs::write(root.join("blob.txt"), b"soil\0\x01").unwrap();
+        fs::write(root.join(".hidden/secret.txt"), "soil").unwrap();
+
+        let target = SearchTarget {
+            project_id: "p1".to_string(),
+            root: root.to_path_buf(),
+            node_ids: vec![("notes.md".to_string(), "n1".to_string())].into_iter().collect(),
+            file_types: Arc::new(FileTypeConfig::default()),
+        };
+        (dir, target)
+    }
+
+    fn search(request: &ContentSearchRequest, target: &SearchTarget) -> (Vec<SearchMatch>, SearchSummary) {
+        let mut matches = Vec::new();
+        let mut summary = SearchSummary::default();
+        run_search(request, std::slice::from_ref(target), &AtomicBool::new(false), &mut summary, |batch, _| {
+            matches.extend(batch);
+        }).unwrap();
+        (matches, summary)
+    }
+
+    #[test]
+    fn literal_search_reports_positions_and_skips_binaries() {
+        let (_dir, target) = project();
+        let mut request = request("soil");
+        request.context_lines = Some(1);
+
+        let (matches, summary) = search(&request, &target);
+
+        let found: Vec<(&str, usize, usize)> = matches.iter().map(|m| (m.path.as_str(), m.line, m.column)).collect();
+        assert_eq!(found, vec![
+            ("notes.md", 1, 1), ("notes.md", 2, 5), ("notes.md", 2, 13),
+            ("src/main.py", 1, 8), ("src/main.py", 2, 7), ("src/main.py", 2, 19),
+            ("src/nested/data.csv", 1, 6),
+        ]);
+        assert_eq!(matches[0].node_id.as_deref(), Some("n1"));
+        assert_eq!(matches[0].context_after, vec!["the soil is Soily".to_string()]);
+        assert_eq!(matches[2].match_text, "Soil");
+        assert_eq!((summary.files_searched, summary.files_matched, summary.files_skipped), (3, 3, 2));
+    }
+
+    #[test]
+    fn extensions_configured_as_binary_are_skipped() {
+        let (_dir, mut target) = project();
+        target.file_types = Arc::new(serde_json::from_str(r#"{
+            "extensions": {"csv": {"mime": "application/x-logger", "category": "data", "description": "Logger dump", "binary": true}}
+        }"#).unwrap());
+
+        let (matches, summary) = search(&request("soil"), &target);
+
+        assert!(matches.iter().all(|m| m.path != "src/nested/data.csv"));
+        assert_eq!((summary.files_searched, summary.files_skipped), (2, 3));
+    }
+
+    #[test]
+    fn case_whole_word_and_regex_options() {
+        let (_dir, target) = project();
+
+        let mut exact = request("Soil");
+        exact.case_sensitive = true;
+        exact.whole_word = true;
+        let (matches, _) = search(&exact, &target);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].snippet, "Soil report");
+
+        let mut pattern = request(r"soil\.(\w+)");
+        pattern.regex = true;
+        let (matches, _) = search(&pattern, &target);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].match_text, "soil.ph");
+
+        pattern.query = "soil(".to_string();
+        assert!(build_matcher(&pattern).is_err());
+    }
+
+    #[test]
+    fn include_and_exclude_globs() {
+        let (_dir, target) = project();
+
+        let mut scoped = request("soil");
+        scoped.include = vec!["src/**".to_string()];
+        scoped.exclude = vec!["*.csv".to_string()];
+        let (matches, _) = search(&scoped, &target);
+        assert!(matches.iter().all(|m| m.path == "src/main.py"));
+        assert_eq!(matches.len(), 3);
+
+        let filter = GlobFilter::new(&["*.{md,py}".to_string()], &["src/nested".to_string()]).unwrap();
+        assert!(filter.allows("notes.md"));
+        assert!(filter.allows("src/main.py"));
+        assert!(!filter.allows("src/nested/data.csv"));
+        assert!(!filter.allows_dir("src/nested"));
+        assert!(GlobFilter::new(&["*.{md".to_string()], &[]).is_err());
+    }
+
+    #[test]
+    fn result_limit_truncates_and_long_lines_are_windowed() {
+        let (_dir, target) = project();
+        let mut limited = request("soil");
+        limited.max_results = Some(2);
+
+        let (matches, summary) = search(&limited, &target);
+        assert_eq!(matches.len(), 2);
+        assert!(summary.truncated);
+
+        let line = format!("{}needle{}", "a".repeat(500), "b".repeat(500));
+        let snippet = snippet_for(&line, 500, 506);
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        assert!(snippet.contains("needle"));
+        assert_eq!(snippet.chars().count(), MAX_SNIPPET_CHARS + 2);
+    }
+}
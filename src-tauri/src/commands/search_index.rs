@@ -0,0 +1,861 @@
+// src-tauri/src/commands/search_index.rs
+// Persistent per-project inverted index with ranked term, phrase and prefix queries.
+// Kept current by the app's own file commands; there is no file watcher, so
+// edits made outside the app show up after a sync or when the index is next loaded.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use super::{
+    file_types::{file_type_config, FileTypeConfig},
+    search::{read_text_file, search_targets, walk_project_files, GlobFilter, DEFAULT_MAX_FILE_SIZE},
+    utils::*,
+};
+
+const INDEX_VERSION: u32 = 1;
+const MAX_TOKEN_CHARS: usize = 64;
+const MAX_PREFIX_EXPANSIONS: usize = 200;
+const DEFAULT_QUERY_LIMIT: usize = 50;
+const MAX_SNIPPET_CHARS: usize = 240;
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+const FLUSH_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedDocument {
+    size: u64,
+    modified: i64, // Milliseconds, to notice edits made outside the app
+    token_count: u32,
+    terms: Vec<String>, // So the document's postings can be dropped on update
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct ProjectIndex {
+    version: u32,
+    project_id: String,
+    built_at: Option<String>,
+    updated_at: Option<String>,
+    documents: BTreeMap<String, IndexedDocument>,
+    postings: BTreeMap<String, BTreeMap<String, Vec<u32>>>, // term -> path -> token positions
+}
+
+/// Loaded indexes, each behind its own lock so projects never wait on each other.
+#[derive(Default)]
+pub struct SearchIndexes {
+    indexes: Mutex<HashMap<String, Arc<Mutex<IndexSlot>>>>,
+}
+
+#[derive(Default)]
+struct IndexSlot {
+    index: Option<ProjectIndex>, // None until first loaded or built
+    flush_pending: bool, // Changed since the last save; a save is scheduled
+}
+
+impl SearchIndexes {
+    fn slot(&self, project_id: &str) -> Arc<Mutex<IndexSlot>> {
+        self.indexes.lock().unwrap()
+            .entry(project_id.to_string())
+            .or_default()
+            .clone()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IndexQueryRequest {
+    pub query: String, // Words, "quoted phrases" and prefix* terms; all must match
+    pub project_id: Option<String>, // Exactly one of project_id or client_id
+    pub client_id: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IndexSearchHit {
+    pub project_id: String,
+    pub node_id: Option<String>,
+    pub path: String,
+    pub score: f64,
+    pub line: Option<usize>, // First line with a matching term
+    pub snippet: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IndexQueryResult {
+    pub hits: Vec<IndexSearchHit>,
+    pub total_hits: usize,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct IndexHealth {
+    pub project_id: String,
+    pub exists: bool,
+    pub healthy: bool,
+    pub documents: usize,
+    pub terms: usize,
+    pub size_bytes: u64,
+    pub built_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub stale: Vec<String>, // Changed on disk since they were indexed
+    pub missing: Vec<String>, // Indexed but no longer on disk
+    pub unindexed: Vec<String>, // Text files on disk the index does not know about
+}
+
+#[derive(Debug, PartialEq)]
+enum Clause {
+    Term(String),
+    Prefix(String),
+    Phrase(Vec<String>),
+}
+
+/// Lowercased alphanumeric words; overlong tokens keep their position but are not indexed.
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(|token| {
+            if token.chars().count() > MAX_TOKEN_CHARS { String::new() } else { token.to_lowercase() }
+        })
+        .collect()
+}
+
+fn parse_query(query: &str) -> Vec<Clause> {
+    let mut clauses = Vec::new();
+
+    // Segments alternate between unquoted text and quoted phrases
+    for (index, segment) in query.split('"').enumerate() {
+        if index % 2 == 1 {
+            let words: Vec<String> = tokenize(segment).into_iter().filter(|word| !word.is_empty()).collect();
+            match words.len() {
+                0 => {}
+                1 => clauses.push(Clause::Term(words[0].clone())),
+                _ => clauses.push(Clause::Phrase(words)),
+            }
+            continue;
+        }
+
+        for word in segment.split_whitespace() {
+            let prefix = word.ends_with('*');
+            let mut tokens: Vec<String> = tokenize(word).into_iter().filter(|token| !token.is_empty()).collect();
+            if prefix {
+                if let Some(last) = tokens.pop() {
+                    clauses.extend(tokens.into_iter().map(Clause::Term));
+                    clauses.push(Clause::Prefix(last));
+                }
+            } else if tokens.len() > 1 {
+                // "foo-bar" reads as the phrase "foo bar"
+                clauses.push(Clause::Phrase(tokens));
+            } else {
+                clauses.extend(tokens.into_iter().map(Clause::Term));
+            }
+        }
+    }
+
+    clauses
+}
+
+fn file_signature(path: &Path) -> Option<(u64, i64)> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok()?
+        .duration_since(std::time::UNIX_EPOCH).ok()?
+        .as_millis() as i64;
+    Some((metadata.len(), modified))
+}
+
+fn is_hidden_path(relative_path: &str) -> bool {
+    relative_path.split('/').any(|part| part.starts_with('.') || part.starts_with("__"))
+}
+
+impl ProjectIndex {
+    fn new(project_id: &str) -> Self {
+        ProjectIndex {
+            version: INDEX_VERSION,
+            project_id: project_id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn remove(&mut self, path: &str) -> bool {
+        let document = match self.documents.remove(path) {
+            Some(document) => document,
+            None => return false,
+        };
+        for term in document.terms {
+            if let Some(paths) = self.postings.get_mut(&term) {
+                paths.remove(path);
+                if paths.is_empty() {
+                    self.postings.remove(&term);
+                }
+            }
+        }
+        true
+    }
+
+    // Drops a path and, when it was a folder, everything indexed under it
+    fn remove_tree(&mut self, path: &str) -> usize {
+        let folder_prefix = format!("{}/", path);
+        let paths: Vec<String> = self.documents.keys()
+            .filter(|indexed| *indexed == path || indexed.starts_with(&folder_prefix))
+            .cloned()
+            .collect();
+        paths.iter().filter(|indexed| self.remove(indexed)).count()
+    }
+
+    fn add(&mut self, path: &str, text: &str, size: u64, modified: i64) {
+        self.remove(path);
+
+        let tokens = tokenize(text);
+        let mut positions: BTreeMap<String, Vec<u32>> = BTreeMap::new();
+        for (position, token) in tokens.iter().enumerate() {
+            if !token.is_empty() {
+                positions.entry(token.clone()).or_default().push(position as u32);
+            }
+        }
+
+        let terms: Vec<String> = positions.keys().cloned().collect();
+        for (term, term_positions) in positions {
+            self.postings.entry(term).or_default().insert(path.to_string(), term_positions);
+        }
+        self.documents.insert(path.to_string(), IndexedDocument {
+            size,
+            modified,
+            token_count: tokens.len() as u32,
+            terms,
+        });
+    }
+
+    /// Indexes one file, or drops it when it is gone, hidden or not text.
+    fn index_file(&mut self, project_dir: &Path, relative_path: &str, file_types: &FileTypeConfig) -> bool {
+        let full_path = project_dir.join(relative_path);
+        let text = if is_hidden_path(relative_path) {
+            None
+        } else {
+            read_text_file(&full_path, DEFAULT_MAX_FILE_SIZE, file_types)
+        };
+        match (text, file_signature(&full_path)) {
+            (Some(text), Some((size, modified))) => {
+                self.add(relative_path, &text, size, modified);
+                true
+            }
+            _ => self.remove(relative_path),
+        }
+    }
+
+    /// Brings a file or folder up to date after it was written, moved or deleted.
+    fn refresh_path(&mut self, project_dir: &Path, relative_path: &str, file_types: &FileTypeConfig) -> Result<usize, String> {
+        let relative_path = relative_path.replace('\\', "/").trim_matches('/').to_string();
+        let full_path = project_dir.join(&relative_path);
+
+        if full_path.is_file() {
+            return Ok(self.index_file(project_dir, &relative_path, file_types) as usize);
+        }
+
+        let mut changed = self.remove_tree(&relative_path);
+        if full_path.is_dir() && !is_hidden_path(&relative_path) {
+            let filter = GlobFilter::new(&[], &[])?;
+            let mut files = Vec::new();
+            walk_project_files(&full_path, &filter, &mut |_, nested| {
+                files.push(format!("{}/{}", relative_path, nested));
+                true
+            })?;
+            for file in files {
+                changed += self.index_file(project_dir, &file, file_types) as usize;
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Re-indexes files whose size or modification time changed and drops
+    /// files that disappeared. Returns how many documents changed.
+    fn refresh_all(&mut self, project_dir: &Path, file_types: &FileTypeConfig) -> Result<usize, String> {
+        let filter = GlobFilter::new(&[], &[])?;
+        let mut seen = HashSet::new();
+        let mut changed = 0;
+
+        if project_dir.exists() {
+            let mut files = Vec::new();
+            walk_project_files(project_dir, &filter, &mut |path, relative_path| {
+                files.push((relative_path.to_string(), file_signature(path)));
+                true
+            })?;
+            for (relative_path, signature) in files {
+                let current = self.documents.get(&relative_path)
+                    .map(|document| Some((document.size, document.modified)) == signature)
+                    .unwrap_or(false);
+                if !current && self.index_file(project_dir, &relative_path, file_types) {
+                    changed += 1;
+                }
+                seen.insert(relative_path);
+            }
+        }
+
+        let gone: Vec<String> = self.documents.keys().filter(|path| !seen.contains(*path)).cloned().collect();
+        for path in gone {
+            changed += self.remove(&path) as usize;
+        }
+        Ok(changed)
+    }
+
+    fn health(&self, project_dir: &Path, file_types: &FileTypeConfig) -> Result<IndexHealth, String> {
+        let filter = GlobFilter::new(&[], &[])?;
+        let mut health = IndexHealth {
+            project_id: self.project_id.clone(),
+            exists: true,
+            documents: self.documents.len(),
+            terms: self.postings.len(),
+            built_at: self.built_at.clone(),
+            updated_at: self.updated_at.clone(),
+            ..Default::default()
+        };
+        let mut seen = HashSet::new();
+
+        if project_dir.exists() {
+            walk_project_files(project_dir, &filter, &mut |path, relative_path| {
+                match self.documents.get(relative_path) {
+                    Some(document) => {
+                        if file_signature(path) != Some((document.size, document.modified)) {
+                            health.stale.push(relative_path.to_string());
+                        }
+                    }
+                    None => {
+                        if read_text_file(path, DEFAULT_MAX_FILE_SIZE, file_types).is_some() {
+                            health.unindexed.push(relative_path.to_string());
+                        }
+                    }
+                }
+                seen.insert(relative_path.to_string());
+                true
+            })?;
+        }
+        health.missing = self.documents.keys().filter(|path| !seen.contains(*path)).cloned().collect();
+        health.healthy = health.stale.is_empty() && health.missing.is_empty() && health.unindexed.is_empty();
+        Ok(health)
+    }
+
+    // Term frequency per document for one clause
+    fn clause_matches(&self, clause: &Clause) -> HashMap<&str, u32> {
+        let mut matches: HashMap<&str, u32> = HashMap::new();
+        match clause {
+            Clause::Term(term) => {
+                if let Some(paths) = self.postings.get(term) {
+                    for (path, positions) in paths {
+                        matches.insert(path, positions.len() as u32);
+                    }
+                }
+            }
+            Clause::Prefix(prefix) => {
+                let expansions = self.postings.range(prefix.clone()..)
+                    .take_while(|(term, _)| term.starts_with(prefix.as_str()))
+                    .take(MAX_PREFIX_EXPANSIONS);
+                for (_, paths) in expansions {
+                    for (path, positions) in paths {
+                        *matches.entry(path.as_str()).or_default() += positions.len() as u32;
+                    }
+                }
+            }
+            Clause::Phrase(words) => {
+                let postings: Option<Vec<&BTreeMap<String, Vec<u32>>>> = words.iter().map(|word| self.postings.get(word)).collect();
+                let postings = match postings {
+                    Some(postings) => postings,
+                    None => return matches,
+                };
+                for (path, first_positions) in postings[0] {
+                    let count = first_positions.iter()
+                        .filter(|&&start| {
+                            postings[1..].iter().enumerate().all(|(offset, paths)| {
+                                paths.get(path)
+                                    .map(|positions| positions.binary_search(&(start + offset as u32 + 1)).is_ok())
+                                    .unwrap_or(false)
+                            })
+                        })
+                        .count();
+                    if count > 0 {
+                        matches.insert(path, count as u32);
+                    }
+                }
+            }
+        }
+        matches
+    }
+
+    /// BM25-ranked paths matching every clause of the query.
+    fn query(&self, query: &str) -> Vec<(String, f64)> {
+        let clauses = parse_query(query);
+        if clauses.is_empty() || self.documents.is_empty() {
+            return Vec::new();
+        }
+
+        let total_documents = self.documents.len() as f64;
+        let average_length = self.documents.values().map(|document| document.token_count as f64).sum::<f64>() / total_documents;
+        let mut scores: Option<HashMap<&str, f64>> = None;
+
+        for clause in &clauses {
+            let matches = self.clause_matches(clause);
+            let frequency = matches.len() as f64;
+            let idf = (1.0 + (total_documents - frequency + 0.5) / (frequency + 0.5)).ln();
+
+            let mut next = HashMap::new();
+            for (path, term_frequency) in matches {
+                // Documents must match every clause
+                let previous = match &scores {
+                    Some(scores) => match scores.get(path) {
+                        Some(score) => *score,
+                        None => continue,
+                    },
+                    None => 0.0,
+                };
+                let length = self.documents.get(path).map(|document| document.token_count as f64).unwrap_or(average_length);
+                let tf = term_frequency as f64;
+                let normalised = tf * (BM25_K1 + 1.0)
+                    / (tf + BM25_K1 * (1.0 - BM25_B + BM25_B * length / average_length.max(1.0)));
+                next.insert(path, previous + idf * normalised);
+            }
+            scores = Some(next);
+        }
+
+        let mut ranked: Vec<(String, f64)> = scores.unwrap_or_default()
+            .into_iter()
+            .map(|(path, score)| (path.to_string(), score))
+            .collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then_with(|| a.0.cmp(&b.0)));
+        ranked
+    }
+}
+
+// First line containing a word the query matched, for display
+fn hit_snippet(full_path: &Path, clauses: &[Clause], file_types: &FileTypeConfig) -> Option<(usize, String)> {
+    let text = read_text_file(full_path, DEFAULT_MAX_FILE_SIZE, file_types)?;
+    let matches_word = |word: &String| {
+        clauses.iter().any(|clause| match clause {
+            Clause::Term(term) => word == term,
+            Clause::Prefix(prefix) => word.starts_with(prefix.as_str()),
+            Clause::Phrase(words) => words.contains(word),
+        })
+    };
+    text.lines()
+        .enumerate()
+        .find(|(_, line)| tokenize(line).iter().any(&matches_word))
+        .map(|(index, line)| (index + 1, line.trim().chars().take(MAX_SNIPPET_CHARS).collect()))
+}
+
+fn index_file_path(app: &AppHandle, project_id: &str) -> Result<PathBuf, String> {
+    Ok(get_search_index_dir(app)?.join(format!("{}.json", project_id)))
+}
+
+fn load_index(app: &AppHandle, project_id: &str) -> Result<Option<ProjectIndex>, String> {
+    let path = index_file_path(app, project_id)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = safe_file_operation(|| fs::read_to_string(&path), "Failed to read search index")?;
+    match serde_json::from_str::<ProjectIndex>(&content) {
+        Ok(index) if index.version == INDEX_VERSION => Ok(Some(index)),
+        // An unreadable or outdated index is rebuilt rather than trusted
+        _ => {
+            println!("⚠️ INDEX: Discarding unreadable index for project {}", project_id);
+            Ok(None)
+        }
+    }
+}
+
+fn save_index(app: &AppHandle, index: &mut ProjectIndex) -> Result<(), String> {
+    index.updated_at = Some(chrono::Utc::now().to_rfc3339());
+    let path = index_file_path(app, &index.project_id)?;
+    ensure_parent_dir(&path).map_err(|e| format!("Failed to create search index directory: {}", e))?;
+
+    let json = serde_json::to_string(index)
+        .map_err(|e| format!("Failed to serialize search index: {}", e))?;
+    let temp_path = path.with_extension("json.tmp");
+    safe_file_operation(|| fs::write(&temp_path, json), "Failed to write search index")?;
+    safe_file_operation(|| fs::rename(&temp_path, &path), "Failed to replace search index")
+}
+
+// What with_index does with a project whose index is not in memory yet
+#[derive(Clone, Copy, PartialEq)]
+enum Load {
+    InMemory,  // Skip it: loading refreshes the index anyway
+    FromDisk,  // Load and refresh it, skipping projects without an index
+    OrCreate,  // Start an empty index when there is none on disk
+}
+
+/// Runs `update` against a project's index, loading it first as `load` allows.
+/// Loading walks the whole project, so it must not run on an async thread.
+/// When `update` reports a change, a save is scheduled rather than rewriting
+/// the index every time.
+fn with_index<R, F>(app: &AppHandle, project_id: &str, load: Load, update: F) -> Result<Option<R>, String>
+where
+    F: FnOnce(&mut ProjectIndex) -> Result<(R, bool), String>,
+{
+    let slot = app.state::<SearchIndexes>().slot(project_id);
+    let mut slot = slot.lock().unwrap();
+
+    if slot.index.is_none() {
+        if load == Load::InMemory {
+            return Ok(None);
+        }
+        match load_index(app, project_id)? {
+            Some(mut index) => {
+                // Changes made after the last save, such as those not flushed before the app closed
+                if index.refresh_all(&get_project_dir(app, project_id)?, &file_type_config(app))? > 0 {
+                    schedule_flush(app, project_id, &mut slot);
+                }
+                slot.index = Some(index);
+            }
+            None if load == Load::OrCreate => slot.index = Some(ProjectIndex::new(project_id)),
+            None => return Ok(None),
+        }
+    }
+
+    let (result, changed) = update(slot.index.as_mut().unwrap())?;
+    if changed {
+        schedule_flush(app, project_id, &mut slot);
+    }
+    Ok(Some(result))
+}
+
+// The first change after a save schedules the next one, which covers every change made meanwhile
+fn schedule_flush(app: &AppHandle, project_id: &str, slot: &mut IndexSlot) {
+    if slot.flush_pending {
+        return;
+    }
+    slot.flush_pending = true;
+
+    let app = app.clone();
+    let project_id = project_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(FLUSH_DELAY).await;
+        if let Err(e) = flush_index(&app, &project_id) {
+            eprintln!("⚠️ INDEX: Failed to save index for project {}: {}", project_id, e);
+        }
+    });
+}
+
+/// Saves a project's index now if it has unsaved changes.
+fn flush_index(app: &AppHandle, project_id: &str) -> Result<(), String> {
+    let slot = match app.state::<SearchIndexes>().indexes.lock().unwrap().get(project_id) {
+        Some(slot) => slot.clone(),
+        None => return Ok(()),
+    };
+    let mut slot = slot.lock().unwrap();
+    if !slot.flush_pending {
+        return Ok(());
+    }
+    slot.flush_pending = false;
+    match slot.index.as_mut() {
+        Some(index) => save_index(app, index),
+        None => Ok(()),
+    }
+}
+
+// Indexes a project from scratch and records when that happened
+fn build_index(app: &AppHandle, project_id: &str) -> Result<(), String> {
+    let project_dir = get_project_dir(app, project_id)?;
+    let file_types = file_type_config(app);
+    with_index(app, project_id, Load::OrCreate, |index| {
+        *index = ProjectIndex::new(project_id);
+        index.refresh_all(&project_dir, &file_types)?;
+        index.built_at = Some(chrono::Utc::now().to_rfc3339());
+        println!("🗂️ INDEX: Built index for project {} ({} documents, {} terms)",
+                 project_id, index.documents.len(), index.postings.len());
+        Ok(((), true))
+    })?;
+    flush_index(app, project_id)
+}
+
+// Project-relative form of a path passed to update_search_index, or None when
+// it is outside the project
+fn index_relative_path(project_dir: &Path, path: &Path) -> Option<String> {
+    let relative_path = if path.is_absolute() {
+        path.strip_prefix(project_dir).ok()?
+    } else {
+        path
+    };
+    let relative_path = relative_path.to_string_lossy().replace('\\', "/");
+    resolve_project_path(project_dir, &relative_path).ok()?;
+    Some(relative_path)
+}
+
+/// Updates the index for files or folders that were just written, imported,
+/// moved or deleted. Paths may be project-relative or absolute inside the
+/// project. Only indexes already in memory are touched, in the background; one
+/// loaded later picks the change up when it is refreshed. Indexing problems
+/// are logged, never returned, so they cannot fail the operation that touched
+/// the files.
+pub(crate) fn update_search_index<P: AsRef<Path>>(app: &AppHandle, project_id: &str, paths: &[P]) {
+    let paths: Vec<PathBuf> = paths.iter().map(|path| path.as_ref().to_path_buf()).collect();
+    let (app, project_id) = (app.clone(), project_id.to_string());
+    tauri::async_runtime::spawn_blocking(move || {
+        let file_types = file_type_config(&app);
+        let result = get_project_dir(&app, &project_id).and_then(|project_dir| {
+            with_index(&app, &project_id, Load::InMemory, |index| {
+                let mut changed = 0;
+                for path in &paths {
+                    match index_relative_path(&project_dir, path) {
+                        Some(relative_path) => changed += index.refresh_path(&project_dir, &relative_path, &file_types)?,
+                        None => eprintln!("⚠️ INDEX: Not indexing {:?}, it is outside project {}", path, project_id),
+                    }
+                }
+                Ok((changed, changed > 0))
+            })
+        });
+        if let Err(e) = result {
+            eprintln!("⚠️ INDEX: Failed to update index for project {}: {}", project_id, e);
+        }
+    });
+}
+
+/// Catches up with changes made outside the app, as found by sync. Runs in
+/// the background like update_search_index.
+pub(crate) fn refresh_search_index(app: &AppHandle, project_id: &str) {
+    let (app, project_id) = (app.clone(), project_id.to_string());
+    tauri::async_runtime::spawn_blocking(move || {
+        let file_types = file_type_config(&app);
+        let result = get_project_dir(&app, &project_id).and_then(|project_dir| {
+            with_index(&app, &project_id, Load::InMemory, |index| {
+                let changed = index.refresh_all(&project_dir, &file_types)?;
+                Ok((changed, changed > 0))
+            })
+        });
+        match result {
+            Ok(Some(changed)) if changed > 0 => println!("🗂️ INDEX: Refreshed {} document(s) in project {}", changed, project_id),
+            Ok(_) => {}
+            Err(e) => eprintln!("⚠️ INDEX: Failed to refresh index for project {}: {}", project_id, e),
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn query_search_index(
+    app: AppHandle,
+    request: IndexQueryRequest,
+) -> Result<IndexQueryResult, String> {
+    let started = Instant::now();
+    let clauses = parse_query(&request.query);
+    if clauses.is_empty() {
+        return Err("Search query has no searchable words".to_string());
+    }
+    let targets = search_targets(&load_project_data(&app)?, &app, request.project_id.as_deref(), request.client_id.as_deref())?;
+
+    let mut hits = Vec::new();
+    for target in &targets {
+        let (query_app, project_id, query) = (app.clone(), target.project_id.clone(), request.query.clone());
+        let ranked = tauri::async_runtime::spawn_blocking(move || -> Result<Vec<(String, f64)>, String> {
+            let run_query = |index: &mut ProjectIndex| Ok((index.query(&query), false));
+            match with_index(&query_app, &project_id, Load::FromDisk, run_query)? {
+                Some(ranked) => Ok(ranked),
+                None => {
+                    // The first query against a project builds its index
+                    build_index(&query_app, &project_id)?;
+                    Ok(with_index(&query_app, &project_id, Load::FromDisk, run_query)?.unwrap_or_default())
+                }
+            }
+        })
+            .await
+            .map_err(|e| format!("Index query failed: {}", e))??;
+        hits.extend(ranked.into_iter().map(|(path, score)| IndexSearchHit {
+            project_id: target.project_id.clone(),
+            node_id: target.node_ids.get(&path).cloned(),
+            path,
+            score,
+            line: None,
+            snippet: None,
+        }));
+    }
+
+    hits.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    let total_hits = hits.len();
+    hits.truncate(request.limit.unwrap_or(DEFAULT_QUERY_LIMIT));
+
+    // Snippets only for the hits that are returned
+    for hit in &mut hits {
+        if let Some(target) = targets.iter().find(|target| target.project_id == hit.project_id) {
+            if let Some((line, snippet)) = hit_snippet(&target.root.join(&hit.path), &clauses, &target.file_types) {
+                hit.line = Some(line);
+                hit.snippet = Some(snippet);
+            }
+        }
+    }
+
+    println!("🗂️ INDEX: '{}' matched {} document(s)", request.query, total_hits);
+    Ok(IndexQueryResult {
+        hits,
+        total_hits,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+#[tauri::command]
+pub async fn rebuild_search_index(
+    app: AppHandle,
+    project_id: String,
+) -> Result<IndexHealth, String> {
+    println!("🔨 INDEX: Rebuilding index for project {}", project_id);
+    let build_app = app.clone();
+    let build_project_id = project_id.clone();
+    tauri::async_runtime::spawn_blocking(move || build_index(&build_app, &build_project_id))
+        .await
+        .map_err(|e| format!("Index build failed: {}", e))??;
+    get_search_index_health(app, project_id).await
+}
+
+#[tauri::command]
+pub async fn get_search_index_health(
+    app: AppHandle,
+    project_id: String,
+) -> Result<IndexHealth, String> {
+    let project_dir = get_project_dir(&app, &project_id)?;
+    let file_types = file_type_config(&app);
+    let (health_app, health_project_id) = (app.clone(), project_id.clone());
+    let health = tauri::async_runtime::spawn_blocking(move || -> Result<Option<IndexHealth>, String> {
+        let health = with_index(&health_app, &health_project_id, Load::FromDisk, |index| {
+            Ok((index.health(&project_dir, &file_types)?, false))
+        })?;
+        // So the reported size is that of the current index
+        flush_index(&health_app, &health_project_id)?;
+        Ok(health)
+    })
+        .await
+        .map_err(|e| format!("Index health check failed: {}", e))??;
+
+    Ok(match health {
+        Some(mut health) => {
+            health.size_bytes = fs::metadata(index_file_path(&app, &project_id)?).map(|meta| meta.len()).unwrap_or(0);
+            health
+        }
+        None => IndexHealth {
+            project_id,
+            ..Default::default()
+        },
+    })
+}
+
+/// Forgets a project's index, e.g. when the project itself is deleted.
+pub(crate) fn remove_search_index(app: &AppHandle, project_id: &str) -> Result<(), String> {
+    app.state::<SearchIndexes>().indexes.lock().unwrap().remove(project_id);
+    let path = index_file_path(app, project_id)?;
+    if path.exists() {
+        safe_file_operation(|| fs::remove_file(&path), "Failed to delete search index")?;
+        println!("🗑️ INDEX: Deleted index for project {}", project_id);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_search_index(app: AppHandle, project_id: String) -> Result<(), String> {
+    remove_search_index(&app, &project_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project() -> (tempfile::TempDir, ProjectIndex) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("notes")).unwrap();
+        fs::write(root.join("notes/soil.md"), "Soil samples from the north field.\nThe soil pH was low.\n").unwrap();
+        fs::write(root.join("notes/water.md"), "Water samples. Soil runoff after rain.").unwrap();
+        fs::write(root.join("report.txt"), "Field report: sampling schedule for the north field").unwrap();
+        fs::write(root.join("photo.png"), "soil").unwrap();
+
+        let mut index = ProjectIndex::new("p1");
+        index.refresh_all(root, &FileTypeConfig::default()).unwrap();
+        (dir, index)
+    }
+
+    fn paths(ranked: Vec<(String, f64)>) -> Vec<String> {
+        ranked.into_iter().map(|(path, _)| path).collect()
+    }
+
+    #[test]
+    fn parses_terms_phrases_and_prefixes() {
+        assert_eq!(parse_query(r#"Soil "north  field" samp* pH-value"#), vec![
+            Clause::Term("soil".to_string()),
+            Clause::Phrase(vec!["north".to_string(), "field".to_string()]),
+            Clause::Prefix("samp".to_string()),
+            Clause::Phrase(vec!["ph".to_string(), "value".to_string()]),
+        ]);
+        assert!(parse_query("  \"\" * ").is_empty());
+    }
+
+    #[test]
+    fn ranks_terms_and_matches_phrases_and_prefixes() {
+        let (_dir, index) = project();
+
+        // Binary files are never indexed
+        assert_eq!(index.documents.len(), 3);
+        assert_eq!(paths(index.query("soil")), vec!["notes/soil.md", "notes/water.md"]);
+        // Equal phrase counts, so the shorter document ranks first
+        assert_eq!(paths(index.query("\"north field\"")), vec!["report.txt", "notes/soil.md"]);
+        assert_eq!(paths(index.query("\"field north\"")), Vec::<String>::new());
+        assert_eq!(paths(index.query("sampl*")).len(), 3);
+        assert_eq!(paths(index.query("sampl* water")), vec!["notes/water.md"]);
+    }
+
+    #[test]
+    fn refresh_tracks_edits_moves_and_deletes() {
+        let (dir, mut index) = project();
+        let root = dir.path();
+
+        fs::write(root.join("notes/soil.md"), "Nothing about dirt here").unwrap();
+        assert_eq!(index.refresh_path(root, "notes/soil.md", &FileTypeConfig::default()).unwrap(), 1);
+        assert_eq!(paths(index.query("soil")), vec!["notes/water.md"]);
+        assert!(!index.postings.contains_key("ph"));
+
+        fs::rename(root.join("notes"), root.join("archive")).unwrap();
+        index.refresh_path(root, "notes", &FileTypeConfig::default()).unwrap();
+        index.refresh_path(root, "archive", &FileTypeConfig::default()).unwrap();
+        assert_eq!(paths(index.query("dirt")), vec!["archive/soil.md"]);
+
+        fs::remove_file(root.join("report.txt")).unwrap();
+        fs::write(root.join("new.txt"), "north").unwrap();
+        assert_eq!(index.refresh_all(root, &FileTypeConfig::default()).unwrap(), 2);
+        assert_eq!(paths(index.query("north")), vec!["new.txt"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn only_paths_inside_the_project_are_indexed() {
+        let project_dir = Path::new("/data/projects/p1");
+        assert_eq!(index_relative_path(project_dir, Path::new("/data/projects/p1/notes/a.md")).as_deref(), Some("notes/a.md"));
+        assert_eq!(index_relative_path(project_dir, Path::new("notes/a.md")).as_deref(), Some("notes/a.md"));
+        assert_eq!(index_relative_path(project_dir, Path::new("/etc/passwd")), None);
+        assert_eq!(index_relative_path(project_dir, Path::new("../p2/a.md")), None);
+        assert_eq!(index_relative_path(project_dir, Path::new("/data/projects/p1/../p2/a.md")), None);
+    }
+
+    #[test]
+    fn each_project_has_its_own_index_lock() {
+        let indexes = SearchIndexes::default();
+        let first = indexes.slot("p1");
+        assert!(Arc::ptr_eq(&first, &indexes.slot("p1")));
+
+        // Holding one project's index leaves the others free
+        let _held = first.lock().unwrap();
+        let other = indexes.slot("p2");
+        assert!(other.try_lock().is_ok());
+        assert!(indexes.slot("p1").try_lock().is_err());
+    }
+
+    #[test]
+    fn health_reports_stale_missing_and_unindexed_files() {
+        let (dir, mut index) = project();
+        let root = dir.path();
+        assert!(index.health(root, &FileTypeConfig::default()).unwrap().healthy);
+
+        fs::write(root.join("report.txt"), "Rewritten outside the app, with a different size").unwrap();
+        fs::remove_file(root.join("notes/water.md")).unwrap();
+        fs::write(root.join("extra.csv"), "a,b").unwrap();
+
+        let health = index.health(root, &FileTypeConfig::default()).unwrap();
+        assert!(!health.healthy);
+        assert_eq!(health.stale, vec!["report.txt"]);
+        assert_eq!(health.missing, vec!["notes/water.md"]);
+        assert_eq!(health.unindexed, vec!["extra.csv"]);
+
+        index.refresh_all(root, &FileTypeConfig::default()).unwrap();
+        assert!(index.health(root, &FileTypeConfig::default()).unwrap().healthy);
+    }
+}
@@ -0,0 +1,861 @@
+// src-tauri/src/commands/sessions.rs
+// Long-lived Python REPL / Jupyter kernel sessions, one per project
+
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, Command};
+use tokio::sync::oneshot;
+use uuid::Uuid;
+use super::{
+    environment::{load_execution_environment, ExecutionEnvironment},
+    history::record_session_execution,
+    utils::*,
+};
+
+// Python side of the session. It reads one JSON request per line from stdin and
+// answers with JSON lines on stdout, so user output never mixes with the protocol.
+// In "jupyter" mode it bridges the same protocol to a kernel via jupyter_client.
+const SESSION_DRIVER: &str = r#"
+import sys, json, ast, io, queue, signal, traceback
+
+_out = sys.stdout
+
+def _send(msg):
+    _out.write(json.dumps(msg) + "\n")
+    _out.flush()
+
+def _requests():
+    while True:
+        try:
+            line = sys.stdin.readline()
+        except KeyboardInterrupt:
+            continue
+        if not line:
+            return
+        if line.strip():
+            yield json.loads(line)
+
+class _Stream(io.TextIOBase):
+    def __init__(self, name):
+        self.name = name
+        self.exec_id = None
+    def writable(self):
+        return True
+    def write(self, text):
+        if text:
+            _send({"type": "stream", "id": self.exec_id, "name": self.name, "text": text})
+        return len(text)
+
+def _run_python():
+    ns = {"__name__": "__main__"}
+    stdout, stderr = _Stream("stdout"), _Stream("stderr")
+    sys.stdout, sys.stderr = stdout, stderr
+    _send({"type": "ready", "version": sys.version.split()[0]})
+    for req in _requests():
+        stdout.exec_id = stderr.exec_id = req["id"]
+        done = {"type": "done", "id": req["id"], "status": "ok"}
+        try:
+            tree = ast.parse(req["code"], "<cell>", "exec")
+            last = None
+            if tree.body and isinstance(tree.body[-1], ast.Expr):
+                last = ast.Expression(tree.body.pop().value)
+            exec(compile(tree, "<cell>", "exec"), ns)
+            if last is not None:
+                value = eval(compile(last, "<cell>", "eval"), ns)
+                if value is not None:
+                    done["result"] = repr(value)
+        except KeyboardInterrupt:
+            done.update(status="interrupted", error="KeyboardInterrupt")
+        except BaseException:
+            done.update(status="error", error=traceback.format_exc())
+        _send(done)
+
+def _run_jupyter(kernel_name):
+    import jupyter_client
+    km = jupyter_client.KernelManager(kernel_name=kernel_name or "python3")
+    km.start_kernel()
+    kc = km.client()
+    kc.start_channels()
+    kc.wait_for_ready(timeout=60)
+    signal.signal(signal.SIGINT, lambda *_: km.interrupt_kernel())
+    _send({"type": "ready", "version": kc.kernel_info(reply=True)["content"].get("implementation_version")})
+    try:
+        for req in _requests():
+            msg_id = kc.execute(req["code"])
+            done = {"type": "done", "id": req["id"], "status": "ok"}
+            while True:
+                try:
+                    msg = kc.get_iopub_msg(timeout=1)
+                except queue.Empty:
+                    # A kernel that dies mid-execution never reports idle
+                    if not km.is_alive():
+                        done.update(status="error", error="Kernel died during execution")
+                        break
+                    continue
+                if msg["parent_header"].get("msg_id") != msg_id:
+                    continue
+                kind, content = msg["msg_type"], msg["content"]
+                if kind == "stream":
+                    _send({"type": "stream", "id": req["id"], "name": content["name"], "text": content["text"]})
+                elif kind in ("execute_result", "display_data"):
+                    _send({"type": "display", "id": req["id"], "data": content["data"]})
+                    if kind == "execute_result":
+                        done["result"] = content["data"].get("text/plain")
+                elif kind == "error":
+                    interrupted = content.get("ename") == "KeyboardInterrupt"
+                    done.update(status="interrupted" if interrupted else "error",
+                                error="\n".join(content.get("traceback", [])))
+                elif kind == "status" and content["execution_state"] == "idle":
+                    break
+            _send(done)
+    finally:
+        kc.stop_channels()
+        km.shutdown_kernel(now=True)
+
+if sys.argv[1] == "jupyter":
+    _run_jupyter(sys.argv[2] if len(sys.argv) > 2 else None)
+else:
+    _run_python()
+"#;
+
+const SESSION_STARTUP_TIMEOUT_SECS: u64 = 60;
+// Driver stderr kept for startup errors; a chatty kernel would otherwise grow it forever
+const MAX_DRIVER_ERROR_BYTES: usize = 16 * 1024;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SessionInfo {
+    pub session_id: String,
+    pub project_id: String,
+    pub kind: String, // "python" or "jupyter"
+    pub python_cmd: String,
+    pub kernel_name: Option<String>,
+    pub version: Option<String>,
+    pub started_at: i64,
+    pub execution_count: u64,
+    pub busy: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SessionOutputEvent {
+    pub session_id: String,
+    pub project_id: String,
+    pub execution_id: String,
+    pub stream: String, // "stdout", "stderr" or "display"
+    pub text: String,
+    pub data: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionExecutionResult {
+    pub session_id: String,
+    pub execution_id: String,
+    pub execution_count: u64,
+    pub status: String, // "ok", "error" or "interrupted"
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+    pub result: Option<String>,
+    pub error: Option<String>,
+    pub display_data: Vec<serde_json::Value>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum DriverMessage {
+    Ready {
+        version: Option<String>,
+    },
+    Stream {
+        id: Option<String>,
+        name: String,
+        text: String,
+    },
+    Display {
+        id: String,
+        data: serde_json::Value,
+    },
+    Done {
+        id: String,
+        status: String,
+        result: Option<String>,
+        error: Option<String>,
+    },
+}
+
+#[derive(Default)]
+struct PendingExecution {
+    stdout: String,
+    stderr: String,
+    held_stdout: String, // Possible start of a secret, waiting for the next write
+    held_stderr: String,
+    display_data: Vec<serde_json::Value>,
+    sender: Option<oneshot::Sender<CompletedExecution>>,
+}
+
+struct CompletedExecution {
+    status: String,
+    stdout: String,
+    stderr: String,
+    result: Option<String>,
+    error: Option<String>,
+    display_data: Vec<serde_json::Value>,
+}
+
+type PendingMap = Arc<Mutex<HashMap<String, PendingExecution>>>;
+
+struct PythonSession {
+    info: Mutex<SessionInfo>,
+    child: tokio::sync::Mutex<Child>,
+    stdin: tokio::sync::Mutex<ChildStdin>,
+    pending: PendingMap,
+    pid: Option<u32>,
+    exited: Arc<AtomicBool>, // Set once the driver's stdout closes
+}
+
+impl PythonSession {
+    fn snapshot(&self) -> SessionInfo {
+        let mut info = self.info.lock().unwrap().clone();
+        info.busy = !self.pending.lock().unwrap().is_empty();
+        info
+    }
+}
+
+/// Registry of running sessions, keyed by project id.
+#[derive(Default)]
+pub struct SessionManager {
+    sessions: Mutex<HashMap<String, Arc<PythonSession>>>,
+}
+
+impl SessionManager {
+    /// The project's running session; one whose driver exited is dropped.
+    fn get(&self, project_id: &str) -> Option<Arc<PythonSession>> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let session = sessions.get(project_id)?.clone();
+        if session.exited.load(Ordering::SeqCst) {
+            sessions.remove(project_id);
+            return None;
+        }
+        Some(session)
+    }
+
+    /// Like get, but a session of another kind than the one asked for is an
+    /// error rather than a silent stand-in.
+    fn get_kind(&self, project_id: &str, kind: &str) -> Result<Option<Arc<PythonSession>>, String> {
+        let session = match self.get(project_id) {
+            Some(session) => session,
+            None => return Ok(None),
+        };
+        let running = session.info.lock().unwrap().kind.clone();
+        if running != kind {
+            return Err(format!(
+                "A {} session is already running for this project; stop it before starting a {} session",
+                running, kind
+            ));
+        }
+        Ok(Some(session))
+    }
+
+    fn insert(&self, session: Arc<PythonSession>) {
+        let project_id = session.info.lock().unwrap().project_id.clone();
+        self.sessions.lock().unwrap().insert(project_id, session);
+    }
+
+    fn remove(&self, project_id: &str) -> Option<Arc<PythonSession>> {
+        self.sessions.lock().unwrap().remove(project_id)
+    }
+
+    // Only the exiting session itself; a restart may already have replaced it
+    fn remove_session(&self, project_id: &str, session_id: &str) {
+        let mut sessions = self.sessions.lock().unwrap();
+        let current = sessions.get(project_id)
+            .map_or(false, |session| session.info.lock().unwrap().session_id == session_id);
+        if current {
+            sessions.remove(project_id);
+        }
+    }
+}
+
+// Appends a line, dropping the oldest text once the buffer is over `cap` bytes
+fn push_capped(buffer: &mut String, line: &str, cap: usize) {
+    buffer.push_str(line);
+    buffer.push('\n');
+    if buffer.len() > cap {
+        let mut cut = buffer.len() - cap;
+        while !buffer.is_char_boundary(cut) {
+            cut += 1;
+        }
+        buffer.drain(..cut);
+    }
+}
+
+async fn spawn_session(
+    app: &AppHandle,
+    project_id: &str,
+    kind: &str,
+    python_cmd: Option<String>,
+    kernel_name: Option<String>,
+) -> Result<Arc<PythonSession>, String> {
+    if kind != "python" && kind != "jupyter" {
+        return Err(format!("Unsupported session kind: {}", kind));
+    }
+
+    let project_dir = get_project_dir(app, project_id)?;
+    safe_file_operation(
+        || std::fs::create_dir_all(&project_dir),
+        "Failed to create project directory"
+    )?;
+
+    // The environment is captured at startup; restart the session to pick up changes
+    let environment = load_execution_environment(app, project_id)?;
+
+    let candidates = match python_cmd {
+        Some(cmd) => vec![cmd],
+        None => vec!["python3".to_string(), "python".to_string(), "py".to_string()],
+    };
+
+    let mut last_error = String::new();
+    for cmd in candidates {
+        let mut command = Command::new(&cmd);
+        command
+            .arg("-u")
+            .arg("-c")
+            .arg(SESSION_DRIVER)
+            .arg(kind);
+        if let Some(name) = &kernel_name {
+            command.arg(name);
+        }
+
+        let mut child = match command
+            .current_dir(&project_dir)
+            .envs(environment.vars.iter().map(|(key, value)| (key, value)))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) => {
+                last_error = format!("{}: {}", cmd, e);
+                continue;
+            }
+        };
+
+        let stdin = child.stdin.take().ok_or("Failed to open session stdin")?;
+        let stdout = child.stdout.take().ok_or("Failed to open session stdout")?;
+        let stderr = child.stderr.take().ok_or("Failed to open session stderr")?;
+
+        let info = SessionInfo {
+            session_id: Uuid::new_v4().to_string(),
+            project_id: project_id.to_string(),
+            kind: kind.to_string(),
+            python_cmd: cmd.clone(),
+            kernel_name: kernel_name.clone(),
+            version: None,
+            started_at: chrono::Utc::now().timestamp(),
+            execution_count: 0,
+            busy: false,
+        };
+
+        let pending: PendingMap = Arc::new(Mutex::new(HashMap::new()));
+        let exited = Arc::new(AtomicBool::new(false));
+        let (ready_tx, ready_rx) = oneshot::channel();
+
+        // Collect driver-level errors (e.g. jupyter_client missing) for startup failures
+        let driver_errors = Arc::new(Mutex::new(String::new()));
+        let stderr_errors = driver_errors.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                push_capped(&mut stderr_errors.lock().unwrap(), &line, MAX_DRIVER_ERROR_BYTES);
+            }
+        });
+
+        tauri::async_runtime::spawn(read_driver_output(
+            app.clone(),
+            info.clone(),
+            stdout,
+            pending.clone(),
+            exited.clone(),
+            ready_tx,
+            environment.clone(),
+        ));
+
+        let version = match tokio::time::timeout(
+            Duration::from_secs(SESSION_STARTUP_TIMEOUT_SECS),
+            ready_rx,
+        ).await {
+            Ok(Ok(version)) => version,
+            Ok(Err(_)) => {
+                let _ = child.kill().await;
+                // Give the stderr reader a moment to drain
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                let errors = environment.redact(driver_errors.lock().unwrap().trim());
+                return Err(format!("Session failed to start with {}: {}", cmd, errors));
+            }
+            Err(_) => {
+                let _ = child.kill().await;
+                return Err("Timed out waiting for session to start".to_string());
+            }
+        };
+
+        let pid = child.id();
+        let session = PythonSession {
+            info: Mutex::new(SessionInfo { version, ..info }),
+            child: tokio::sync::Mutex::new(child),
+            stdin: tokio::sync::Mutex::new(stdin),
+            pending,
+            pid,
+            exited,
+        };
+
+        return Ok(Arc::new(session));
+    }
+
+    Err(format!("Failed to start Python session. Last error: {}", last_error))
+}
+
+async fn read_driver_output(
+    app: AppHandle,
+    info: SessionInfo,
+    stdout: tokio::process::ChildStdout,
+    pending: PendingMap,
+    exited: Arc<AtomicBool>,
+    ready_tx: oneshot::Sender<Option<String>>,
+    environment: ExecutionEnvironment,
+) {
+    let SessionInfo { session_id, project_id, .. } = info;
+    let mut ready_tx = Some(ready_tx);
+    let mut lines = BufReader::new(stdout).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        let message: DriverMessage = match serde_json::from_str(&line) {
+            Ok(message) => message,
+            Err(_) => {
+                println!("⚠️ SESSION: Ignoring unexpected driver output: {}", line);
+                continue;
+            }
+        };
+
+        match message {
+            DriverMessage::Ready { version } => {
+                if let Some(tx) = ready_tx.take() {
+                    let _ = tx.send(version);
+                }
+            }
+            DriverMessage::Stream { id, name, text } => {
+                let execution_id = match id {
+                    Some(id) => id,
+                    None => continue,
+                };
+                // A secret can be split across writes, so redaction spans them
+                let text = match pending.lock().unwrap().get_mut(&execution_id) {
+                    Some(execution) => {
+                        let (output, held) = if name == "stderr" {
+                            (&mut execution.stderr, &mut execution.held_stderr)
+                        } else {
+                            (&mut execution.stdout, &mut execution.held_stdout)
+                        };
+                        let text = environment.redact_chunk(held, &text);
+                        output.push_str(&text);
+                        text
+                    }
+                    None => environment.redact(&text),
+                };
+                if text.is_empty() {
+                    continue;
+                }
+                let _ = app.emit_all("python-session-output", SessionOutputEvent {
+                    session_id: session_id.clone(),
+                    project_id: project_id.clone(),
+                    execution_id,
+                    stream: name,
+                    text,
+                    data: None,
+                });
+            }
+            DriverMessage::Display { id, data } => {
+                let data = environment.redact_json(&data);
+                if let Some(execution) = pending.lock().unwrap().get_mut(&id) {
+                    execution.display_data.push(data.clone());
+                }
+                let text = data.get("text/plain")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string();
+                let _ = app.emit_all("python-session-output", SessionOutputEvent {
+                    session_id: session_id.clone(),
+                    project_id: project_id.clone(),
+                    execution_id: id,
+                    stream: "display".to_string(),
+                    text,
+                    data: Some(data),
+                });
+            }
+            DriverMessage::Done { id, status, result, error } => {
+                let execution = pending.lock().unwrap().remove(&id);
+                if let Some(mut execution) = execution {
+                    // Whatever was held back did not turn into a secret
+                    let held = [
+                        ("stdout", std::mem::take(&mut execution.held_stdout)),
+                        ("stderr", std::mem::take(&mut execution.held_stderr)),
+                    ];
+                    for (stream, text) in held.into_iter().filter(|(_, text)| !text.is_empty()) {
+                        if stream == "stderr" {
+                            execution.stderr.push_str(&text);
+                        } else {
+                            execution.stdout.push_str(&text);
+                        }
+                        let _ = app.emit_all("python-session-output", SessionOutputEvent {
+                            session_id: session_id.clone(),
+                            project_id: project_id.clone(),
+                            execution_id: id.clone(),
+                            stream: stream.to_string(),
+                            text,
+                            data: None,
+                        });
+                    }
+
+                    if let Some(sender) = execution.sender.take() {
+                        let _ = sender.send(CompletedExecution {
+                            status,
+                            stdout: execution.stdout,
+                            stderr: execution.stderr,
+                            result: result.map(|result| environment.redact(&result)),
+                            error: error.map(|error| environment.redact(&error)),
+                            display_data: execution.display_data,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    // Driver exited: dropping the senders fails every outstanding execution, and
+    // the next execution starts a fresh session instead of writing to a dead one
+    exited.store(true, Ordering::SeqCst);
+    pending.lock().unwrap().clear();
+    app.state::<SessionManager>().remove_session(&project_id, &session_id);
+    println!("🐍 SESSION: Driver for project {} exited", project_id);
+}
+
+// Code and cells run the same in either kind, so any live session will do
+async fn ensure_session(
+    app: &AppHandle,
+    manager: &SessionManager,
+    project_id: &str,
+) -> Result<Arc<PythonSession>, String> {
+    if let Some(session) = manager.get(project_id) {
+        return Ok(session);
+    }
+
+    let session = spawn_session(app, project_id, "python", None, None).await?;
+    manager.insert(session.clone());
+    Ok(session)
+}
+
+#[tauri::command]
+pub async fn start_python_session(
+    app: AppHandle,
+    state: State<'_, SessionManager>,
+    project_id: String,
+    kind: Option<String>,
+    python_cmd: Option<String>,
+    kernel_name: Option<String>,
+) -> Result<SessionInfo, String> {
+    let kind = kind.unwrap_or_else(|| "python".to_string());
+    if let Some(session) = state.get_kind(&project_id, &kind)? {
+        println!("🐍 SESSION: Reusing running session for project {}", project_id);
+        return Ok(session.snapshot());
+    }
+
+    println!("🐍 SESSION: Starting {} session for project {}", kind, project_id);
+
+    let session = spawn_session(&app, &project_id, &kind, python_cmd, kernel_name).await?;
+    let info = session.snapshot();
+    state.insert(session);
+
+    println!("✅ SESSION: Started {} ({})", info.session_id, info.python_cmd);
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn execute_in_session(
+    app: AppHandle,
+    state: State<'_, SessionManager>,
+    project_id: String,
+    code: String,
+) -> Result<SessionExecutionResult, String> {
+    run_and_record(&app, &state, &project_id, "", code).await
+}
+
+#[tauri::command]
+pub async fn execute_notebook_cell(
+    app: AppHandle,
+    state: State<'_, SessionManager>,
+    project_id: String,
+    file_path: String,
+    cell_index: usize,
+) -> Result<SessionExecutionResult, String> {
+    let project_dir = get_project_dir(&app, &project_id)?;
+    let notebook_path = project_dir.join(&file_path);
+
+    let content = safe_file_operation(
+        || std::fs::read_to_string(&notebook_path),
+        "Failed to read notebook"
+    )?;
+    let notebook: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse notebook: {}", e))?;
+
+    let cell = notebook.get("cells")
+        .and_then(|cells| cells.get(cell_index))
+        .ok_or_else(|| format!("Notebook has no cell {}", cell_index))?;
+
+    if cell.get("cell_type").and_then(|t| t.as_str()) != Some("code") {
+        return Err(format!("Cell {} is not a code cell", cell_index));
+    }
+
+    // Cell sources are either a single string or a list of lines
+    let code = match cell.get("source") {
+        Some(serde_json::Value::String(source)) => source.clone(),
+        Some(serde_json::Value::Array(lines)) => lines.iter()
+            .filter_map(|line| line.as_str())
+            .collect::<String>(),
+        _ => String::new(),
+    };
+
+    run_and_record(&app, &state, &project_id, &file_path, code).await
+}
+
+// Runs code in the project's session and records it in the execution history
+async fn run_and_record(
+    app: &AppHandle,
+    manager: &SessionManager,
+    project_id: &str,
+    file_path: &str,
+    code: String,
+) -> Result<SessionExecutionResult, String> {
+    let started_at = chrono::Utc::now().timestamp_millis();
+    let (interpreter, outcome) = match ensure_session(app, manager, project_id).await {
+        Ok(session) => {
+            let interpreter = {
+                let info = session.info.lock().unwrap();
+                format!("{} ({} session)", info.python_cmd, info.kind)
+            };
+            (Some(interpreter), run_in_session(&session, code.clone()).await)
+        }
+        Err(e) => (None, Err(e)),
+    };
+    record_session_execution(app, project_id, file_path, interpreter, &code, started_at, &outcome);
+    outcome
+}
+
+async fn run_in_session(
+    session: &PythonSession,
+    code: String,
+) -> Result<SessionExecutionResult, String> {
+    let execution_id = Uuid::new_v4().to_string();
+    let start_time = std::time::Instant::now();
+
+    let (tx, rx) = oneshot::channel();
+    session.pending.lock().unwrap().insert(execution_id.clone(), PendingExecution {
+        sender: Some(tx),
+        ..Default::default()
+    });
+
+    let request = serde_json::json!({ "id": execution_id, "code": code });
+    let write_result = {
+        let mut stdin = session.stdin.lock().await;
+        match stdin.write_all(format!("{}\n", request).as_bytes()).await {
+            Ok(()) => stdin.flush().await,
+            Err(e) => Err(e),
+        }
+    };
+
+    if let Err(e) = write_result {
+        session.pending.lock().unwrap().remove(&execution_id);
+        return Err(format!("Failed to send code to session: {}", e));
+    }
+
+    let (session_id, execution_count) = {
+        let mut info = session.info.lock().unwrap();
+        info.execution_count += 1;
+        (info.session_id.clone(), info.execution_count)
+    };
+
+    let completed = rx.await
+        .map_err(|_| "Session terminated during execution".to_string())?;
+    let duration = start_time.elapsed().as_millis() as u64;
+
+    println!("✅ SESSION: Execution {} finished ({}) in {}ms", execution_count, completed.status, duration);
+
+    Ok(SessionExecutionResult {
+        session_id,
+        execution_id,
+        execution_count,
+        success: completed.status == "ok",
+        status: completed.status,
+        stdout: completed.stdout,
+        stderr: completed.stderr,
+        result: completed.result,
+        error: completed.error,
+        display_data: completed.display_data,
+        duration_ms: duration,
+    })
+}
+
+#[tauri::command]
+pub async fn interrupt_python_session(
+    state: State<'_, SessionManager>,
+    project_id: String,
+) -> Result<(), String> {
+    let session = state.get(&project_id)
+        .ok_or_else(|| "No session running for this project".to_string())?;
+    let pid = session.pid.ok_or_else(|| "Session process has exited".to_string())?;
+
+    println!("⏹️ SESSION: Interrupting session for project {}", project_id);
+
+    if cfg!(unix) {
+        let status = std::process::Command::new("kill")
+            .args(["-INT", &pid.to_string()])
+            .status()
+            .map_err(|e| format!("Failed to interrupt session: {}", e))?;
+        if !status.success() {
+            return Err("Failed to interrupt session".to_string());
+        }
+        Ok(())
+    } else {
+        Err("Interrupt is not supported on this platform, restart the session instead".to_string())
+    }
+}
+
+#[tauri::command]
+pub async fn restart_python_session(
+    app: AppHandle,
+    state: State<'_, SessionManager>,
+    project_id: String,
+) -> Result<SessionInfo, String> {
+    let old = state.remove(&project_id)
+        .ok_or_else(|| "No session running for this project".to_string())?;
+    let (kind, python_cmd, kernel_name) = {
+        let info = old.info.lock().unwrap();
+        (info.kind.clone(), info.python_cmd.clone(), info.kernel_name.clone())
+    };
+
+    println!("🔄 SESSION: Restarting {} session for project {}", kind, project_id);
+    let _ = old.child.lock().await.kill().await;
+
+    let session = spawn_session(&app, &project_id, &kind, Some(python_cmd), kernel_name).await?;
+    let info = session.snapshot();
+    state.insert(session);
+
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn stop_python_session(
+    state: State<'_, SessionManager>,
+    project_id: String,
+) -> Result<(), String> {
+    if let Some(session) = state.remove(&project_id) {
+        println!("🛑 SESSION: Stopping session for project {}", project_id);
+        let _ = session.child.lock().await.kill().await;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_python_sessions(
+    state: State<'_, SessionManager>,
+) -> Result<Vec<SessionInfo>, String> {
+    let sessions: Vec<Arc<PythonSession>> = state.sessions.lock().unwrap()
+        .values()
+        .cloned()
+        .collect();
+
+    Ok(sessions.iter().map(|session| session.snapshot()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A session around a process that just echoes, standing in for the driver
+    fn fake_session(project_id: &str, kind: &str) -> Arc<PythonSession> {
+        let mut child = Command::new("cat")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .unwrap();
+        let stdin = child.stdin.take().unwrap();
+        Arc::new(PythonSession {
+            info: Mutex::new(SessionInfo {
+                session_id: Uuid::new_v4().to_string(),
+                project_id: project_id.to_string(),
+                kind: kind.to_string(),
+                python_cmd: "cat".to_string(),
+                kernel_name: None,
+                version: None,
+                started_at: 0,
+                execution_count: 0,
+                busy: false,
+            }),
+            pid: child.id(),
+            child: tokio::sync::Mutex::new(child),
+            stdin: tokio::sync::Mutex::new(stdin),
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            exited: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    #[tokio::test]
+    async fn exited_sessions_are_not_reused() {
+        let manager = SessionManager::default();
+        let session = fake_session("p1", "python");
+        manager.insert(session.clone());
+        assert!(manager.get("p1").is_some());
+
+        session.exited.store(true, Ordering::SeqCst);
+        assert!(manager.get("p1").is_none());
+        assert!(manager.sessions.lock().unwrap().is_empty());
+
+        // Removal on exit leaves a replacement started by a restart alone
+        let exited_id = session.info.lock().unwrap().session_id.clone();
+        let replacement = fake_session("p1", "python");
+        let replacement_id = replacement.info.lock().unwrap().session_id.clone();
+        manager.insert(replacement);
+        manager.remove_session("p1", &exited_id);
+        assert!(manager.get("p1").is_some());
+        manager.remove_session("p1", &replacement_id);
+        assert!(manager.get("p1").is_none());
+    }
+
+    #[tokio::test]
+    async fn sessions_of_another_kind_are_not_handed_out() {
+        let manager = SessionManager::default();
+        manager.insert(fake_session("p1", "python"));
+
+        assert!(manager.get_kind("p1", "python").unwrap().is_some());
+        let error = manager.get_kind("p1", "jupyter").err().unwrap();
+        assert!(error.contains("A python session is already running"));
+        assert!(manager.get_kind("p2", "jupyter").unwrap().is_none());
+    }
+
+    #[test]
+    fn driver_errors_keep_only_the_latest_text() {
+        let mut errors = String::new();
+        for line in 0..100 {
+            push_capped(&mut errors, &format!("é error {}", line), 64);
+        }
+        assert!(errors.len() <= 64);
+        assert!(errors.ends_with("error 99\n"));
+    }
+}
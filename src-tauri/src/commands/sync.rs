@@ -5,7 +5,12 @@ use std::fs;
 use std::collections::HashSet;
 use tauri::AppHandle;
 use uuid::Uuid;
-use super::{ProjectData, Node, utils::*};
+use super::{
+    ProjectData, Node,
+    file_types::{detect_file_type, file_type_config, FileTypeConfig},
+    search_index::refresh_search_index,
+    utils::*,
+};
 
 #[tauri::command]
 pub async fn sync_external_files(
@@ -21,7 +26,8 @@ pub async fn sync_external_files(
     println!("🔄 SYNC: Starting sync for project: {}", project_id);
     println!("🔄 SYNC: Project directory: {:?}", project_dir);
     
-    // Load current project data to get existing nodes
+    // Load current project data to get existing nodes, holding the lock until it is saved
+    let _guard = lock_project_data(&app);
     let projects_file = get_projects_file(&app)?;
     let existing_data: ProjectData = if projects_file.exists() {
         let content = safe_file_operation(
@@ -79,6 +85,7 @@ pub async fn sync_external_files(
         &existing_paths,
         &mut new_nodes,
         &project_dir,
+        &file_type_config(&app),
     )?;
     
     println!("🔄 SYNC: Found {} new files to add", new_nodes.len());
@@ -109,6 +116,9 @@ pub async fn sync_external_files(
         println!("✅ SYNC: No new files found - project is already in sync");
     }
     
+    // Sync is also how edits made outside the app are noticed
+    refresh_search_index(&app, &project_id);
+    
     Ok(new_nodes)
 }
 
@@ -119,6 +129,7 @@ fn scan_directory_for_new_files(
     existing_paths: &HashSet<String>,
     new_nodes: &mut Vec<Node>,
     base_dir: &std::path::Path,
+    file_types: &FileTypeConfig,
 ) -> Result<(), String> {
     println!("🔍 SCAN: Scanning directory: {:?} (parent: {})", dir, parent_id);
     
@@ -159,65 +170,105 @@ fn scan_directory_for_new_files(
         
         if path.is_dir() {
             // Create folder node
-            let folder_node = Node {
-                id: node_id.clone(),
-                name: file_name.to_string(),
-                r#type: "folder".to_string(),
-                extension: None,
-                parent_id: Some(parent_id.to_string()),
-                project_id: project_id.to_string(),
-                hidden: Some(false),
-                file_path: Some(relative_path.clone()),
-                size: None,
-                modified: None,
-                is_binary: None,
-            };
+            let folder_node = build_folder_node(node_id.clone(), file_name, parent_id, project_id, &relative_path);
             
             new_nodes.push(folder_node);
             println!("📁 SCAN: Found new folder: {} -> {}", file_name, relative_path);
             
             // Recursively scan subdirectory
-            scan_directory_for_new_files(&path, &node_id, project_id, existing_paths, new_nodes, base_dir)?;
+            scan_directory_for_new_files(&path, &node_id, project_id, existing_paths, new_nodes, base_dir, file_types)?;
             
         } else if path.is_file() {
             // Create file node
-            let extension = get_file_extension(&path);
-            let is_binary = is_binary_file(&path);
-            
-            let metadata = safe_file_operation(
-                || fs::metadata(&path),
-                "Failed to get file metadata"
-            )?;
-            
-            let modified = metadata.modified()
-                .map(|time| time.duration_since(std::time::UNIX_EPOCH)
-                    .map(|duration| duration.as_secs() as i64)
-                    .unwrap_or(0))
-                .unwrap_or(0);
-            
-            let file_node = Node {
-                id: node_id,
-                name: file_name.to_string(),
-                r#type: "file".to_string(),
-                extension,
-                parent_id: Some(parent_id.to_string()),
-                project_id: project_id.to_string(),
-                hidden: Some(false),
-                file_path: Some(relative_path.clone()),
-                size: Some(metadata.len()),
-                modified: Some(modified),
-                is_binary: Some(is_binary),
-            };
+            let file_node = build_file_node(node_id, &path, file_name, parent_id, project_id, &relative_path, file_types)?;
+            let (size, is_binary) = (file_node.size.unwrap_or(0), file_node.is_binary.unwrap_or(false));
             
             new_nodes.push(file_node);
             println!("📄 SCAN: Found new file: {} -> {} ({} bytes, binary: {})", 
-                     file_name, relative_path, metadata.len(), is_binary);
+                     file_name, relative_path, size, is_binary);
         }
     }
     
     Ok(())
 }
 
+pub(crate) fn build_folder_node(
+    node_id: String,
+    name: &str,
+    parent_id: &str,
+    project_id: &str,
+    relative_path: &str,
+) -> Node {
+    Node {
+        id: node_id,
+        name: name.to_string(),
+        r#type: "folder".to_string(),
+        extension: None,
+        parent_id: Some(parent_id.to_string()),
+        project_id: project_id.to_string(),
+        hidden: Some(false),
+        file_path: Some(relative_path.to_string()),
+        size: None,
+        modified: None,
+        is_binary: None,
+        mime_type: None,
+        category: None,
+        produced_by: None,
+    }
+}
+
+pub(crate) fn build_file_node(
+    node_id: String,
+    path: &std::path::Path,
+    name: &str,
+    parent_id: &str,
+    project_id: &str,
+    relative_path: &str,
+    file_types: &FileTypeConfig,
+) -> Result<Node, String> {
+    let extension = get_file_extension(path);
+    let file_type = detect_file_type(path, file_types);
+    
+    let metadata = safe_file_operation(
+        || fs::metadata(path),
+        "Failed to get file metadata"
+    )?;
+    
+    let modified = metadata.modified()
+        .map(|time| time.duration_since(std::time::UNIX_EPOCH)
+            .map(|duration| duration.as_secs() as i64)
+            .unwrap_or(0))
+        .unwrap_or(0);
+    
+    Ok(Node {
+        id: node_id,
+        name: name.to_string(),
+        r#type: "file".to_string(),
+        extension,
+        parent_id: Some(parent_id.to_string()),
+        project_id: project_id.to_string(),
+        hidden: Some(false),
+        file_path: Some(relative_path.to_string()),
+        size: Some(metadata.len()),
+        modified: Some(modified),
+        is_binary: Some(file_type.is_binary),
+        mime_type: Some(file_type.mime),
+        category: Some(file_type.category),
+        produced_by: None,
+    })
+}
+
+// The hidden root node every project tree hangs off
+pub(crate) fn find_project_root_id(data: &ProjectData, project_id: &str) -> Option<String> {
+    data.nodes
+        .iter()
+        .find(|node| 
+            node.project_id == project_id && 
+            (node.hidden == Some(true) || node.name == "__PROJECT_ROOT__")
+        )
+        .map(|node| node.id.clone())
+}
+
 #[tauri::command]
 pub async fn auto_sync_project_files(
     app: AppHandle,
@@ -252,7 +303,8 @@ pub async fn rebuild_project_tree(
         return Err("Project directory not found".to_string());
     }
     
-    // Load current project data
+    // Load current project data, holding the lock until it is saved
+    let _guard = lock_project_data(&app);
     let projects_file = get_projects_file(&app)?;
     let mut existing_data: ProjectData = if projects_file.exists() {
         let content = safe_file_operation(
@@ -296,6 +348,7 @@ pub async fn rebuild_project_tree(
         &empty_paths,
         &mut new_nodes,
         &project_dir,
+        &file_type_config(&app),
     )?;
     
     // Add the new nodes
@@ -0,0 +1,106 @@
+// src-tauri/src/commands/test_server.rs
+// Local HTTP fixture server for tests of the web commands
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+
+pub(crate) struct MockRequest {
+    pub method: String,
+    pub target: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl MockRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+pub(crate) struct MockResponse {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: Vec<u8>,
+}
+
+pub(crate) fn respond(status: u16, content_type: &'static str, body: Vec<u8>) -> MockResponse {
+    MockResponse { status, content_type, headers: Vec::new(), body }
+}
+
+// A one-thread HTTP/1.1 server answering every connection with `handler`
+pub(crate) fn spawn_server(handler: fn(&MockRequest) -> MockResponse) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = match stream {
+                Ok(stream) => stream,
+                Err(_) => continue,
+            };
+
+            let mut data = Vec::new();
+            let mut buffer = [0u8; 4096];
+            let header_end = loop {
+                let read = stream.read(&mut buffer).unwrap_or(0);
+                if read == 0 {
+                    break None;
+                }
+                data.extend_from_slice(&buffer[..read]);
+                if let Some(position) = data.windows(4).position(|window| window == b"\r\n\r\n") {
+                    break Some(position + 4);
+                }
+            };
+            let header_end = match header_end {
+                Some(end) => end,
+                None => continue,
+            };
+
+            let head = String::from_utf8_lossy(&data[..header_end]).to_string();
+            let headers: Vec<(String, String)> = head.lines()
+                .skip(1)
+                .filter_map(|line| line.split_once(':'))
+                .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+                .collect();
+            let content_length = headers.iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+                .and_then(|(_, value)| value.parse::<usize>().ok())
+                .unwrap_or(0);
+            while data.len() < header_end + content_length {
+                let read = stream.read(&mut buffer).unwrap_or(0);
+                if read == 0 {
+                    break;
+                }
+                data.extend_from_slice(&buffer[..read]);
+            }
+
+            let mut request_line = head.lines().next().unwrap_or("").split_whitespace();
+            let request = MockRequest {
+                method: request_line.next().unwrap_or("").to_string(),
+                target: request_line.next().unwrap_or("").to_string(),
+                headers,
+                body: data[header_end..].to_vec(),
+            };
+
+            let reply = handler(&request);
+            let mut head = format!(
+                "HTTP/1.1 {} OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+                reply.status, reply.content_type, reply.body.len()
+            );
+            for (name, value) in &reply.headers {
+                head.push_str(&format!("{}: {}\r\n", name, value));
+            }
+            head.push_str("\r\n");
+            let mut response = head.into_bytes();
+            if request.method != "HEAD" {
+                response.extend_from_slice(&reply.body);
+            }
+            let _ = stream.write_all(&response);
+        }
+    });
+
+    format!("http://{}", address)
+}
@@ -0,0 +1,362 @@
+// src-tauri/src/commands/text_encoding.rs
+// Encoding and line-ending detection so non-UTF-8 text opens in the editor and saves back unchanged
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use serde::{Deserialize, Serialize};
+
+// Bytes looked at when guessing the format of a file
+const DETECTION_SAMPLE_BYTES: usize = 64 * 1024;
+// Bytes of a file checked before trusting that it is UTF-8; the editor opens nothing bigger
+const UTF8_CHECK_BYTES: u64 = 64 * 1024 * 1024;
+const BINARY_CHECK_CHARS: usize = 8192;
+
+/// How a text file is stored on disk.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextFormat {
+    pub encoding: String, // WHATWG name, e.g. "UTF-8", "UTF-16LE" or "windows-1252" (Latin-1)
+    pub bom: bool,
+    pub line_ending: String, // "LF", "CRLF", "CR", "mixed" or "none"
+    #[serde(default)]
+    pub lossy: bool, // Some bytes did not decode, so saving the text back would change them
+}
+
+impl Default for TextFormat {
+    fn default() -> Self {
+        TextFormat {
+            encoding: UTF_8.name().to_string(),
+            bom: false,
+            line_ending: "none".to_string(),
+            lossy: false,
+        }
+    }
+}
+
+impl TextFormat {
+    pub(crate) fn encoding(&self) -> &'static Encoding {
+        Encoding::for_label(self.encoding.as_bytes()).unwrap_or(UTF_8)
+    }
+
+    pub(crate) fn is_utf16(&self) -> bool {
+        let encoding = self.encoding();
+        encoding == UTF_16LE || encoding == UTF_16BE
+    }
+
+    pub(crate) fn bom_length(&self) -> usize {
+        match (self.bom, self.is_utf16()) {
+            (false, _) => 0,
+            (true, true) => 2,
+            (true, false) => 3,
+        }
+    }
+
+    /// The same file saved in another encoding, named by any WHATWG label.
+    pub(crate) fn with_encoding(&self, label: &str) -> Result<TextFormat, String> {
+        let encoding = Encoding::for_label(label.trim().as_bytes())
+            .ok_or_else(|| format!("Unknown encoding: {}", label))?;
+        Ok(TextFormat {
+            encoding: encoding.name().to_string(),
+            // A byte order mark only survives when the encoding stays the same
+            bom: self.bom && encoding == self.encoding(),
+            line_ending: self.line_ending.clone(),
+            lossy: false,
+        })
+    }
+}
+
+// UTF-16 without a BOM shows up as a NUL in every other byte of Latin text
+fn utf16_without_bom(sample: &[u8]) -> Option<&'static Encoding> {
+    let pairs = sample.len() / 2;
+    if pairs < 2 {
+        return None;
+    }
+    let zeros_at = |parity: usize| sample.chunks_exact(2).filter(|pair| pair[parity] == 0).count();
+    let (even, odd) = (zeros_at(0), zeros_at(1));
+    // At least half the code units must be Latin-1, with no stray NULs in between
+    if odd * 2 >= pairs && even * 20 < pairs {
+        Some(UTF_16LE)
+    } else if even * 2 >= pairs && odd * 20 < pairs {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+fn guess_legacy_encoding(bytes: &[u8], complete: bool) -> &'static Encoding {
+    let mut detector = chardetng::EncodingDetector::new();
+    detector.feed(bytes, complete);
+    detector.guess(None, true)
+}
+
+// `complete` is false for the start of a longer file, whose last character may be cut off
+fn detect_encoding_of(bytes: &[u8], complete: bool) -> (&'static Encoding, usize) {
+    if let Some(found) = Encoding::for_bom(bytes) {
+        return found;
+    }
+    let sample = &bytes[..bytes.len().min(DETECTION_SAMPLE_BYTES)];
+    if let Some(encoding) = utf16_without_bom(sample) {
+        return (encoding, 0);
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(_) => return (UTF_8, 0),
+        Err(e) if !complete && e.error_len().is_none() => return (UTF_8, 0),
+        Err(_) => {}
+    }
+    (guess_legacy_encoding(bytes, complete), 0)
+}
+
+/// Guesses the encoding of a file's content: BOM first, then UTF-16
+/// patterns, then UTF-8 validity of all of it, then a statistical guess for
+/// legacy encodings. Returns the encoding and the length of its BOM.
+pub(crate) fn detect_encoding(bytes: &[u8]) -> (&'static Encoding, usize) {
+    detect_encoding_of(bytes, true)
+}
+
+pub(crate) fn detect_line_ending(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let (mut crlf, mut lf, mut cr) = (0, 0, 0);
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\r' if bytes.get(index + 1) == Some(&b'\n') => {
+                crlf += 1;
+                index += 1;
+            }
+            b'\r' => cr += 1,
+            b'\n' => lf += 1,
+            _ => {}
+        }
+        index += 1;
+    }
+    match (crlf > 0, lf > 0, cr > 0) {
+        (false, false, false) => "none",
+        (true, false, false) => "CRLF",
+        (false, true, false) => "LF",
+        (false, false, true) => "CR",
+        _ => "mixed",
+    }.to_string()
+}
+
+// NULs never appear in real text, whatever the encoding
+pub(crate) fn looks_binary(text: &str) -> bool {
+    text.chars().take(BINARY_CHECK_CHARS).any(|c| c == '\0')
+}
+
+fn decode_text_of(bytes: &[u8], complete: bool) -> Option<(String, TextFormat)> {
+    let (detected, bom_length) = detect_encoding_of(bytes, complete);
+    let mut encoding = detected;
+    let mut content = &bytes[bom_length..];
+    if !complete {
+        // Leave out a character the sample cut in half
+        content = match (encoding == UTF_8, std::str::from_utf8(content)) {
+            (true, Err(e)) if e.error_len().is_none() => &content[..e.valid_up_to()],
+            _ if encoding == UTF_16LE || encoding == UTF_16BE => &content[..content.len() & !1],
+            _ => content,
+        };
+    }
+
+    let (mut text, mut lossy) = encoding.decode_without_bom_handling(content);
+    if lossy {
+        // The first guess (say UTF-16 without a BOM) did not fit; try the statistical one
+        let guess = guess_legacy_encoding(content, complete);
+        if guess != encoding {
+            let (guessed_text, guessed_lossy) = guess.decode_without_bom_handling(content);
+            if !guessed_lossy {
+                encoding = guess;
+                text = guessed_text;
+                lossy = false;
+            }
+        }
+    }
+    if looks_binary(&text) {
+        return None;
+    }
+    let format = TextFormat {
+        encoding: encoding.name().to_string(),
+        bom: bom_length > 0 && encoding == detected,
+        line_ending: detect_line_ending(&text),
+        lossy,
+    };
+    Some((text.into_owned(), format))
+}
+
+/// Decodes a text file to UTF-8 along with the format it was stored in.
+/// None when the content is binary.
+pub(crate) fn decode_text(bytes: &[u8]) -> Option<(String, TextFormat)> {
+    decode_text_of(bytes, true)
+}
+
+// Whether the rest of a file after its sample is UTF-8 too; when it is not, the
+// legacy encoding that fits the whole file
+fn check_rest_is_utf8(sample: &[u8], rest: &mut impl Read) -> Option<&'static Encoding> {
+    let mut detector = chardetng::EncodingDetector::new();
+    detector.feed(sample, false);
+    let cut = match std::str::from_utf8(sample) {
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        _ => sample.len(),
+    };
+    let mut pending = sample[cut..].to_vec(); // A character split between blocks
+    let mut valid = true;
+    let mut block = vec![0u8; DETECTION_SAMPLE_BYTES];
+
+    loop {
+        let read = rest.read(&mut block).ok()?;
+        if read == 0 {
+            break;
+        }
+        detector.feed(&block[..read], false);
+        if valid {
+            pending.extend_from_slice(&block[..read]);
+            match std::str::from_utf8(&pending) {
+                Ok(_) => pending.clear(),
+                Err(e) if e.error_len().is_none() => {
+                    pending.drain(..e.valid_up_to());
+                }
+                Err(_) => valid = false,
+            }
+        }
+    }
+    detector.feed(&[], true);
+    if valid && pending.is_empty() {
+        None
+    } else {
+        Some(detector.guess(None, true))
+    }
+}
+
+/// Format of a file on disk. Its start settles the BOM, UTF-16 and line
+/// endings; the rest (up to UTF8_CHECK_BYTES) is checked before calling it
+/// UTF-8, since a legacy byte can follow any amount of ASCII. None when the
+/// file is missing or binary.
+pub(crate) fn read_text_format(path: &Path) -> Option<TextFormat> {
+    let mut file = File::open(path).ok()?;
+    let mut sample = Vec::with_capacity(DETECTION_SAMPLE_BYTES);
+    (&mut file).take(DETECTION_SAMPLE_BYTES as u64)
+        .read_to_end(&mut sample)
+        .ok()?;
+    let complete = sample.len() < DETECTION_SAMPLE_BYTES;
+    let (_, mut format) = decode_text_of(&sample, complete)?;
+
+    if !complete && !format.bom && format.encoding() == UTF_8 {
+        let mut rest = file.take(UTF8_CHECK_BYTES - DETECTION_SAMPLE_BYTES as u64);
+        if let Some(encoding) = check_rest_is_utf8(&sample, &mut rest) {
+            format.encoding = encoding.name().to_string();
+        }
+    }
+    Some(format)
+}
+
+fn convert_line_endings(text: &str, line_ending: &str) -> String {
+    let unified = text.replace("\r\n", "\n").replace('\r', "\n");
+    match line_ending {
+        "CRLF" => unified.replace('\n', "\r\n"),
+        "CR" => unified.replace('\n', "\r"),
+        _ => unified,
+    }
+}
+
+/// Encodes editor text in a file's format. Line breaks are converted to the
+/// file's style, except in files that already mixed them.
+pub(crate) fn encode_text(text: &str, format: &TextFormat) -> Result<Vec<u8>, String> {
+    let text = match format.line_ending.as_str() {
+        "LF" | "CRLF" | "CR" => convert_line_endings(text, &format.line_ending),
+        _ => text.to_string(),
+    };
+
+    let encoding = format.encoding();
+    let mut bytes = Vec::with_capacity(text.len() + 3);
+    if format.is_utf16() {
+        // encoding_rs only decodes UTF-16, so code units are written by hand
+        let little_endian = encoding == UTF_16LE;
+        let unit_bytes = |unit: u16| if little_endian { unit.to_le_bytes() } else { unit.to_be_bytes() };
+        if format.bom {
+            bytes.extend_from_slice(&unit_bytes(0xFEFF));
+        }
+        for unit in text.encode_utf16() {
+            bytes.extend_from_slice(&unit_bytes(unit));
+        }
+        return Ok(bytes);
+    }
+
+    if format.bom && encoding == UTF_8 {
+        bytes.extend_from_slice(b"\xEF\xBB\xBF");
+    }
+    let (encoded, _, unmappable) = encoding.encode(&text);
+    if unmappable {
+        return Err(format!(
+            "The text contains characters that cannot be saved as {}; save it as UTF-8 instead",
+            encoding.name()
+        ));
+    }
+    bytes.extend_from_slice(&encoded);
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_boms_utf16_and_latin1() {
+        let (text, format) = decode_text(b"\xEF\xBB\xBFname\r\nvalue\r\n").unwrap();
+        assert_eq!((text.as_str(), format.encoding.as_str(), format.bom), ("name\r\nvalue\r\n", "UTF-8", true));
+        assert_eq!(format.line_ending, "CRLF");
+
+        let utf16: Vec<u8> = "id,città\n1,Zürich\n".encode_utf16().flat_map(|unit| unit.to_le_bytes()).collect();
+        let (text, format) = decode_text(&utf16).unwrap();
+        assert_eq!((text.as_str(), format.encoding.as_str(), format.bom), ("id,città\n1,Zürich\n", "UTF-16LE", false));
+
+        let latin1 = b"name;city\nJos\xE9;M\xFCnchen\nFran\xE7ois;Besan\xE7on\n";
+        let (text, format) = decode_text(latin1).unwrap();
+        assert_eq!(text, "name;city\nJosé;München\nFrançois;Besançon\n");
+        assert_eq!((format.encoding.as_str(), format.line_ending.as_str()), ("windows-1252", "LF"));
+
+        assert!(decode_text(b"PK\x03\x04\x00\x00binary").is_none());
+    }
+
+    #[test]
+    fn encoding_round_trips_format_and_line_endings() {
+        let samples: Vec<Vec<u8>> = vec![
+            b"caf\xE9\r\nna\xEFve\r\n".to_vec(),
+            [&[0xFE, 0xFF][..], &"é\nü\n".encode_utf16().flat_map(|unit| unit.to_be_bytes()).collect::<Vec<u8>>()].concat(),
+            b"\xEF\xBB\xBFmixed\r\nendings\n".to_vec(),
+        ];
+        for original in samples {
+            let (text, format) = decode_text(&original).unwrap();
+            assert_eq!(encode_text(&text, &format).unwrap(), original);
+        }
+
+        // Editors hand back LF text; the file keeps CRLF
+        let (_, format) = decode_text(b"a\r\nb\r\n").unwrap();
+        assert_eq!(encode_text("a\nb\nc\n", &format).unwrap(), b"a\r\nb\r\nc\r\n");
+
+        let latin1 = TextFormat::default().with_encoding("latin1").unwrap();
+        assert_eq!(latin1.encoding, "windows-1252");
+        assert!(encode_text("emoji 😀", &latin1).unwrap_err().contains("UTF-8"));
+    }
+
+    #[test]
+    fn legacy_bytes_after_the_sample_are_not_missed() {
+        let mut latin1 = vec![b'a'; DETECTION_SAMPLE_BYTES + 10];
+        latin1.extend_from_slice(b"\ncaf\xE9\n");
+
+        let (text, format) = decode_text(&latin1).unwrap();
+        assert!(text.ends_with("caf\u{e9}\n"));
+        assert_eq!((format.encoding.as_str(), format.lossy), ("windows-1252", false));
+        assert_eq!(encode_text(&text, &format).unwrap(), latin1);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.csv");
+        std::fs::write(&path, &latin1).unwrap();
+        assert_eq!(read_text_format(&path).unwrap().encoding, "windows-1252");
+
+        // A long UTF-8 file whose sample ends mid-character is still UTF-8
+        let mut utf8 = vec![b'a'; DETECTION_SAMPLE_BYTES - 1];
+        utf8.extend_from_slice("\u{e9}t\u{e9}\n".as_bytes());
+        std::fs::write(&path, &utf8).unwrap();
+        let format = read_text_format(&path).unwrap();
+        assert_eq!((format.encoding.as_str(), format.lossy), ("UTF-8", false));
+    }
+}
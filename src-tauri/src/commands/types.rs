@@ -31,6 +31,12 @@ pub struct Node {
     pub size: Option<u64>,
     pub modified: Option<i64>,
     pub is_binary: Option<bool>, // Track binary files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>, // File type category, e.g. "image" or "data"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub produced_by: Option<String>, // Execution record that created or last wrote this file
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -55,7 +61,11 @@ pub struct FileStats {
     pub modified: i64,
     pub created: i64,
     pub is_binary: bool,
-    pub file_type: String,
+    pub file_type: String, // Description, e.g. "PNG Image"
+    pub mime_type: String,
+    pub category: String,
+    pub encoding: Option<String>, // None for binary files
+    pub line_ending: Option<String>, // "LF", "CRLF", "CR", "mixed" or "none"
 }
 
 #[derive(Debug, Serialize)]
@@ -67,13 +77,31 @@ pub struct ImportResult {
     pub size: u64,
     pub is_binary: bool,
     pub file_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ExecutionResult {
     pub success: bool,
     pub stdout: String,
     pub stderr: String,
     pub exit_code: Option<i32>,
     pub duration_ms: u64,
+    #[serde(default)]
+    pub run_id: Option<String>, // Execution history record for this run
+    #[serde(default)]
+    pub created_files: Vec<String>,
+    #[serde(default)]
+    pub modified_files: Vec<String>,
+    #[serde(default)]
+    pub new_nodes: Vec<Node>, // Nodes registered for created_files
+    #[serde(default)]
+    pub files_overlapped: bool, // Another run wrote to the project meanwhile, so the file lists may include its files
+    #[serde(default)]
+    pub cancelled: bool,
+    #[serde(default)]
+    pub timed_out: bool,
 }
\ No newline at end of file
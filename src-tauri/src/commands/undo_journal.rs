@@ -0,0 +1,375 @@
+// src-tauri/src/commands/undo_journal.rs
+// Undo journal for bulk edits: snapshots of the previous content, undone a batch at a time
+
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+use uuid::Uuid;
+use super::{artifacts::register_project_files, utils::*};
+
+const MAX_JOURNAL_ENTRIES: usize = 1000;
+const DEFAULT_JOURNAL_LIMIT: usize = 200;
+const TEMP_SUFFIX: &str = ".edit-tmp";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UndoEntry {
+    pub id: String,
+    pub batch_id: String, // Entries written together are undone together
+    pub project_id: String,
+    pub file_path: String,
+    pub operation: String, // e.g. "replace"
+    pub description: String,
+    pub created_at: i64, // Unix time in milliseconds
+    pub before_sha256: String,
+    pub after_sha256: String,
+    #[serde(default)]
+    pub undone: bool,
+}
+
+/// One file edit to journal: the content before and after.
+pub(crate) struct JournalEdit<'a> {
+    pub(crate) project_id: &'a str,
+    pub(crate) file_path: &'a str,
+    pub(crate) before: &'a [u8],
+    pub(crate) after: &'a [u8],
+}
+
+/// Serializes writers of the journal file.
+#[derive(Default)]
+pub struct UndoJournal {
+    write_lock: Mutex<()>,
+}
+
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn journal_file(journal_dir: &Path) -> PathBuf {
+    journal_dir.join("journal.jsonl")
+}
+
+fn snapshot_path(journal_dir: &Path, entry_id: &str) -> PathBuf {
+    journal_dir.join("snapshots").join(entry_id)
+}
+
+fn read_entries(journal_dir: &Path) -> Result<Vec<UndoEntry>, String> {
+    let path = journal_file(journal_dir);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = safe_file_operation(|| fs::read_to_string(&path), "Failed to read undo journal")?;
+
+    // A partially written last line must not hide the rest of the journal
+    Ok(content.lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+fn write_entries(journal_dir: &Path, entries: &[UndoEntry]) -> Result<(), String> {
+    let mut content = String::new();
+    for entry in entries {
+        let line = serde_json::to_string(entry)
+            .map_err(|e| format!("Failed to serialize undo entry: {}", e))?;
+        content.push_str(&line);
+        content.push('\n');
+    }
+
+    let path = journal_file(journal_dir);
+    let temp_path = path.with_extension("jsonl.tmp");
+    safe_file_operation(|| fs::write(&temp_path, content), "Failed to write undo journal")?;
+    safe_file_operation(|| fs::rename(&temp_path, &path), "Failed to replace undo journal")
+}
+
+/// Replaces several files so that either all of them get their new content or
+/// none do. Each entry is (path, new content, current content).
+pub(crate) fn replace_files_atomically(files: &[(PathBuf, Vec<u8>, Vec<u8>)]) -> Result<(), String> {
+    let temp_path = |path: &Path| {
+        let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        path.with_file_name(format!(".{}{}", name, TEMP_SUFFIX))
+    };
+
+    // Stage everything first so a full disk fails before any file is touched
+    for (index, (path, content, _)) in files.iter().enumerate() {
+        let staged = fs::write(temp_path(path), content)
+            .and_then(|_| copy_permissions(path, &temp_path(path)));
+        if let Err(e) = staged {
+            for (staged, _, _) in &files[..=index] {
+                let _ = fs::remove_file(temp_path(staged));
+            }
+            return Err(format!("Failed to stage {:?}: {}", path, e));
+        }
+    }
+
+    for (index, (path, _, _)) in files.iter().enumerate() {
+        if let Err(e) = fs::rename(temp_path(path), path) {
+            // Put back the files already replaced and drop the remaining stages
+            for (replaced, _, original) in &files[..index] {
+                if let Err(restore_error) = fs::write(replaced, original) {
+                    eprintln!("⚠️ JOURNAL: Failed to restore {:?}: {}", replaced, restore_error);
+                }
+            }
+            for (staged, _, _) in &files[index..] {
+                let _ = fs::remove_file(temp_path(staged));
+            }
+            return Err(format!("Failed to replace {:?}: {}", path, e));
+        }
+    }
+    Ok(())
+}
+
+// How many of the oldest entries to drop to get under `max`, in whole batches so none is
+// left half undoable. The newest batch always stays, however large.
+fn entries_to_trim(entries: &[UndoEntry], max: usize) -> usize {
+    if entries.len() <= max {
+        return 0;
+    }
+    let newest_batch_start = entries.iter()
+        .rposition(|entry| entry.batch_id != entries[entries.len() - 1].batch_id)
+        .map_or(0, |index| index + 1);
+
+    let mut cut = entries.len() - max;
+    while cut < newest_batch_start && entries[cut].batch_id == entries[cut - 1].batch_id {
+        cut += 1;
+    }
+    cut.min(newest_batch_start)
+}
+
+fn append_batch(
+    journal_dir: &Path,
+    operation: &str,
+    description: &str,
+    edits: &[JournalEdit],
+) -> Result<Vec<UndoEntry>, String> {
+    fs::create_dir_all(journal_dir.join("snapshots"))
+        .map_err(|e| format!("Failed to create undo journal directory: {}", e))?;
+
+    let batch_id = Uuid::new_v4().to_string();
+    let created_at = chrono::Utc::now().timestamp_millis();
+    let mut lines = String::new();
+    let mut entries = Vec::new();
+
+    for edit in edits {
+        let entry = UndoEntry {
+            id: Uuid::new_v4().to_string(),
+            batch_id: batch_id.clone(),
+            project_id: edit.project_id.to_string(),
+            file_path: edit.file_path.to_string(),
+            operation: operation.to_string(),
+            description: description.to_string(),
+            created_at,
+            before_sha256: sha256_hex(edit.before),
+            after_sha256: sha256_hex(edit.after),
+            undone: false,
+        };
+        safe_file_operation(
+            || fs::write(snapshot_path(journal_dir, &entry.id), edit.before),
+            "Failed to write undo snapshot"
+        )?;
+        lines.push_str(&serde_json::to_string(&entry).map_err(|e| format!("Failed to serialize undo entry: {}", e))?);
+        lines.push('\n');
+        entries.push(entry);
+    }
+
+    safe_file_operation(
+        || OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(journal_file(journal_dir))
+            .and_then(|mut file| file.write_all(lines.as_bytes())),
+        "Failed to append undo journal"
+    )?;
+
+    // Oldest batches and their snapshots go once the journal is full
+    let all = read_entries(journal_dir)?;
+    let drop_count = entries_to_trim(&all, MAX_JOURNAL_ENTRIES);
+    if drop_count > 0 {
+        let (dropped, kept) = all.split_at(drop_count);
+        write_entries(journal_dir, kept)?;
+        for entry in dropped {
+            let _ = fs::remove_file(snapshot_path(journal_dir, &entry.id));
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Restores every file of a batch to its journaled content. Refuses (and
+/// changes nothing) when any of them was modified after the edit.
+fn undo_batch<F>(journal_dir: &Path, batch_id: &str, project_dir: F) -> Result<Vec<UndoEntry>, String>
+where
+    F: Fn(&str) -> Result<PathBuf, String>,
+{
+    let mut entries = read_entries(journal_dir)?;
+    let batch: Vec<UndoEntry> = entries.iter()
+        .filter(|entry| entry.batch_id == batch_id && !entry.undone)
+        .cloned()
+        .collect();
+    if batch.is_empty() {
+        return Err(format!("Nothing to undo for batch {}", batch_id));
+    }
+
+    let mut files = Vec::new();
+    let mut conflicts = Vec::new();
+    for entry in &batch {
+        let full_path = resolve_project_path(&project_dir(&entry.project_id)?, &entry.file_path)?;
+        let current = fs::read(&full_path).unwrap_or_default();
+        if !full_path.exists() || sha256_hex(&current) != entry.after_sha256 {
+            conflicts.push(entry.file_path.clone());
+            continue;
+        }
+        let before = safe_file_operation(
+            || fs::read(snapshot_path(journal_dir, &entry.id)),
+            "Failed to read undo snapshot"
+        )?;
+        files.push((full_path, before, current));
+    }
+    if !conflicts.is_empty() {
+        return Err(format!("Files changed since the edit, not undone: {}", conflicts.join(", ")));
+    }
+
+    replace_files_atomically(&files)?;
+
+    for entry in entries.iter_mut().filter(|entry| entry.batch_id == batch_id) {
+        entry.undone = true;
+    }
+    write_entries(journal_dir, &entries)?;
+    Ok(batch)
+}
+
+/// Journals a batch of edits that were just written. Returns the batch id.
+pub(crate) fn record_edits(
+    app: &AppHandle,
+    operation: &str,
+    description: &str,
+    edits: &[JournalEdit],
+) -> Result<String, String> {
+    let journal = app.state::<UndoJournal>();
+    let _guard = journal.write_lock.lock().unwrap();
+    let entries = append_batch(&get_undo_dir(app)?, operation, description, edits)?;
+    entries.first()
+        .map(|entry| entry.batch_id.clone())
+        .ok_or_else(|| "No edits to journal".to_string())
+}
+
+// Written files get fresh node metadata and index entries
+pub(crate) fn register_edited_files(app: &AppHandle, paths_by_project: &HashMap<String, Vec<String>>) {
+    for (project_id, paths) in paths_by_project {
+        if let Err(e) = register_project_files(app, project_id, &[], paths, None) {
+            eprintln!("⚠️ JOURNAL: Failed to refresh nodes for project {}: {}", project_id, e);
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_undo_journal(
+    app: AppHandle,
+    project_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<UndoEntry>, String> {
+    let entries = read_entries(&get_undo_dir(&app)?)?;
+    Ok(entries.into_iter()
+        .rev()
+        .filter(|entry| project_id.as_ref().map_or(true, |id| &entry.project_id == id))
+        .take(limit.unwrap_or(DEFAULT_JOURNAL_LIMIT))
+        .collect())
+}
+
+#[tauri::command]
+pub async fn undo_edit_batch(
+    app: AppHandle,
+    batch_id: String,
+) -> Result<Vec<UndoEntry>, String> {
+    let restored = {
+        let journal = app.state::<UndoJournal>();
+        let _guard = journal.write_lock.lock().unwrap();
+        undo_batch(&get_undo_dir(&app)?, &batch_id, |project_id| get_project_dir(&app, project_id))?
+    };
+
+    let mut paths_by_project: HashMap<String, Vec<String>> = HashMap::new();
+    for entry in &restored {
+        paths_by_project.entry(entry.project_id.clone()).or_default().push(entry.file_path.clone());
+    }
+    register_edited_files(&app, &paths_by_project);
+
+    println!("↩️ JOURNAL: Undid batch {} ({} files)", batch_id, restored.len());
+    Ok(restored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undo_restores_a_batch_and_refuses_changed_files() {
+        let journal = tempfile::tempdir().unwrap();
+        let project = tempfile::tempdir().unwrap();
+        let project_dir = |_: &str| Ok(project.path().to_path_buf());
+        fs::write(project.path().join("a.py"), "KEY = 2").unwrap();
+        fs::write(project.path().join("b.py"), "use(KEY)").unwrap();
+
+        let edits = [
+            JournalEdit { project_id: "p1", file_path: "a.py", before: b"OLD = 2", after: b"KEY = 2" },
+            JournalEdit { project_id: "p1", file_path: "b.py", before: b"use(OLD)", after: b"use(KEY)" },
+        ];
+        let batch_id = append_batch(journal.path(), "replace", "OLD -> KEY", &edits).unwrap()[0].batch_id.clone();
+
+        // Edited again after the batch: nothing is restored
+        fs::write(project.path().join("b.py"), "use(KEY, 3)").unwrap();
+        let error = undo_batch(journal.path(), &batch_id, project_dir).unwrap_err();
+        assert!(error.contains("b.py"));
+        assert_eq!(fs::read_to_string(project.path().join("a.py")).unwrap(), "KEY = 2");
+
+        fs::write(project.path().join("b.py"), "use(KEY)").unwrap();
+        assert_eq!(undo_batch(journal.path(), &batch_id, project_dir).unwrap().len(), 2);
+        assert_eq!(fs::read_to_string(project.path().join("a.py")).unwrap(), "OLD = 2");
+        assert_eq!(fs::read_to_string(project.path().join("b.py")).unwrap(), "use(OLD)");
+        assert!(read_entries(journal.path()).unwrap().iter().all(|entry| entry.undone));
+        assert!(undo_batch(journal.path(), &batch_id, project_dir).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn replaced_files_keep_their_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+        let project = tempfile::tempdir().unwrap();
+        let script = project.path().join("run.sh");
+        fs::write(&script, "echo old").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o750)).unwrap();
+
+        replace_files_atomically(&[(script.clone(), b"echo new".to_vec(), b"echo old".to_vec())]).unwrap();
+
+        assert_eq!(fs::read_to_string(&script).unwrap(), "echo new");
+        assert_eq!(fs::metadata(&script).unwrap().permissions().mode() & 0o777, 0o750);
+    }
+
+    #[test]
+    fn journal_is_trimmed_by_whole_batches() {
+        let entries: Vec<UndoEntry> = ["a", "a", "b", "b", "b", "c"].iter()
+            .map(|batch| UndoEntry {
+                id: Uuid::new_v4().to_string(),
+                batch_id: batch.to_string(),
+                project_id: "p1".to_string(),
+                file_path: "f.txt".to_string(),
+                operation: "replace".to_string(),
+                description: String::new(),
+                created_at: 0,
+                before_sha256: String::new(),
+                after_sha256: String::new(),
+                undone: false,
+            })
+            .collect();
+
+        assert_eq!(entries_to_trim(&entries, 6), 0);
+        // One entry too many takes all of batch "a"
+        assert_eq!(entries_to_trim(&entries, 5), 2);
+        assert_eq!(entries_to_trim(&entries, 3), 5);
+        // The newest batch is kept even when it alone is over the limit
+        assert_eq!(entries_to_trim(&entries[2..5], 1), 0);
+    }
+}
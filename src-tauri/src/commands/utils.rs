@@ -2,8 +2,10 @@
 // Shared utility functions used across command modules
 
 use std::fs;
-use std::path::{Path, PathBuf};
-use tauri::AppHandle;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use tauri::{AppHandle, Manager};
+use super::ProjectData;
 
 // Path utilities
 pub fn get_app_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
@@ -24,6 +26,88 @@ pub fn get_projects_file(app: &AppHandle) -> Result<PathBuf, String> {
     Ok(get_app_data_dir(app)?.join("projects.json"))
 }
 
+pub fn get_execution_queue_file(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_app_data_dir(app)?.join("execution_queue.json"))
+}
+
+pub fn get_runner_settings_file(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_app_data_dir(app)?.join("runners.json"))
+}
+
+pub fn get_schedules_file(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_app_data_dir(app)?.join("schedules.json"))
+}
+
+pub fn get_environment_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_app_data_dir(app)?.join("env"))
+}
+
+// Machine-local, so it is not carried along with roaming app data
+pub fn get_secrets_key_file(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path_resolver()
+        .app_local_data_dir()
+        .map(|dir| dir.join("secrets.key"))
+        .ok_or_else(|| "Failed to get app local data directory".to_string())
+}
+
+pub fn get_http_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_app_data_dir(app)?.join("http"))
+}
+
+pub fn get_search_index_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_app_data_dir(app)?.join("search_index"))
+}
+
+pub fn get_undo_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_app_data_dir(app)?.join("undo"))
+}
+
+pub fn get_logs_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_app_data_dir(app)?.join("logs"))
+}
+
+pub fn get_execution_history_file(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_logs_dir(app)?.join("execution_history.jsonl"))
+}
+
+// Project data utilities
+/// Serializes read-modify-write cycles of projects.json. Runs, downloads, the
+/// crawler and edits all register files, and without it they drop each other's nodes.
+#[derive(Default)]
+pub struct ProjectDataLock(Mutex<()>);
+
+/// Hold the returned guard from loading projects.json until it is saved again.
+pub fn lock_project_data(app: &AppHandle) -> MutexGuard<'_, ()> {
+    // A writer that panicked left the file whole; later writers can carry on
+    app.state::<ProjectDataLock>().inner().0.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn load_project_data(app: &AppHandle) -> Result<ProjectData, String> {
+    let projects_file = get_projects_file(app)?;
+    if !projects_file.exists() {
+        return Err("Projects file not found".to_string());
+    }
+    
+    let content = safe_file_operation(
+        || fs::read_to_string(&projects_file),
+        "Failed to read projects file"
+    )?;
+    
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse projects data: {}", e))
+}
+
+pub fn save_project_data(app: &AppHandle, data: &ProjectData) -> Result<(), String> {
+    let projects_file = get_projects_file(app)?;
+    let json = serde_json::to_string_pretty(data)
+        .map_err(|e| format!("Failed to serialize projects data: {}", e))?;
+    
+    safe_file_operation(
+        || fs::write(&projects_file, json),
+        "Failed to write projects file"
+    )
+}
+
 // File utilities
 pub fn get_file_extension(path: &Path) -> Option<String> {
     path.extension()
@@ -31,77 +115,12 @@ pub fn get_file_extension(path: &Path) -> Option<String> {
         .map(|ext| ext.to_lowercase())
 }
 
-pub fn is_binary_file(path: &Path) -> bool {
-    if let Some(extension) = get_file_extension(path) {
-        matches!(extension.as_str(),
-            // Documents
-            "pdf" | "doc" | "docx" | "xls" | "xlsx" | "ppt" | "pptx" | "odt" | "ods" | "odp" |
-            // Images
-            "jpg" | "jpeg" | "png" | "gif" | "bmp" | "tiff" | "tif" | "svg" | "ico" | "webp" |
-            // Audio
-            "mp3" | "wav" | "flac" | "ogg" | "aac" | "m4a" | "wma" |
-            // Video
-            "mp4" | "avi" | "mov" | "wmv" | "flv" | "webm" | "mkv" | "m4v" |
-            // Archives
-            "zip" | "rar" | "7z" | "tar" | "gz" | "bz2" | "xz" |
-            // Executables
-            "exe" | "dll" | "so" | "dylib" | "bin" |
-            // Other binary
-            "dat" | "db" | "sqlite" | "sqlite3"
-        )
-    } else {
-        false
-    }
-}
-
 pub fn is_content_binary(content: &[u8]) -> bool {
     // Check first 8192 bytes for null bytes (common indicator of binary content)
     let check_length = std::cmp::min(content.len(), 8192);
     content[..check_length].contains(&0)
 }
 
-pub fn get_file_type_description(path: &Path) -> String {
-    if let Some(extension) = get_file_extension(path) {
-        match extension.as_str() {
-            // Documents
-            "pdf" => "PDF Document".to_string(),
-            "doc" => "Word Document".to_string(),
-            "docx" => "Word Document".to_string(),
-            "xls" => "Excel Spreadsheet".to_string(),
-            "xlsx" => "Excel Spreadsheet".to_string(),
-            "ppt" => "PowerPoint Presentation".to_string(),
-            "pptx" => "PowerPoint Presentation".to_string(),
-            
-            // Code
-            "py" => "Python Script".to_string(),
-            "js" => "JavaScript File".to_string(),
-            "html" => "HTML File".to_string(),
-            "css" => "CSS Stylesheet".to_string(),
-            "json" => "JSON Data".to_string(),
-            
-            // Text
-            "txt" => "Text File".to_string(),
-            "md" => "Markdown File".to_string(),
-            "csv" => "CSV Data".to_string(),
-            
-            // Images
-            "jpg" | "jpeg" => "JPEG Image".to_string(),
-            "png" => "PNG Image".to_string(),
-            "gif" => "GIF Image".to_string(),
-            "svg" => "SVG Image".to_string(),
-            
-            // Archives
-            "zip" => "ZIP Archive".to_string(),
-            "rar" => "RAR Archive".to_string(),
-            "7z" => "7-Zip Archive".to_string(),
-            
-            _ => format!("{} File", extension.to_uppercase()),
-        }
-    } else {
-        "Unknown File".to_string()
-    }
-}
-
 // File system utilities
 pub fn copy_directory_recursive(src: &Path, dst: &Path) -> Result<u64, std::io::Error> {
     let mut total_size = 0;
@@ -125,6 +144,24 @@ pub fn copy_directory_recursive(src: &Path, dst: &Path) -> Result<u64, std::io::
     Ok(total_size)
 }
 
+// Join a user-supplied relative path onto a project directory, refusing absolute
+// paths and `..` so the result can never point outside the project
+pub fn resolve_project_path(project_dir: &Path, relative_path: &str) -> Result<PathBuf, String> {
+    if relative_path.trim().is_empty() {
+        return Err("File path cannot be empty".to_string());
+    }
+
+    let relative = Path::new(relative_path);
+    for component in relative.components() {
+        match component {
+            Component::Normal(_) | Component::CurDir => {}
+            _ => return Err(format!("Path must stay inside the project: {}", relative_path)),
+        }
+    }
+
+    Ok(project_dir.join(relative))
+}
+
 pub fn ensure_parent_dir(path: &Path) -> Result<(), std::io::Error> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)?;
@@ -132,6 +169,15 @@ pub fn ensure_parent_dir(path: &Path) -> Result<(), std::io::Error> {
     Ok(())
 }
 
+// A file replaced through a temporary copy keeps the original's permissions, e.g. its executable bit
+pub fn copy_permissions(original: &Path, replacement: &Path) -> Result<(), std::io::Error> {
+    match fs::metadata(original) {
+        Ok(metadata) => fs::set_permissions(replacement, metadata.permissions()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
 pub fn safe_file_operation<F, T>(operation: F, operation_name: &str) -> Result<T, String>
 where
     F: FnOnce() -> Result<T, std::io::Error>,
@@ -2,202 +2,944 @@
 // Commands for web scraping and HTTP requests
 
 use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::header::{CONTENT_RANGE, RANGE, SET_COOKIE};
+use reqwest::{Client, Method, StatusCode};
+use scraper::{Html, Selector};
 use serde::{Deserialize, Serialize};
-use tauri::AppHandle;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager, State};
+use tokio::io::AsyncWriteExt;
 use uuid::Uuid;
+use super::{Node, ProjectData, artifacts::register_project_files, http_cache::cached_request, markdown::readable_format, text_encoding::detect_encoding, utils::*};
 
-#[derive(Debug, Serialize, Deserialize)]
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+const PART_SUFFIX: &str = ".part";
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+const DOWNLOAD_CANCELLED: &str = "Download cancelled";
+const META_CHARSET_SCAN_BYTES: usize = 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HttpRequest {
     pub url: String,
-    pub method: String, // GET, POST, etc.
+    pub method: String, // GET, POST, PUT, PATCH, DELETE or HEAD
     pub headers: Option<HashMap<String, String>>,
+    pub query: Option<HashMap<String, String>>,
     pub body: Option<String>,
     pub timeout_seconds: Option<u64>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseTiming {
+    pub headers_ms: u64, // Until the status line and headers arrived
+    pub total_ms: u64, // Including the body
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HttpResponse {
     pub status: u16,
     pub headers: HashMap<String, String>,
     pub body: String,
+    pub body_encoding: String, // "utf8" or "base64"
+    pub body_size: usize,
+    pub content_type: Option<String>,
+    pub final_url: String, // After redirects
+    pub timing: ResponseTiming,
     pub success: bool,
     pub error: Option<String>,
+    #[serde(default)]
+    pub from_cache: bool,
+    #[serde(skip)]
+    pub set_cookies: Vec<String>, // Every Set-Cookie header; `headers` keeps only the last one
 }
 
-#[tauri::command]
-pub async fn fetch_url(request: HttpRequest) -> Result<HttpResponse, String> {
-    use reqwest::Client;
-    use std::time::Duration;
-    
-    let client = Client::builder()
-        .timeout(Duration::from_secs(request.timeout_seconds.unwrap_or(30)))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-    
-    let mut req_builder = match request.method.to_uppercase().as_str() {
-        "GET" => client.get(&request.url),
-        "POST" => client.post(&request.url),
-        "PUT" => client.put(&request.url),
-        "DELETE" => client.delete(&request.url),
-        _ => return Err("Unsupported HTTP method".to_string()),
+#[derive(Debug, Deserialize)]
+pub struct DownloadRequest {
+    pub url: String,
+    pub project_id: String,
+    pub file_path: String, // Relative to the parent folder, or to the project root
+    pub parent_id: Option<String>,
+    pub expected_sha256: Option<String>, // Hex digest to verify before the file is kept
+    pub download_id: Option<String>, // Chosen by the caller to match progress events and cancel
+    pub resume: Option<bool>, // Continue an existing .part file (default true)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadProgressEvent {
+    pub download_id: String,
+    pub url: String,
+    pub file_path: String,
+    pub status: String, // "downloading", "verifying", "completed", "failed" or "cancelled"
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>, // None when the server sends no length
+    pub bytes_per_second: f64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DownloadResult {
+    pub download_id: String,
+    pub node: Node,
+    pub file_path: String,
+    pub size: u64,
+    pub sha256: String,
+    pub resumed_from: u64, // Bytes kept from an earlier partial download
+    pub duration_ms: u64,
+}
+
+struct StreamedDownload {
+    size: u64,
+    sha256: String,
+    resumed_from: u64,
+}
+
+/// Cancel flags of downloads in progress, by download id.
+#[derive(Default)]
+pub struct ActiveDownloads {
+    downloads: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+fn parse_method(method: &str) -> Result<Method, String> {
+    match method.to_uppercase().as_str() {
+        "GET" => Ok(Method::GET),
+        "POST" => Ok(Method::POST),
+        "PUT" => Ok(Method::PUT),
+        "PATCH" => Ok(Method::PATCH),
+        "DELETE" => Ok(Method::DELETE),
+        "HEAD" => Ok(Method::HEAD),
+        other => Err(format!("Unsupported HTTP method: {}", other)),
+    }
+}
+
+fn is_text_content_type(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or("").trim().to_lowercase();
+    mime.starts_with("text/")
+        || mime.ends_with("+json")
+        || mime.ends_with("+xml")
+        || matches!(
+            mime.as_str(),
+            "application/json" | "application/xml" | "application/javascript" | "application/x-www-form-urlencoded"
+        )
+}
+
+// Text bodies are returned as-is; anything else is base64 so no bytes are lost
+pub(crate) fn encode_body(bytes: &[u8], content_type: Option<&str>) -> (String, String) {
+    let declared_binary = content_type.map_or(false, |content_type| !is_text_content_type(content_type));
+
+    if !declared_binary && !is_content_binary(bytes) {
+        if let Ok(text) = std::str::from_utf8(bytes) {
+            return (text.to_string(), "utf8".to_string());
+        }
+    }
+    (BASE64.encode(bytes), "base64".to_string())
+}
+
+fn build_client(timeout_seconds: Option<u64>, follow_redirects: bool) -> Result<Client, String> {
+    let redirects = if follow_redirects {
+        reqwest::redirect::Policy::default()
+    } else {
+        reqwest::redirect::Policy::none()
     };
-    
+    Client::builder()
+        .timeout(Duration::from_secs(timeout_seconds.unwrap_or(DEFAULT_TIMEOUT_SECS)))
+        .redirect(redirects)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+pub(crate) async fn perform_request(request: &HttpRequest) -> Result<HttpResponse, String> {
+    send_request(request, true).await
+}
+
+// A single hop: a redirect comes back as the response, for callers that act on each hop
+pub(crate) async fn perform_request_without_redirects(request: &HttpRequest) -> Result<HttpResponse, String> {
+    send_request(request, false).await
+}
+
+async fn send_request(request: &HttpRequest, follow_redirects: bool) -> Result<HttpResponse, String> {
+    let method = parse_method(&request.method)?;
+    let client = build_client(request.timeout_seconds, follow_redirects)?;
+
+    let mut req_builder = client.request(method.clone(), &request.url);
+
+    if let Some(query) = &request.query {
+        req_builder = req_builder.query(query);
+    }
+
     // Add headers if provided
-    if let Some(headers) = request.headers {
+    if let Some(headers) = &request.headers {
         for (key, value) in headers {
-            req_builder = req_builder.header(&key, &value);
+            req_builder = req_builder.header(key, value);
         }
     }
-    
+
     // Add body if provided
-    if let Some(body) = request.body {
-        req_builder = req_builder.body(body);
-    }
-    
-    println!("🌐 Making HTTP request: {} {}", request.method, request.url);
-    
-    match req_builder.send().await {
-        Ok(response) => {
-            let status = response.status().as_u16();
-            let headers: HashMap<String, String> = response
-                .headers()
-                .iter()
-                .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
-                .collect();
-            
-            match response.text().await {
-                Ok(body) => {
-                    println!("✅ HTTP request completed: {} bytes", body.len());
-                    Ok(HttpResponse {
-                        status,
-                        headers,
-                        body,
-                        success: true,
-                        error: None,
-                    })
-                }
-                Err(e) => {
-                    let error_msg = format!("Failed to read response body: {}", e);
-                    Ok(HttpResponse {
-                        status,
-                        headers,
-                        body: String::new(),
-                        success: false,
-                        error: Some(error_msg),
-                    })
-                }
-            }
-        }
+    if let Some(body) = &request.body {
+        req_builder = req_builder.body(body.clone());
+    }
+
+    println!("🌐 Making HTTP request: {} {}", method, request.url);
+    let start_time = Instant::now();
+
+    let response = match req_builder.send().await {
+        Ok(response) => response,
         Err(e) => {
             let error_msg = format!("HTTP request failed: {}", e);
             println!("❌ {}", error_msg);
-            Ok(HttpResponse {
+            let elapsed = start_time.elapsed().as_millis() as u64;
+            return Ok(HttpResponse {
                 status: 0,
                 headers: HashMap::new(),
                 body: String::new(),
+                body_encoding: "utf8".to_string(),
+                body_size: 0,
+                content_type: None,
+                final_url: request.url.clone(),
+                timing: ResponseTiming { headers_ms: elapsed, total_ms: elapsed },
+                success: false,
+                error: Some(error_msg),
+                from_cache: false,
+                set_cookies: Vec::new(),
+            });
+        }
+    };
+
+    let headers_ms = start_time.elapsed().as_millis() as u64;
+    let status = response.status().as_u16();
+    let final_url = response.url().to_string();
+    let headers: HashMap<String, String> = response
+        .headers()
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
+        .collect();
+    let set_cookies: Vec<String> = response.headers()
+        .get_all(SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok().map(str::to_string))
+        .collect();
+    let content_type = headers.get("content-type").cloned();
+
+    // HEAD responses carry headers only
+    let body_result = if method == Method::HEAD {
+        Ok(Vec::new())
+    } else {
+        response.bytes().await.map(|bytes| bytes.to_vec())
+    };
+    let timing = ResponseTiming {
+        headers_ms,
+        total_ms: start_time.elapsed().as_millis() as u64,
+    };
+
+    match body_result {
+        Ok(bytes) => {
+            let (body, body_encoding) = encode_body(&bytes, content_type.as_deref());
+            println!("✅ HTTP request completed: {} {} bytes in {}ms", status, bytes.len(), timing.total_ms);
+            Ok(HttpResponse {
+                status,
+                headers,
+                body,
+                body_encoding,
+                body_size: bytes.len(),
+                content_type,
+                final_url,
+                timing,
+                success: true,
+                error: None,
+                from_cache: false,
+                set_cookies,
+            })
+        }
+        Err(e) => {
+            let error_msg = format!("Failed to read response body: {}", e);
+            Ok(HttpResponse {
+                status,
+                headers,
+                body: String::new(),
+                body_encoding: "utf8".to_string(),
+                body_size: 0,
+                content_type,
+                final_url,
+                timing,
                 success: false,
                 error: Some(error_msg),
+                from_cache: false,
+                set_cookies,
             })
         }
     }
 }
 
+// Values of a `Content-Range: bytes start-end/total` header
+fn parse_content_range(value: &str) -> Option<(u64, Option<u64>)> {
+    let (range, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+    let start = range.split_once('-')?.0.trim().parse().ok()?;
+    Some((start, total.trim().parse().ok()))
+}
+
+// Hidden, so sync and the watcher never register an unfinished download as a node
+fn part_path_for(full_path: &Path) -> PathBuf {
+    let mut part_name = std::ffi::OsString::from(".");
+    part_name.push(full_path.file_name().unwrap_or_default());
+    part_name.push(PART_SUFFIX);
+    full_path.with_file_name(part_name)
+}
+
+// Feed an interrupted download back into the hasher so the checksum covers the whole file
+async fn hash_existing_part(part_path: &Path) -> Result<Sha256, String> {
+    let part_path = part_path.to_path_buf();
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut hasher = Sha256::new();
+        let mut file = fs::File::open(&part_path)?;
+        std::io::copy(&mut file, &mut hasher)?;
+        Ok::<_, std::io::Error>(hasher)
+    })
+        .await
+        .map_err(|e| format!("Failed to hash partial download: {}", e))?
+        .map_err(|e| format!("Failed to hash partial download: {}", e))
+}
+
+/// Streams `url` into `part_path`, resuming from an existing partial file when
+/// `resume` is set and the server honours the Range request. `on_progress`
+/// receives (downloaded bytes, total bytes if known, bytes per second).
+async fn stream_to_part(
+    url: &str,
+    part_path: &Path,
+    resume: bool,
+    cancel: &AtomicBool,
+    mut on_progress: impl FnMut(u64, Option<u64>, f64),
+) -> Result<StreamedDownload, String> {
+    // No overall timeout: large downloads legitimately take longer than any fixed limit
+    let client = Client::builder()
+        .connect_timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let mut offset = if resume {
+        fs::metadata(part_path).map(|metadata| metadata.len()).unwrap_or(0)
+    } else {
+        0
+    };
+
+    let mut response = loop {
+        let mut request = client.get(url);
+        if offset > 0 {
+            request = request.header(RANGE, format!("bytes={}-", offset));
+        }
+        let response = request.send()
+            .await
+            .map_err(|e| format!("Failed to download file: {}", e))?;
+
+        // The partial file no longer matches the remote one: start over
+        if offset > 0 && response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+            println!("⚠️ DOWNLOAD: Server rejected resume at {} bytes, restarting", offset);
+            offset = 0;
+            continue;
+        }
+        if !response.status().is_success() {
+            return Err(format!("HTTP error: {}", response.status()));
+        }
+        break response;
+    };
+
+    let mut total = response.content_length();
+    if offset > 0 {
+        let content_range = response.headers()
+            .get(CONTENT_RANGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_content_range);
+
+        match (response.status(), content_range) {
+            (StatusCode::PARTIAL_CONTENT, Some((start, range_total))) if start == offset => {
+                println!("⏯️ DOWNLOAD: Resuming at {} bytes", offset);
+                total = range_total.or_else(|| total.map(|length| length + offset));
+            }
+            (StatusCode::PARTIAL_CONTENT, _) => {
+                return Err("Server resumed the download at an unexpected offset".to_string());
+            }
+            // The server ignored the Range header and sent the whole file
+            _ => offset = 0,
+        }
+    }
+
+    let mut hasher = if offset > 0 {
+        hash_existing_part(part_path).await?
+    } else {
+        Sha256::new()
+    };
+
+    ensure_parent_dir(part_path)
+        .map_err(|e| format!("Failed to create directory: {}", e))?;
+    let mut options = tokio::fs::OpenOptions::new();
+    if offset > 0 {
+        options.append(true);
+    } else {
+        options.write(true).create(true).truncate(true);
+    }
+    let mut file = options.open(part_path)
+        .await
+        .map_err(|e| format!("Failed to open partial file: {}", e))?;
+
+    let started = Instant::now();
+    let mut last_progress = started;
+    let mut downloaded = offset;
+    let rate = |downloaded: u64| {
+        let seconds = started.elapsed().as_secs_f64();
+        if seconds > 0.0 { (downloaded - offset) as f64 / seconds } else { 0.0 }
+    };
+
+    let streamed = loop {
+        if cancel.load(Ordering::SeqCst) {
+            break Err(DOWNLOAD_CANCELLED.to_string());
+        }
+
+        let chunk = match response.chunk().await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => break Ok(()),
+            Err(e) => break Err(format!("Download interrupted: {}", e)),
+        };
+        if let Err(e) = file.write_all(&chunk).await {
+            break Err(format!("Failed to write file: {}", e));
+        }
+        hasher.update(&chunk);
+        downloaded += chunk.len() as u64;
+
+        if last_progress.elapsed() >= PROGRESS_INTERVAL {
+            last_progress = Instant::now();
+            on_progress(downloaded, total, rate(downloaded));
+        }
+    };
+
+    // Also after a failure, so a resume starts from what arrived and the last
+    // progress report says how much that was
+    let flushed = file.flush()
+        .await
+        .map_err(|e| format!("Failed to write file: {}", e));
+    on_progress(downloaded, total, rate(downloaded));
+    streamed?;
+    flushed?;
+
+    if let Some(total) = total {
+        if downloaded != total {
+            return Err(format!("Download incomplete: received {} of {} bytes", downloaded, total));
+        }
+    }
+
+    Ok(StreamedDownload {
+        size: downloaded,
+        sha256: format!("{:x}", hasher.finalize()),
+        resumed_from: offset,
+    })
+}
+
+/// Resolves a destination relative to the project, inside `parent_id` when given.
+pub(crate) fn destination_path(
+    data: &ProjectData,
+    project_id: &str,
+    parent_id: Option<&str>,
+    file_path: &str,
+) -> Result<String, String> {
+    let file_path = file_path.replace('\\', "/");
+    let parent_path = match parent_id {
+        Some(parent_id) => {
+            let parent = data.nodes.iter()
+                .find(|node| node.id == parent_id && node.project_id == project_id)
+                .ok_or_else(|| format!("Parent folder not found: {}", parent_id))?;
+            if parent.r#type != "folder" {
+                return Err(format!("{} is not a folder", parent.name));
+            }
+            parent.file_path.clone().unwrap_or_default()
+        }
+        None => String::new(),
+    };
+
+    let parent_path = parent_path.replace('\\', "/");
+    let parent_path = parent_path.trim_matches('/');
+    Ok(if parent_path.is_empty() {
+        file_path
+    } else {
+        format!("{}/{}", parent_path, file_path)
+    })
+}
+
+/// Registers a file just written at `relative_path` under its folder, or refreshes
+/// the node it replaced, and returns that node.
+pub(crate) fn register_saved_file(
+    app: &AppHandle,
+    project_id: &str,
+    relative_path: &str,
+    existed: bool,
+) -> Result<Node, String> {
+    let paths = vec![relative_path.to_string()];
+    if existed {
+        register_project_files(app, project_id, &[], &paths, None)?;
+    } else {
+        register_project_files(app, project_id, &paths, &[], None)?;
+    }
+
+    load_project_data(app)?
+        .nodes
+        .into_iter()
+        .find(|node| node.project_id == project_id && node.file_path.as_deref() == Some(relative_path))
+        .ok_or_else(|| format!("{} was not registered", relative_path))
+}
+
+/// Writes `content` to `file_path` (inside `parent_id` when given) and registers it.
+pub(crate) fn save_project_file(
+    app: &AppHandle,
+    project_id: &str,
+    parent_id: Option<&str>,
+    file_path: &str,
+    content: &[u8],
+) -> Result<Node, String> {
+    let project_dir = get_project_dir(app, project_id)?;
+    let relative_path = destination_path(&load_project_data(app)?, project_id, parent_id, file_path)?;
+    let full_path = resolve_project_path(&project_dir, &relative_path)?;
+
+    let existed = full_path.exists();
+    ensure_parent_dir(&full_path)
+        .map_err(|e| format!("Failed to create directory: {}", e))?;
+    safe_file_operation(
+        || fs::write(&full_path, content),
+        "Failed to write file"
+    )?;
+
+    println!("💾 Saved {}", relative_path);
+    register_saved_file(app, project_id, &relative_path, existed)
+}
+
+fn emit_download_progress(app: &AppHandle, event: DownloadProgressEvent) {
+    if let Err(e) = app.emit_all("download-progress", event) {
+        eprintln!("⚠️ DOWNLOAD: Failed to emit progress: {}", e);
+    }
+}
+
+/// Fetches a page as text, returning it with the final URL (after redirects).
+/// Goes through the response cache, so selectors can be developed offline.
+pub(crate) async fn fetch_html(app: &AppHandle, url: &str) -> Result<(String, String), String> {
+    let response = cached_request(app, &HttpRequest {
+        url: url.to_string(),
+        method: "GET".to_string(),
+        headers: None,
+        query: None,
+        body: None,
+        timeout_seconds: None,
+    }).await?;
+
+    if !response.success {
+        return Err(response.error.unwrap_or_else(|| "Failed to fetch webpage".to_string()));
+    }
+    if !(200..300).contains(&response.status) {
+        return Err(match StatusCode::from_u16(response.status) {
+            Ok(status) => format!("HTTP error: {}", status),
+            Err(_) => format!("HTTP error: {}", response.status),
+        });
+    }
+
+    let html_content = if response.body_encoding == "base64" {
+        let bytes = BASE64.decode(&response.body)
+            .map_err(|e| format!("Failed to read webpage content: {}", e))?;
+        decode_html(&bytes, response.content_type.as_deref())
+    } else {
+        response.body
+    };
+    Ok((html_content, response.final_url))
+}
+
+static META_CHARSET: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)<meta[^>]+charset\s*=\s*["']?\s*([A-Za-z0-9_:.\-]+)"#).unwrap()
+});
+
+// The charset a page declares in a <meta> tag near its start
+fn meta_charset(bytes: &[u8]) -> Option<&'static encoding_rs::Encoding> {
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(META_CHARSET_SCAN_BYTES)]);
+    let label = META_CHARSET.captures(&head)?.get(1)?.as_str().to_string();
+    encoding_rs::Encoding::for_label(label.as_bytes())
+}
+
+/// Decodes a page in the charset it declares: BOM, then the Content-Type header,
+/// then a <meta> tag, and otherwise whatever the bytes look like.
+pub(crate) fn decode_html(bytes: &[u8], content_type: Option<&str>) -> String {
+    let declared = content_type
+        .and_then(|content_type| content_type.split(';')
+            .filter_map(|parameter| parameter.trim().split_once('='))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("charset"))
+            .map(|(_, value)| value.trim().trim_matches('"').to_string()))
+        .and_then(|label| encoding_rs::Encoding::for_label(label.as_bytes()));
+
+    let encoding = encoding_rs::Encoding::for_bom(bytes).map(|(encoding, _)| encoding)
+        .or(declared)
+        .or_else(|| meta_charset(bytes))
+        .unwrap_or_else(|| detect_encoding(bytes).0);
+    encoding.decode(bytes).0.into_owned()
+}
+
+fn extract_text(html_content: &str, css_selector: &str) -> Result<String, String> {
+    let document = Html::parse_document(html_content);
+    let selector = Selector::parse(css_selector)
+        .map_err(|e| format!("Invalid CSS selector: {}", e))?;
+
+    let extracted_text: Vec<String> = document.select(&selector)
+        .map(|element| element.text().collect::<Vec<_>>().join(" "))
+        .collect();
+
+    Ok(extracted_text.join("\n"))
+}
+
+#[tauri::command]
+pub async fn fetch_url(app: AppHandle, request: HttpRequest) -> Result<HttpResponse, String> {
+    cached_request(&app, &request).await
+}
+
 #[tauri::command]
 pub async fn download_file(
     app: AppHandle,
-    url: String,
-    project_id: String,
-    file_path: String,
-) -> Result<super::ImportResult, String> {
-    use reqwest::Client;
-    use std::io::Write;
-    use uuid::Uuid;
-    
-    let client = Client::new();
-    
+    downloads: State<'_, ActiveDownloads>,
+    request: DownloadRequest,
+) -> Result<DownloadResult, String> {
+    let DownloadRequest { url, project_id, file_path, parent_id, expected_sha256, download_id, resume } = request;
     println!("📥 Downloading file from: {}", url);
-    
-    let response = client.get(&url)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to download file: {}", e))?;
-    
-    if !response.status().is_success() {
-        return Err(format!("HTTP error: {}", response.status()));
+
+    // Validate the destination before spending time on the download
+    let project_dir = get_project_dir(&app, &project_id)?;
+    let relative_path = destination_path(&load_project_data(&app)?, &project_id, parent_id.as_deref(), &file_path)?;
+    let full_path = resolve_project_path(&project_dir, &relative_path)?;
+    let part_path = part_path_for(&full_path);
+
+    let download_id = download_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+    let cancel = Arc::new(AtomicBool::new(false));
+    {
+        let mut active = downloads.downloads.lock().unwrap();
+        if active.contains_key(&download_id) {
+            return Err(format!("Download {} is already running", download_id));
+        }
+        active.insert(download_id.clone(), cancel.clone());
     }
-    
-    let bytes = response.bytes()
-        .await
-        .map_err(|e| format!("Failed to read file bytes: {}", e))?;
-    
-    // Save to project directory
-    let project_dir = super::utils::get_project_dir(&app, &project_id)?;
-    let full_path = project_dir.join(&file_path);
-    
-    super::utils::ensure_parent_dir(&full_path)
-        .map_err(|e| format!("Failed to create directory: {}", e))?;
-    
-    let mut file = std::fs::File::create(&full_path)
-        .map_err(|e| format!("Failed to create file: {}", e))?;
-    
-    file.write_all(&bytes)
-        .map_err(|e| format!("Failed to write file: {}", e))?;
-    
-    let file_name = full_path.file_name()
-        .unwrap_or_else(|| std::ffi::OsStr::new(&file_path))
-        .to_string_lossy()
-        .to_string();
-    
-    let extension = super::utils::get_file_extension(&full_path);
-    let is_binary = super::utils::is_binary_file(&full_path);
-    
-    println!("✅ Downloaded file: {} ({} bytes)", file_name, bytes.len());
-    
-    Ok(super::ImportResult {
-        node_id: Uuid::new_v4().to_string(),
-        name: file_name,
-        r#type: "file".to_string(),
-        extension,
-        size: bytes.len() as u64,
-        is_binary,
+
+    let progress_event = |status: &str, downloaded_bytes: u64, total_bytes: Option<u64>, bytes_per_second: f64, error: Option<String>| {
+        DownloadProgressEvent {
+            download_id: download_id.clone(),
+            url: url.clone(),
+            file_path: relative_path.clone(),
+            status: status.to_string(),
+            downloaded_bytes,
+            total_bytes,
+            bytes_per_second,
+            error,
+        }
+    };
+
+    let started = Instant::now();
+    let mut received = (0, None);
+    let streamed = stream_to_part(&url, &part_path, resume.unwrap_or(true), &cancel, |downloaded, total, rate| {
+        received = (downloaded, total);
+        emit_download_progress(&app, progress_event("downloading", downloaded, total, rate, None));
+    }).await;
+    downloads.downloads.lock().unwrap().remove(&download_id);
+
+    let verified = streamed.and_then(|streamed| {
+        if let Some(expected) = &expected_sha256 {
+            emit_download_progress(&app, progress_event("verifying", streamed.size, Some(streamed.size), 0.0, None));
+            if !expected.trim().eq_ignore_ascii_case(&streamed.sha256) {
+                // A corrupt partial file must not be resumed later
+                let _ = fs::remove_file(&part_path);
+                return Err(format!("Checksum mismatch: expected {}, got {}", expected.trim(), streamed.sha256));
+            }
+        }
+        Ok(streamed)
+    });
+
+    let streamed = match verified {
+        Ok(streamed) => streamed,
+        Err(e) => {
+            // Keep the .part file after a cancel or network error so the download can resume
+            let status = if e == DOWNLOAD_CANCELLED { "cancelled" } else { "failed" };
+            println!("❌ DOWNLOAD: {} {}: {}", status, url, e);
+            let (downloaded, total) = received;
+            emit_download_progress(&app, progress_event(status, downloaded, total, 0.0, Some(e.clone())));
+            return Err(e);
+        }
+    };
+
+    let existed = full_path.exists();
+    safe_file_operation(
+        || fs::rename(&part_path, &full_path),
+        "Failed to move download into place"
+    )?;
+    let node = register_saved_file(&app, &project_id, &relative_path, existed)?;
+
+    emit_download_progress(&app, progress_event("completed", streamed.size, Some(streamed.size), 0.0, None));
+    println!("✅ Downloaded file: {} ({} bytes)", relative_path, streamed.size);
+
+    Ok(DownloadResult {
+        download_id: download_id.clone(),
+        node,
+        file_path: relative_path.clone(),
+        size: streamed.size,
+        sha256: streamed.sha256,
+        resumed_from: streamed.resumed_from,
+        duration_ms: started.elapsed().as_millis() as u64,
     })
 }
 
+#[tauri::command]
+pub async fn cancel_download(
+    downloads: State<'_, ActiveDownloads>,
+    download_id: String,
+) -> Result<(), String> {
+    let active = downloads.downloads.lock().unwrap();
+    let cancel = active.get(&download_id)
+        .ok_or_else(|| format!("No running download: {}", download_id))?;
+    cancel.store(true, Ordering::SeqCst);
+    println!("🛑 DOWNLOAD: Cancel requested for {}", download_id);
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn scrape_webpage(
+    app: AppHandle,
     url: String,
     selector: Option<String>,
+    format: Option<String>, // "html" (default), "markdown" or "text"
 ) -> Result<String, String> {
-    use reqwest::Client;
-    use scraper::{Html, Selector};
-    
-    let client = Client::new();
-    
     println!("🕷️ Scraping webpage: {}", url);
-    
-    let response = client.get(&url)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch webpage: {}", e))?;
-    
-    let html_content = response.text()
-        .await
-        .map_err(|e| format!("Failed to read webpage content: {}", e))?;
-    
-    if let Some(css_selector) = selector {
+
+    let (html_content, final_url) = fetch_html(&app, &url).await?;
+
+    match (format.as_deref().unwrap_or("html"), selector) {
         // Parse HTML and extract specific elements
-        let document = Html::parse_document(&html_content);
-        let selector = Selector::parse(&css_selector)
-            .map_err(|e| format!("Invalid CSS selector: {}", e))?;
-        
-        let mut extracted_text = Vec::new();
-        for element in document.select(&selector) {
-            extracted_text.push(element.text().collect::<Vec<_>>().join(" "));
-        }
-        
-        Ok(extracted_text.join("\n"))
-    } else {
+        ("html", Some(css_selector)) => extract_text(&html_content, &css_selector),
         // Return full HTML
-        Ok(html_content)
+        ("html", None) => Ok(html_content),
+        // Main content (or the selected element) as Markdown or readable text
+        (format, selector) => readable_format(&html_content, &final_url, selector.as_deref(), format),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_server::*;
+
+    fn echo(request: &MockRequest) -> MockResponse {
+        let body = format!("{} {} {}", request.method, request.target, String::from_utf8_lossy(&request.body));
+        respond(200, "text/plain; charset=utf-8", body.into_bytes())
+    }
+
+    fn request(method: &str, url: String) -> HttpRequest {
+        HttpRequest {
+            url,
+            method: method.to_string(),
+            headers: None,
+            query: None,
+            body: None,
+            timeout_seconds: Some(5),
+        }
+    }
+
+    #[tokio::test]
+    async fn get_sends_query_params_and_returns_text() {
+        let base = spawn_server(echo);
+        let mut get = request("get", format!("{}/echo", base));
+        get.query = Some(HashMap::from([("page".to_string(), "2".to_string())]));
+
+        let response = perform_request(&get).await.unwrap();
+
+        assert!(response.success);
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, "GET /echo?page=2 ");
+        assert_eq!(response.body_encoding, "utf8");
+        assert!(response.timing.total_ms >= response.timing.headers_ms);
+    }
+
+    #[tokio::test]
+    async fn patch_sends_body() {
+        let base = spawn_server(echo);
+        let mut patch = request("PATCH", format!("{}/items/1", base));
+        patch.body = Some("{\"done\":true}".to_string());
+
+        let response = perform_request(&patch).await.unwrap();
+
+        assert_eq!(response.body, "PATCH /items/1 {\"done\":true}");
+    }
+
+    #[tokio::test]
+    async fn head_returns_headers_without_body() {
+        let base = spawn_server(echo);
+
+        let response = perform_request(&request("HEAD", format!("{}/", base))).await.unwrap();
+
+        assert!(response.success);
+        assert_eq!(response.status, 200);
+        assert!(response.body.is_empty());
+        assert_eq!(response.content_type.as_deref(), Some("text/plain; charset=utf-8"));
+    }
+
+    #[tokio::test]
+    async fn binary_body_is_base64() {
+        fn png(_: &MockRequest) -> MockResponse {
+            respond(200, "image/png", vec![0x89, b'P', b'N', b'G', 0x00, 0xff])
+        }
+        let base = spawn_server(png);
+
+        let response = perform_request(&request("GET", format!("{}/logo.png", base))).await.unwrap();
+
+        assert_eq!(response.body_encoding, "base64");
+        assert_eq!(response.body_size, 6);
+        assert_eq!(BASE64.decode(&response.body).unwrap(), vec![0x89, b'P', b'N', b'G', 0x00, 0xff]);
+    }
+
+    #[tokio::test]
+    async fn unsupported_method_is_rejected() {
+        let result = perform_request(&request("TRACE", "http://127.0.0.1:9/".to_string())).await;
+        assert!(result.is_err());
+    }
+
+    const DATASET: &[u8] = b"id,value\n1,alpha\n2,beta\n3,gamma\n";
+
+    fn sha256_hex(bytes: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(bytes))
+    }
+
+    // Serves DATASET, honouring `Range: bytes=N-`
+    fn dataset(request: &MockRequest) -> MockResponse {
+        let start = request.header("range")
+            .and_then(|range| range.strip_prefix("bytes="))
+            .and_then(|range| range.trim_end_matches('-').parse::<usize>().ok());
+
+        match start {
+            Some(start) if start < DATASET.len() => {
+                let mut reply = respond(206, "text/csv", DATASET[start..].to_vec());
+                reply.headers.push(("Content-Range", format!("bytes {}-{}/{}", start, DATASET.len() - 1, DATASET.len())));
+                reply
+            }
+            Some(_) => respond(416, "text/plain", Vec::new()),
+            None => respond(200, "text/csv", DATASET.to_vec()),
+        }
+    }
+
+    async fn download(url: &str, part_path: &Path, cancel: &AtomicBool) -> Result<StreamedDownload, String> {
+        stream_to_part(url, part_path, true, cancel, |_, _, _| {}).await
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn download_streams_into_part_file_with_checksum() {
+        let base = spawn_server(dataset);
+        let dir = tempfile::tempdir().unwrap();
+        let part_path = dir.path().join("data/data.csv.part");
+        let mut progress = Vec::new();
+
+        let streamed = stream_to_part(&format!("{}/data.csv", base), &part_path, true, &AtomicBool::new(false), |downloaded, total, _| {
+            progress.push((downloaded, total));
+        }).await.unwrap();
+
+        assert_eq!(std::fs::read(&part_path).unwrap(), DATASET);
+        assert_eq!(streamed.size, DATASET.len() as u64);
+        assert_eq!(streamed.resumed_from, 0);
+        assert_eq!(streamed.sha256, sha256_hex(DATASET));
+        assert_eq!(progress.last(), Some(&(DATASET.len() as u64, Some(DATASET.len() as u64))));
+    }
+
+    #[tokio::test]
+    async fn download_resumes_from_existing_part_file() {
+        let base = spawn_server(dataset);
+        let dir = tempfile::tempdir().unwrap();
+        let part_path = dir.path().join("data.csv.part");
+        std::fs::write(&part_path, &DATASET[..10]).unwrap();
+
+        let streamed = download(&format!("{}/data.csv", base), &part_path, &AtomicBool::new(false)).await.unwrap();
+
+        assert_eq!(streamed.resumed_from, 10);
+        assert_eq!(std::fs::read(&part_path).unwrap(), DATASET);
+        assert_eq!(streamed.sha256, sha256_hex(DATASET));
+    }
+
+    #[tokio::test]
+    async fn download_restarts_when_range_is_ignored_or_rejected() {
+        fn full_only(_: &MockRequest) -> MockResponse {
+            respond(200, "text/csv", DATASET.to_vec())
+        }
+        let dir = tempfile::tempdir().unwrap();
+
+        let part_path = dir.path().join("ignored.csv.part");
+        std::fs::write(&part_path, b"stale").unwrap();
+        let streamed = download(&format!("{}/data.csv", spawn_server(full_only)), &part_path, &AtomicBool::new(false)).await.unwrap();
+        assert_eq!(streamed.resumed_from, 0);
+        assert_eq!(std::fs::read(&part_path).unwrap(), DATASET);
+
+        // A partial file longer than the remote one gets 416 and is replaced
+        let part_path = dir.path().join("rejected.csv.part");
+        std::fs::write(&part_path, vec![b'x'; DATASET.len() + 5]).unwrap();
+        let streamed = download(&format!("{}/data.csv", spawn_server(dataset)), &part_path, &AtomicBool::new(false)).await.unwrap();
+        assert_eq!(streamed.resumed_from, 0);
+        assert_eq!(std::fs::read(&part_path).unwrap(), DATASET);
+    }
+
+    #[tokio::test]
+    async fn cancelled_download_keeps_part_file() {
+        let base = spawn_server(dataset);
+        let dir = tempfile::tempdir().unwrap();
+        let part_path = dir.path().join("data.csv.part");
+        std::fs::write(&part_path, &DATASET[..10]).unwrap();
+
+        let mut received = Vec::new();
+        let result = stream_to_part(&format!("{}/data.csv", base), &part_path, true, &AtomicBool::new(true), |downloaded, _, _| {
+            received.push(downloaded);
+        }).await;
+
+        assert_eq!(result.err().as_deref(), Some(DOWNLOAD_CANCELLED));
+        assert_eq!(std::fs::read(&part_path).unwrap(), &DATASET[..10]);
+        // The failure is reported with what had arrived
+        assert_eq!(received.last(), Some(&10));
+    }
+
+    #[tokio::test]
+    async fn download_fails_on_http_error() {
+        fn missing(_: &MockRequest) -> MockResponse {
+            respond(404, "text/plain", b"not found".to_vec())
+        }
+        let base = spawn_server(missing);
+        let dir = tempfile::tempdir().unwrap();
+
+        let result = download(&format!("{}/missing", base), &dir.path().join("missing.part"), &AtomicBool::new(false)).await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parses_content_range() {
+        assert_eq!(parse_content_range("bytes 10-29/30"), Some((10, Some(30))));
+        assert_eq!(parse_content_range("bytes 10-29/*"), Some((10, None)));
+        assert_eq!(parse_content_range("items 1-2/3"), None);
+    }
+
+    #[test]
+    fn part_file_sits_next_to_the_destination() {
+        assert_eq!(part_path_for(Path::new("/p/data/big.zip")), PathBuf::from("/p/data/.big.zip.part"));
+    }
+
+    #[test]
+    fn extract_text_uses_selector() {
+        let html = "<html><body><h1>Title</h1><p class='x'>One</p><p class='x'>Two</p></body></html>";
+
+        assert_eq!(extract_text(html, "p.x").unwrap(), "One\nTwo");
+        assert!(extract_text(html, "p[").is_err());
+    }
+
+    #[test]
+    fn html_is_decoded_in_its_declared_charset() {
+        let latin1 = b"<html><head><meta charset=\"iso-8859-1\"></head><body>caf\xe9</body></html>";
+        assert!(decode_html(latin1, Some("text/html")).contains("caf\u{e9}"));
+        assert!(decode_html(b"caf\xe9", Some("text/html; charset=ISO-8859-1")).ends_with("caf\u{e9}"));
+
+        // http-equiv declarations count too, and UTF-8 needs no declaration
+        let shift_jis = b"<meta http-equiv=\"Content-Type\" content=\"text/html; charset=Shift_JIS\">\x82\xa0";
+        assert!(decode_html(shift_jis, None).ends_with('\u{3042}'));
+        assert!(decode_html("caf\u{e9}".as_bytes(), Some("text/html")).ends_with("caf\u{e9}"));
+    }
+}
@@ -10,6 +10,23 @@ use std::fs;
 
 fn main() {
     tauri::Builder::default()
+        .manage(commands::SessionManager::default())
+        .manage(commands::ExecutionQueue::default())
+        .manage(commands::ExecutionHistory::default())
+        .manage(commands::RunningExecutions::default())
+        .manage(commands::Scheduler::default())
+        .manage(commands::ProjectEnvironments::default())
+        .manage(commands::ActiveDownloads::default())
+        .manage(commands::CrawlJobs::default())
+        .manage(commands::HttpCollections::default())
+        .manage(commands::ActiveSearches::default())
+        .manage(commands::SearchIndexes::default())
+        .manage(commands::UndoJournal::default())
+        .manage(commands::FileFinder::default())
+        .manage(commands::FileLineIndexes::default())
+        .manage(commands::FileTypes::default())
+        .manage(commands::utils::ProjectDataLock::default())
+        .manage(commands::artifacts::ProjectRuns::default())
         .invoke_handler(tauri::generate_handler![
             // Project management
             commands::load_projects,
@@ -24,8 +41,18 @@ fn main() {
             
             // Content management
             commands::get_file_content,
+            commands::get_file_content_versioned,
+            commands::get_file_version,
             commands::save_file_content,
             commands::get_file_stats,
+            commands::read_file_range,
+            commands::read_file_bytes,
+            commands::write_file_bytes,
+            
+            // Hex viewer
+            commands::get_hex_dump,
+            commands::search_file_bytes,
+            commands::get_file_type,
             
             // Import/Export operations
             commands::import_file,
@@ -44,6 +71,103 @@ fn main() {
             commands::check_python_installation,
             commands::install_python_package,
             
+            // Persistent Python / Jupyter sessions
+            commands::start_python_session,
+            commands::execute_in_session,
+            commands::execute_notebook_cell,
+            commands::interrupt_python_session,
+            commands::restart_python_session,
+            commands::stop_python_session,
+            commands::list_python_sessions,
+            
+            // Backend execution queue
+            commands::enqueue_execution_job,
+            commands::get_execution_queue,
+            commands::cancel_execution_job,
+            commands::clear_finished_execution_jobs,
+            
+            // Script pipelines
+            commands::get_pipeline,
+            commands::save_pipeline,
+            commands::run_pipeline,
+            commands::reset_pipeline_state,
+            
+            // Execution history
+            commands::get_execution_history,
+            commands::rerun_execution,
+            commands::prune_execution_history,
+            
+            // Language runners
+            commands::execute_file,
+            commands::cancel_execution,
+            commands::get_runner_settings,
+            commands::save_runner_settings,
+            commands::reset_runner_settings,
+            commands::check_runner_availability,
+            
+            // Scheduled runs
+            commands::create_schedule,
+            commands::list_schedules,
+            commands::pause_schedule,
+            commands::resume_schedule,
+            commands::delete_schedule,
+            commands::run_schedule_now,
+            commands::preview_schedule,
+            
+            // Project environment and secrets
+            commands::get_project_environment,
+            commands::set_project_env_var,
+            commands::delete_project_env_var,
+            
+            // Web scraping and HTTP requests
+            commands::fetch_url,
+            commands::download_file,
+            commands::cancel_download,
+            commands::scrape_webpage,
+            commands::scrape_structured,
+            commands::scrape_tables,
+            commands::scrape_links,
+            commands::scrape_images,
+            commands::save_scrape_result,
+            commands::convert_html_to_markdown,
+            commands::save_webpage_as_markdown,
+            commands::start_crawl,
+            commands::cancel_crawl,
+            commands::list_crawls,
+
+            // Saved HTTP request collections
+            commands::list_request_collections,
+            commands::save_request_collection,
+            commands::delete_request_collection,
+            commands::import_http_requests,
+            commands::send_saved_request,
+            commands::list_request_history,
+            commands::clear_request_history,
+            commands::save_history_response,
+            commands::list_request_cookies,
+            commands::clear_request_cookies,
+            commands::get_http_cache_status,
+            commands::save_http_cache_settings,
+            commands::clear_http_cache,
+
+            // Content search
+            commands::search_content,
+            commands::cancel_search,
+            commands::query_search_index,
+            commands::rebuild_search_index,
+            commands::get_search_index_health,
+            commands::delete_search_index,
+
+            // Find and replace with undo
+            commands::preview_replace,
+            commands::apply_replace,
+            commands::get_undo_journal,
+            commands::undo_edit_batch,
+
+            // Fuzzy file finder
+            commands::find_files,
+            commands::record_file_access,
+            
             // File sync - UPDATED COMMANDS
             commands::sync_external_files,
             commands::auto_sync_project_files,
@@ -161,6 +285,12 @@ fn main() {
                 }
             }
             
+            // Resume execution queue jobs interrupted by the last shutdown
+            commands::restore_execution_queue(&app.handle());
+            
+            // Start the scheduler; runs missed while closed follow each schedule's catch-up policy
+            commands::restore_schedules(&app.handle());
+            
             println!("✅ App setup completed successfully");
             Ok(())
         })
@@ -16,8 +16,14 @@ import ExecutionControls from './CodeRunner/ExecutionControls';
 import ExecutionQueue from './CodeRunner/ExecutionQueue';
 import OutputConsole from './CodeRunner/OutputConsole';
 import ExecutionHistory from './CodeRunner/ExecutionHistory';
+import RunnerSettings from './CodeRunner/RunnerSettings';
+import SchedulePanel from './CodeRunner/SchedulePanel';
+import EnvironmentPanel from './CodeRunner/EnvironmentPanel';
 import { useCodeExecution } from './CodeRunner/hooks/useCodeExecution';
 import { usePythonEnvironment } from './CodeRunner/hooks/usePythonEnvironment';
+import { useRunnerRegistry } from './CodeRunner/hooks/useRunnerRegistry';
+import { useSchedules } from './CodeRunner/hooks/useSchedules';
+import { useProjectEnvironment } from './CodeRunner/hooks/useProjectEnvironment';
 
 const CodeRunner = ({ nodes, selectedNode, projects, onFilesSync }) => {
   const {
@@ -26,6 +32,27 @@ const CodeRunner = ({ nodes, selectedNode, projects, onFilesSync }) => {
     checkPythonInstallation,
   } = usePythonEnvironment();
 
+  const {
+    runnerSettings,
+    availability,
+    saving,
+    runnersByExtension,
+    getRunnerForNode,
+    saveRunnerSettings,
+    resetRunnerSettings,
+  } = useRunnerRegistry();
+
+  const {
+    schedules,
+    runningSchedules,
+    createSchedule,
+    pauseSchedule,
+    resumeSchedule,
+    deleteSchedule,
+    runScheduleNow,
+    previewSchedule,
+  } = useSchedules();
+
   const {
     running,
     output,
@@ -41,8 +68,17 @@ const CodeRunner = ({ nodes, selectedNode, projects, onFilesSync }) => {
     setClearOnRun,
     autoSyncAfterRun,
     setAutoSyncAfterRun,
+    queueMode,
+    setQueueMode,
+    stopOnFailure,
+    setStopOnFailure,
+    activeRunId,
+    database,
+    setDatabase,
     runSingleFile,
+    cancelExecution,
     runQueue,
+    cancelQueue,
     runSelectedFile,
     manualSyncFiles,
     addToQueue,
@@ -50,20 +86,18 @@ const CodeRunner = ({ nodes, selectedNode, projects, onFilesSync }) => {
     clearQueue,
     clearOutput,
     clearHistory,
+    rerunExecution,
   } = useCodeExecution({
     nodes,
     selectedNode,
     projects,
     onFilesSync,
     pythonInstalled,
+    getRunnerForNode,
   });
 
   // Filter executable files
-  const executableFiles = nodes.filter(node => {
-    if (node.type !== 'file') return false;
-    const ext = node.extension?.toLowerCase();
-    return ext === 'py' || ext === 'ipynb';
-  });
+  const executableFiles = nodes.filter(node => getRunnerForNode(node) !== null);
 
   const getSelectedFileName = () => {
     if (!selectedNode) return null;
@@ -71,12 +105,25 @@ const CodeRunner = ({ nodes, selectedNode, projects, onFilesSync }) => {
     return node?.name;
   };
 
+  const selectedFile = nodes.find(n => n.id === selectedNode && n.type === 'file');
+  const selectedRunner = getRunnerForNode(selectedFile);
+  const selectedProjectId = selectedFile?.project_id || selectedFile?.projectId;
+  const selectedProject = projects?.find(p => p.id === selectedProjectId);
+
+  const {
+    environment,
+    setVariable,
+    deleteVariable,
+  } = useProjectEnvironment(selectedProjectId);
+
+  const executableExtensions = Object.keys(runnersByExtension).map(ext => `.${ext}`).join(', ');
+
   return (
     <VStack spacing={6} align="stretch" h="100%" p={4}>
       {/* Header */}
       <Flex justify="space-between" align="center" wrap="wrap" gap={4}>
         <VStack align="start" spacing={1}>
-          <Text fontSize="xl" fontWeight="bold">Python Code Runner</Text>
+          <Text fontSize="xl" fontWeight="bold">Code Runner</Text>
           <HStack spacing={2}>
             {pythonInstalled === true && (
               <Badge colorScheme="green" variant="subtle">
@@ -125,13 +172,35 @@ const CodeRunner = ({ nodes, selectedNode, projects, onFilesSync }) => {
         selectedNode={selectedNode}
         selectedFileName={getSelectedFileName()}
         pythonInstalled={pythonInstalled}
+        selectedRunner={selectedRunner}
+        database={database}
+        setDatabase={setDatabase}
         running={running}
+        canCancel={!!activeRunId}
         onRunSelectedFile={runSelectedFile}
+        onCancelExecution={cancelExecution}
         onManualSync={manualSyncFiles}
         onClearOutput={clearOutput}
         onCheckPython={checkPythonInstallation}
       />
 
+      <RunnerSettings
+        runnerSettings={runnerSettings}
+        availability={availability}
+        saving={saving}
+        onSave={saveRunnerSettings}
+        onReset={resetRunnerSettings}
+      />
+
+      {selectedProjectId && (
+        <EnvironmentPanel
+          projectName={selectedProject?.name}
+          environment={environment}
+          onSetVariable={setVariable}
+          onDeleteVariable={deleteVariable}
+        />
+      )}
+
       <Divider />
 
       {/* Execution Queue */}
@@ -141,12 +210,32 @@ const CodeRunner = ({ nodes, selectedNode, projects, onFilesSync }) => {
         nodes={nodes}
         running={running}
         pythonInstalled={pythonInstalled}
+        queueMode={queueMode}
+        setQueueMode={setQueueMode}
+        stopOnFailure={stopOnFailure}
+        setStopOnFailure={setStopOnFailure}
         onRunQueue={runQueue}
+        onCancelQueue={cancelQueue}
         onAddToQueue={addToQueue}
         onRemoveFromQueue={removeFromQueue}
         onClearQueue={clearQueue}
       />
 
+      <Divider />
+
+      {/* Scheduled runs */}
+      <SchedulePanel
+        schedules={schedules}
+        runningSchedules={runningSchedules}
+        selectedFile={selectedFile}
+        onCreateSchedule={createSchedule}
+        onPauseSchedule={pauseSchedule}
+        onResumeSchedule={resumeSchedule}
+        onDeleteSchedule={deleteSchedule}
+        onRunScheduleNow={runScheduleNow}
+        onPreviewSchedule={previewSchedule}
+      />
+
       {/* Output Console */}
       <OutputConsole
         output={output}
@@ -158,7 +247,9 @@ const CodeRunner = ({ nodes, selectedNode, projects, onFilesSync }) => {
       {/* Execution History */}
       <ExecutionHistory
         executionHistory={executionHistory}
+        running={running}
         onClearHistory={clearHistory}
+        onRerun={rerunExecution}
       />
 
       {/* No Python files message */}
@@ -166,8 +257,8 @@ const CodeRunner = ({ nodes, selectedNode, projects, onFilesSync }) => {
         <Alert status="info">
           <AlertIcon />
           <Box>
-            <Text fontWeight="medium">No Python files found</Text>
-            <Text fontSize="sm">Create some .py or .ipynb files to get started!</Text>
+            <Text fontWeight="medium">No runnable files found</Text>
+            <Text fontSize="sm">Create some {executableExtensions || '.py or .ipynb'} files to get started!</Text>
           </Box>
         </Alert>
       )}
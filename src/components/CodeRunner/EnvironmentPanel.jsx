@@ -0,0 +1,122 @@
+// src/components/CodeRunner/EnvironmentPanel.jsx - Project environment variables and secrets
+import React, { useState } from 'react';
+import {
+  VStack,
+  HStack,
+  Button,
+  Text,
+  Input,
+  Switch,
+  Badge,
+  Flex,
+  IconButton,
+  Collapse,
+  FormControl,
+  FormLabel,
+} from '@chakra-ui/react';
+import { FiKey, FiLock, FiTrash2, FiPlus } from 'react-icons/fi';
+
+const EnvironmentPanel = ({
+  projectName,
+  environment,
+  onSetVariable,
+  onDeleteVariable,
+}) => {
+  const [isOpen, setIsOpen] = useState(false);
+  const [key, setKey] = useState('');
+  const [value, setValue] = useState('');
+  const [secret, setSecret] = useState(false);
+
+  const variableNames = Object.keys(environment.variables);
+  const dotenvNames = Object.keys(environment.dotenv);
+  const total = variableNames.length + environment.secrets.length;
+
+  const handleAdd = async () => {
+    if (await onSetVariable(key.trim(), value, secret)) {
+      setKey('');
+      setValue('');
+      setSecret(false);
+    }
+  };
+
+  return (
+    <VStack spacing={3} align="stretch">
+      <Button
+        size="sm"
+        variant="ghost"
+        leftIcon={<FiKey />}
+        justifyContent="flex-start"
+        onClick={() => setIsOpen(open => !open)}
+      >
+        Environment{projectName ? ` (${projectName})` : ''}: {total} variable{total === 1 ? '' : 's'}
+      </Button>
+
+      <Collapse in={isOpen} animateOpacity>
+        <VStack spacing={2} align="stretch" p={3} bg="gray.50" borderRadius="md" _dark={{ bg: "gray.800" }}>
+          {variableNames.map(name => (
+            <Flex key={name} justify="space-between" align="center">
+              <HStack>
+                <Text fontSize="sm" fontFamily="mono">{name}</Text>
+                <Text fontSize="sm" fontFamily="mono" color="gray.500" noOfLines={1}>
+                  = {environment.variables[name]}
+                </Text>
+              </HStack>
+              <IconButton icon={<FiTrash2 />} size="xs" variant="ghost" onClick={() => onDeleteVariable(name)} />
+            </Flex>
+          ))}
+
+          {environment.secrets.map(name => (
+            <Flex key={name} justify="space-between" align="center">
+              <HStack>
+                <FiLock />
+                <Text fontSize="sm" fontFamily="mono">{name}</Text>
+                <Badge size="sm" colorScheme="purple">secret</Badge>
+              </HStack>
+              <IconButton icon={<FiTrash2 />} size="xs" variant="ghost" onClick={() => onDeleteVariable(name)} />
+            </Flex>
+          ))}
+
+          {dotenvNames.length > 0 && (
+            <Text fontSize="xs" color="gray.500">
+              From .env: {dotenvNames.join(', ')}
+            </Text>
+          )}
+
+          <HStack spacing={2}>
+            <Input
+              size="sm"
+              fontFamily="mono"
+              placeholder="NAME"
+              value={key}
+              onChange={(e) => setKey(e.target.value)}
+            />
+            <Input
+              size="sm"
+              type={secret ? 'password' : 'text'}
+              placeholder="value"
+              value={value}
+              onChange={(e) => setValue(e.target.value)}
+            />
+            <FormControl display="flex" alignItems="center" w="auto">
+              <FormLabel fontSize="xs" mb="0" mr={1}>Secret</FormLabel>
+              <Switch size="sm" isChecked={secret} onChange={(e) => setSecret(e.target.checked)} />
+            </FormControl>
+            <IconButton
+              icon={<FiPlus />}
+              size="sm"
+              colorScheme="blue"
+              isDisabled={!key.trim()}
+              onClick={handleAdd}
+            />
+          </HStack>
+
+          <Text fontSize="xs" color="gray.500">
+            Secrets are encrypted on disk and redacted from output and history. Restart a Python session to pick up changes.
+          </Text>
+        </VStack>
+      </Collapse>
+    </VStack>
+  );
+};
+
+export default EnvironmentPanel;
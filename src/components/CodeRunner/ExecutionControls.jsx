@@ -10,9 +10,11 @@ import {
   FormLabel,
   IconButton,
   Tooltip,
+  Input,
 } from '@chakra-ui/react';
 import {
   FiPlay,
+  FiSquare,
   FiRefreshCw,
   FiTrash2,
 } from 'react-icons/fi';
@@ -27,8 +29,13 @@ const ExecutionControls = ({
   selectedNode,
   selectedFileName,
   pythonInstalled,
+  selectedRunner,
+  database,
+  setDatabase,
   running,
+  canCancel,
   onRunSelectedFile,
+  onCancelExecution,
   onManualSync,
   onClearOutput,
   onCheckPython,
@@ -63,18 +70,43 @@ const ExecutionControls = ({
             onClick={onRunSelectedFile}
             isLoading={running}
             loadingText="Running"
-            isDisabled={!selectedNode || !pythonInstalled}
+            isDisabled={!selectedNode || !selectedRunner || (['python', 'jupyter'].includes(selectedRunner.id) && !pythonInstalled)}
           >
             Run Selected File
           </Button>
+
+          {canCancel && (
+            <Button
+              leftIcon={<FiSquare />}
+              colorScheme="red"
+              variant="outline"
+              size="md"
+              onClick={onCancelExecution}
+            >
+              Cancel
+            </Button>
+          )}
           
           {selectedFileName && (
             <Text fontSize="sm" color="gray.600">
-              Selected: {selectedFileName}
+              Selected: {selectedFileName}{selectedRunner ? ` (${selectedRunner.name})` : ''}
             </Text>
           )}
         </HStack>
 
+        {selectedRunner?.needs_database && (
+          <FormControl display="flex" alignItems="center">
+            <FormLabel fontSize="sm" mb="0" mr={2}>Database:</FormLabel>
+            <Input
+              size="sm"
+              maxW="320px"
+              placeholder={selectedRunner.default_database || 'data.db (relative to project)'}
+              value={database}
+              onChange={(e) => setDatabase(e.target.value)}
+            />
+          </FormControl>
+        )}
+
         {/* Action Buttons */}
         <HStack spacing={2}>
           <Tooltip label="Manual file sync">
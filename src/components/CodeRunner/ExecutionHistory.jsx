@@ -11,12 +11,15 @@ import {
 } from '@chakra-ui/react';
 import {
   FiCheck,
+  FiRotateCw,
   FiX,
 } from 'react-icons/fi';
 
 const ExecutionHistory = ({
   executionHistory,
+  running,
   onClearHistory,
+  onRerun,
 }) => {
   const formatDuration = (ms) => {
     if (ms < 1000) return `${ms}ms`;
@@ -65,11 +68,27 @@ const ExecutionHistory = ({
                   Exit: {exec.exitCode}
                 </Badge>
               )}
+              {exec.createdFiles?.length > 0 && (
+                <Badge size="sm" colorScheme="purple" title={exec.createdFiles.join('\n')}>
+                  {exec.createdFiles.length} output{exec.createdFiles.length === 1 ? '' : 's'}
+                </Badge>
+              )}
             </HStack>
             <HStack spacing={2} fontSize="xs" color="gray.600">
               <Text>{formatDuration(exec.duration)}</Text>
               <Text>•</Text>
               <Text>{formatTimestamp(exec.timestamp)}</Text>
+              {exec.recordId && (
+                <Button
+                  size="xs"
+                  variant="ghost"
+                  leftIcon={<FiRotateCw />}
+                  onClick={() => onRerun(exec.recordId)}
+                  isDisabled={running}
+                >
+                  Re-run
+                </Button>
+              )}
             </HStack>
           </Flex>
         ))}
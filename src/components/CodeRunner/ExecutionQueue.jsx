@@ -9,9 +9,13 @@ import {
   Flex,
   Badge,
   Box,
+  Switch,
+  FormControl,
+  FormLabel,
 } from '@chakra-ui/react';
 import {
   FiPlay,
+  FiSquare,
   FiTrash2,
 } from 'react-icons/fi';
 
@@ -21,7 +25,12 @@ const ExecutionQueue = ({
   nodes,
   running,
   pythonInstalled,
+  queueMode,
+  setQueueMode,
+  stopOnFailure,
+  setStopOnFailure,
   onRunQueue,
+  onCancelQueue,
   onAddToQueue,
   onRemoveFromQueue,
   onClearQueue,
@@ -42,6 +51,17 @@ const ExecutionQueue = ({
           >
             Run Queue ({executionQueue.length})
           </Button>
+          {running && (
+            <Button
+              leftIcon={<FiSquare />}
+              colorScheme="red"
+              size="sm"
+              variant="outline"
+              onClick={onCancelQueue}
+            >
+              Cancel
+            </Button>
+          )}
           <Button
             leftIcon={<FiTrash2 />}
             size="sm"
@@ -55,6 +75,28 @@ const ExecutionQueue = ({
       </HStack>
 
       <HStack wrap="wrap" spacing={3}>
+        <Select
+          size="sm"
+          maxW="150px"
+          value={queueMode}
+          onChange={(e) => setQueueMode(e.target.value)}
+          isDisabled={running}
+        >
+          <option value="sequential">Sequential</option>
+          <option value="parallel">Parallel</option>
+        </Select>
+        <FormControl display="flex" alignItems="center" w="auto">
+          <FormLabel htmlFor="stop-on-failure" mb="0" fontSize="sm">
+            Stop on failure
+          </FormLabel>
+          <Switch
+            id="stop-on-failure"
+            size="sm"
+            isChecked={stopOnFailure}
+            onChange={(e) => setStopOnFailure(e.target.checked)}
+            isDisabled={running}
+          />
+        </FormControl>
         <Select 
           placeholder="Add file to queue" 
           size="sm" 
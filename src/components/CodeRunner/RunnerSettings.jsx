@@ -0,0 +1,168 @@
+// src/components/CodeRunner/RunnerSettings.jsx - Language runner registry editor
+import React, { useState, useEffect } from 'react';
+import {
+  VStack,
+  HStack,
+  Box,
+  Button,
+  Text,
+  Input,
+  Switch,
+  Badge,
+  Collapse,
+  FormControl,
+  FormLabel,
+  useToast,
+} from '@chakra-ui/react';
+import { FiSettings, FiSave, FiRotateCcw } from 'react-icons/fi';
+
+const parseTimeout = (value) => {
+  const seconds = parseInt(value, 10);
+  return Number.isFinite(seconds) && seconds > 0 ? seconds : null;
+};
+
+const RunnerSettings = ({
+  runnerSettings,
+  availability,
+  saving,
+  onSave,
+  onReset,
+}) => {
+  const [isOpen, setIsOpen] = useState(false);
+  const [draft, setDraft] = useState(runnerSettings);
+  const toast = useToast();
+
+  useEffect(() => {
+    setDraft(runnerSettings);
+  }, [runnerSettings]);
+
+  if (!draft) return null;
+
+  const updateRunner = (index, changes) => {
+    setDraft(prev => ({
+      ...prev,
+      runners: prev.runners.map((runner, i) => (i === index ? { ...runner, ...changes } : runner)),
+    }));
+  };
+
+  const handleSave = async () => {
+    try {
+      await onSave(draft);
+      toast({ title: 'Runner settings saved', status: 'success', duration: 2000, isClosable: true });
+    } catch (error) {
+      toast({ title: 'Invalid runner settings', description: error.toString(), status: 'error', duration: 5000, isClosable: true });
+    }
+  };
+
+  const handleReset = async () => {
+    try {
+      await onReset();
+      toast({ title: 'Runner settings reset to defaults', status: 'info', duration: 2000, isClosable: true });
+    } catch (error) {
+      toast({ title: 'Reset failed', description: error.toString(), status: 'error', duration: 5000, isClosable: true });
+    }
+  };
+
+  return (
+    <VStack spacing={3} align="stretch">
+      <Button
+        size="sm"
+        variant="ghost"
+        leftIcon={<FiSettings />}
+        justifyContent="flex-start"
+        onClick={() => setIsOpen(open => !open)}
+      >
+        Language Runners ({draft.runners.filter(runner => runner.enabled).length} enabled)
+      </Button>
+
+      <Collapse in={isOpen} animateOpacity>
+        <VStack spacing={3} align="stretch" p={3} bg="gray.50" borderRadius="md" _dark={{ bg: "gray.800" }}>
+          <FormControl display="flex" alignItems="center">
+            <FormLabel fontSize="sm" mb="0" mr={2}>Default timeout (s):</FormLabel>
+            <Input
+              size="sm"
+              type="number"
+              w="100px"
+              placeholder="none"
+              value={draft.default_timeout_secs ?? ''}
+              onChange={(e) => setDraft(prev => ({ ...prev, default_timeout_secs: parseTimeout(e.target.value) }))}
+            />
+          </FormControl>
+
+          {draft.runners.map((runner, index) => {
+            const status = availability[runner.id];
+            return (
+              <Box key={runner.id} p={2} borderWidth="1px" borderRadius="md">
+                <HStack justify="space-between" mb={2}>
+                  <HStack>
+                    <Text fontSize="sm" fontWeight="medium">{runner.name}</Text>
+                    <Text fontSize="xs" color="gray.500">
+                      {runner.extensions.map(ext => `.${ext}`).join(' ')}
+                    </Text>
+                    {status && (
+                      <Badge size="sm" colorScheme={status.available ? 'green' : 'red'}>
+                        {status.available ? status.command : 'not found'}
+                      </Badge>
+                    )}
+                  </HStack>
+                  <Switch
+                    size="sm"
+                    isChecked={runner.enabled}
+                    onChange={(e) => updateRunner(index, { enabled: e.target.checked })}
+                  />
+                </HStack>
+
+                <HStack spacing={2}>
+                  <Input
+                    size="xs"
+                    title="Commands, tried in order"
+                    value={runner.commands.join(', ')}
+                    onChange={(e) => updateRunner(index, {
+                      commands: e.target.value.split(',').map(command => command.trim()).filter(Boolean),
+                    })}
+                  />
+                  <Input
+                    size="xs"
+                    title="Arguments; {file} and {database} are substituted"
+                    value={runner.args.join(' ')}
+                    onChange={(e) => updateRunner(index, { args: e.target.value.split(/\s+/).filter(Boolean) })}
+                  />
+                  <Input
+                    size="xs"
+                    type="number"
+                    w="90px"
+                    title="Timeout in seconds"
+                    placeholder="timeout"
+                    value={runner.timeout_secs ?? ''}
+                    onChange={(e) => updateRunner(index, { timeout_secs: parseTimeout(e.target.value) })}
+                  />
+                </HStack>
+
+                {runner.needs_database && (
+                  <Input
+                    size="xs"
+                    mt={2}
+                    placeholder="Default database (relative to project)"
+                    value={runner.default_database || ''}
+                    onChange={(e) => updateRunner(index, { default_database: e.target.value || null })}
+                  />
+                )}
+              </Box>
+            );
+          })}
+
+          <HStack justify="flex-end">
+            <Button size="xs" variant="outline" leftIcon={<FiRotateCcw />} onClick={handleReset}>
+              Reset
+            </Button>
+            <Button size="xs" colorScheme="blue" leftIcon={<FiSave />} onClick={handleSave} isLoading={saving}>
+              Save
+            </Button>
+          </HStack>
+        </VStack>
+      </Collapse>
+    </VStack>
+  );
+};
+
+export default RunnerSettings;
@@ -0,0 +1,190 @@
+// src/components/CodeRunner/SchedulePanel.jsx - Scheduled and recurring runs
+import React, { useState, useEffect } from 'react';
+import {
+  VStack,
+  HStack,
+  Button,
+  Text,
+  Input,
+  Select,
+  Flex,
+  Badge,
+  IconButton,
+  Tooltip,
+} from '@chakra-ui/react';
+import {
+  FiClock,
+  FiPause,
+  FiPlay,
+  FiZap,
+  FiTrash2,
+} from 'react-icons/fi';
+
+const formatTime = (seconds) => (seconds ? new Date(seconds * 1000).toLocaleString() : '—');
+
+const statusColor = {
+  success: 'green',
+  failed: 'red',
+  skipped: 'gray',
+};
+
+const SchedulePanel = ({
+  schedules,
+  runningSchedules,
+  selectedFile,
+  onCreateSchedule,
+  onPauseSchedule,
+  onResumeSchedule,
+  onDeleteSchedule,
+  onRunScheduleNow,
+  onPreviewSchedule,
+}) => {
+  const [cron, setCron] = useState('0 2 * * *');
+  const [catchUp, setCatchUp] = useState('run_once');
+  const [targetKind, setTargetKind] = useState('file');
+  const [preview, setPreview] = useState(null);
+
+  // Show the next few run times while the expression is edited
+  useEffect(() => {
+    let cancelled = false;
+    onPreviewSchedule(cron).then(times => {
+      if (!cancelled) setPreview(times);
+    });
+    return () => {
+      cancelled = true;
+    };
+  }, [cron, onPreviewSchedule]);
+
+  const projectId = selectedFile?.project_id || selectedFile?.projectId;
+
+  const handleCreate = async () => {
+    const target = targetKind === 'pipeline'
+      ? { kind: 'pipeline', project_id: projectId, force: false }
+      : {
+        kind: 'file',
+        project_id: projectId,
+        file_path: selectedFile.file_path || selectedFile.name,
+        node_id: selectedFile.id,
+      };
+
+    await onCreateSchedule({
+      name: targetKind === 'pipeline' ? `Pipeline (${selectedFile.name})` : selectedFile.name,
+      cron,
+      target,
+      catchUp,
+    });
+  };
+
+  return (
+    <VStack spacing={3} align="stretch">
+      <HStack>
+        <FiClock />
+        <Text fontWeight="medium">Schedules</Text>
+      </HStack>
+
+      <HStack spacing={2} wrap="wrap">
+        <Input
+          size="sm"
+          maxW="180px"
+          fontFamily="mono"
+          value={cron}
+          onChange={(e) => setCron(e.target.value)}
+          isInvalid={preview === null}
+          placeholder="min hour day month weekday"
+        />
+        <Select size="sm" maxW="150px" value={targetKind} onChange={(e) => setTargetKind(e.target.value)}>
+          <option value="file">Selected file</option>
+          <option value="pipeline">Project pipeline</option>
+        </Select>
+        <Select size="sm" maxW="170px" value={catchUp} onChange={(e) => setCatchUp(e.target.value)}>
+          <option value="run_once">Catch up: run once</option>
+          <option value="run_all">Catch up: run all</option>
+          <option value="skip">Catch up: skip</option>
+        </Select>
+        <Button
+          size="sm"
+          colorScheme="blue"
+          onClick={handleCreate}
+          isDisabled={!selectedFile || !projectId || preview === null}
+        >
+          Schedule
+        </Button>
+      </HStack>
+
+      {preview && preview.length > 0 && (
+        <Text fontSize="xs" color="gray.500">
+          Next: {preview.map(formatTime).join(', ')}
+        </Text>
+      )}
+
+      {schedules.length > 0 && (
+        <VStack align="stretch" spacing={1} maxH="200px" overflowY="auto">
+          {schedules.map(schedule => {
+            const lastRun = schedule.recent_runs[schedule.recent_runs.length - 1];
+            const isRunning = runningSchedules[schedule.id];
+
+            return (
+              <Flex
+                key={schedule.id}
+                justify="space-between"
+                align="center"
+                p={2}
+                borderWidth="1px"
+                borderRadius="md"
+                opacity={schedule.enabled ? 1 : 0.6}
+              >
+                <VStack align="start" spacing={0}>
+                  <HStack>
+                    <Text fontSize="sm" fontWeight="medium">{schedule.name}</Text>
+                    <Badge size="sm" fontFamily="mono">{schedule.cron}</Badge>
+                    {isRunning && <Badge size="sm" colorScheme="blue">running</Badge>}
+                    {!isRunning && lastRun && (
+                      <Badge size="sm" colorScheme={statusColor[lastRun.status]} title={lastRun.message || ''}>
+                        {lastRun.status}
+                      </Badge>
+                    )}
+                  </HStack>
+                  <Text fontSize="xs" color="gray.500">
+                    {schedule.enabled ? `Next: ${formatTime(schedule.next_run_at)}` : 'Paused'}
+                    {schedule.last_run_at ? ` • Last: ${formatTime(schedule.last_run_at)}` : ''}
+                  </Text>
+                </VStack>
+
+                <HStack spacing={1}>
+                  <Tooltip label="Run now">
+                    <IconButton
+                      icon={<FiZap />}
+                      size="xs"
+                      variant="ghost"
+                      isDisabled={isRunning}
+                      onClick={() => onRunScheduleNow(schedule.id)}
+                    />
+                  </Tooltip>
+                  <Tooltip label={schedule.enabled ? 'Pause' : 'Resume'}>
+                    <IconButton
+                      icon={schedule.enabled ? <FiPause /> : <FiPlay />}
+                      size="xs"
+                      variant="ghost"
+                      onClick={() => (schedule.enabled ? onPauseSchedule(schedule.id) : onResumeSchedule(schedule.id))}
+                    />
+                  </Tooltip>
+                  <Tooltip label="Delete">
+                    <IconButton
+                      icon={<FiTrash2 />}
+                      size="xs"
+                      variant="ghost"
+                      colorScheme="red"
+                      onClick={() => onDeleteSchedule(schedule.id)}
+                    />
+                  </Tooltip>
+                </HStack>
+              </Flex>
+            );
+          })}
+        </VStack>
+      )}
+    </VStack>
+  );
+};
+
+export default SchedulePanel;
@@ -1,7 +1,8 @@
 // src/components/CodeRunner/hooks/useCodeExecution.js - Main execution logic hook
-import { useState, useCallback } from 'react';
+import { useState, useCallback, useEffect, useRef } from 'react';
 import { useToast } from '@chakra-ui/react';
 import { invoke } from '@tauri-apps/api/tauri';
+import { listen } from '@tauri-apps/api/event';
 
 export const useCodeExecution = ({
   nodes,
@@ -9,6 +10,7 @@ export const useCodeExecution = ({
   projects,
   onFilesSync,
   pythonInstalled,
+  getRunnerForNode,
 }) => {
   const [running, setRunning] = useState(false);
   const [output, setOutput] = useState('');
@@ -18,6 +20,11 @@ export const useCodeExecution = ({
   const [autoScroll, setAutoScroll] = useState(true);
   const [clearOnRun, setClearOnRun] = useState(true);
   const [autoSyncAfterRun, setAutoSyncAfterRun] = useState(true);
+  const [queueMode, setQueueMode] = useState('sequential');
+  const [stopOnFailure, setStopOnFailure] = useState(false);
+  const [activeJobId, setActiveJobId] = useState(null);
+  const [activeRunId, setActiveRunId] = useState(null);
+  const [database, setDatabase] = useState('');
   
   const toast = useToast();
 
@@ -25,6 +32,29 @@ export const useCodeExecution = ({
     setOutput(prev => prev + text);
   }, []);
 
+  // The manual run whose output is streamed into the console as it arrives
+  const streamingRun = useRef(null);
+
+  useEffect(() => {
+    const unlistenPromise = listen('execution-output', ({ payload }) => {
+      const run = streamingRun.current;
+      if (!run || payload.trigger !== 'manual' || payload.node_id !== run.nodeId) return;
+
+      if (payload.stream === 'started') {
+        run.runId = payload.run_id;
+        run.streamed = true;
+        setActiveRunId(payload.run_id);
+        appendOutput(`$ ${payload.line}\n`);
+      } else if (payload.run_id === run.runId) {
+        appendOutput(`${payload.line}\n`);
+      }
+    });
+
+    return () => {
+      unlistenPromise.then(unlisten => unlisten());
+    };
+  }, [appendOutput]);
+
   // Auto-sync files after execution
   const autoSyncFiles = useCallback(async (projectId) => {
     if (!autoSyncAfterRun || !projectId) return;
@@ -66,6 +96,29 @@ export const useCodeExecution = ({
     }
   }, [autoSyncAfterRun, appendOutput, onFilesSync, toast]);
 
+  // Show files the backend attributed to this run and add their nodes to the tree
+  const reportArtifacts = useCallback((result) => {
+    const created = result.created_files || [];
+    const modified = result.modified_files || [];
+    const newNodes = result.new_nodes || [];
+
+    if (newNodes.length > 0 && onFilesSync) {
+      onFilesSync(newNodes);
+    }
+
+    if (result.files_overlapped && (created.length > 0 || modified.length > 0)) {
+      appendOutput(`⚠️ Another run changed this project at the same time; these files may include its outputs\n`);
+    }
+    if (created.length > 0) {
+      appendOutput(`📦 Created ${created.length} file(s):\n`);
+      created.forEach(path => appendOutput(`   📄 ${path}\n`));
+    }
+    if (modified.length > 0) {
+      appendOutput(`✏️ Modified ${modified.length} file(s):\n`);
+      modified.forEach(path => appendOutput(`   📄 ${path}\n`));
+    }
+  }, [appendOutput, onFilesSync]);
+
   const runSingleFile = useCallback(async (nodeId) => {
     const node = nodes.find(n => n.id === nodeId);
     if (!node) {
@@ -83,50 +136,55 @@ export const useCodeExecution = ({
       const projectId = node.project_id || node.projectId;
       const filePath = node.file_path || node.name;
       
-      console.log('🐍 Executing Python file:', {
+      console.log('▶️ Executing file:', {
         nodeId,
         filePath,
         projectId,
         extension: node.extension
       });
 
-      let result;
-      
-      if (node.extension?.toLowerCase() === 'py') {
-        // Execute Python script
-        result = await invoke('execute_python_file', {
-          nodeId,
-          filePath,
-          projectId,
-        });
-      } else if (node.extension?.toLowerCase() === 'ipynb') {
-        // Execute Jupyter notebook
-        result = await invoke('execute_jupyter_notebook', {
-          nodeId,
-          filePath,
-          projectId,
-        });
-      } else {
-        throw new Error(`Unsupported file type: ${node.extension}`);
+      const runner = getRunnerForNode(node);
+      if (!runner) {
+        throw new Error(`No runner configured for .${node.extension} files`);
       }
+
+      // The backend picks the runner for the extension and streams output lines
+      streamingRun.current = { nodeId, runId: null, streamed: false };
+      const result = await invoke('execute_file', {
+        nodeId,
+        filePath,
+        projectId,
+        database: runner.needs_database && database.trim() ? database.trim() : null,
+      });
       
       const endTime = Date.now();
       const duration = endTime - startTime;
       
-      // Display results
-      if (result.stdout && result.stdout.trim()) {
-        appendOutput(`📤 Output:\n${result.stdout}\n`);
-      }
-      
-      if (result.stderr && result.stderr.trim()) {
-        appendOutput(`⚠️ Errors/Warnings:\n${result.stderr}\n`);
+      // Display results unless they were already streamed
+      if (!streamingRun.current?.streamed) {
+        if (result.stdout && result.stdout.trim()) {
+          appendOutput(`📤 Output:\n${result.stdout}\n`);
+        }
+        
+        if (result.stderr && result.stderr.trim()) {
+          appendOutput(`⚠️ Errors/Warnings:\n${result.stderr}\n`);
+        }
       }
       
-      if (result.success) {
+      // Outputs are tracked per run, even when the script fails partway
+      reportArtifacts(result);
+
+      if (result.cancelled) {
+        appendOutput(`⏹️ Cancelled after ${duration}ms\n`);
+      } else if (result.timed_out) {
+        appendOutput(`⏱️ Timed out after ${duration}ms\n`);
+      } else if (result.success) {
         appendOutput(`✅ Completed successfully in ${duration}ms\n`);
         
-        // Auto-sync files after successful execution
-        await autoSyncFiles(projectId);
+        // Artifacts are already registered; fall back to a sync for older backends
+        if (!result.run_id) {
+          await autoSyncFiles(projectId);
+        }
       } else {
         appendOutput(`❌ Execution failed (Exit code: ${result.exit_code || 'unknown'})\n`);
       }
@@ -143,6 +201,8 @@ export const useCodeExecution = ({
         stdout: result.stdout,
         stderr: result.stderr,
         exitCode: result.exit_code,
+        recordId: result.run_id,
+        createdFiles: result.created_files || [],
       };
       
       setExecutionHistory(prev => [...prev, executionRecord]);
@@ -178,9 +238,144 @@ export const useCodeExecution = ({
       
       return executionRecord;
     } finally {
+      streamingRun.current = null;
+      setActiveRunId(null);
       setCurrentExecution(null);
     }
-  }, [nodes, appendOutput, autoSyncFiles, toast]);
+  }, [nodes, database, getRunnerForNode, appendOutput, autoSyncFiles, reportArtifacts, toast]);
+
+  const cancelExecution = useCallback(async () => {
+    if (!activeRunId) return;
+
+    try {
+      await invoke('cancel_execution', { runId: activeRunId });
+      appendOutput(`\n⏹️ Cancelling...\n`);
+    } catch (error) {
+      console.error('❌ Failed to cancel execution:', error);
+    }
+  }, [activeRunId, appendOutput]);
+
+  // Execution history is persisted by the backend; load the most recent runs
+  const loadHistory = useCallback(async () => {
+    try {
+      const records = await invoke('get_execution_history', { limit: 50 });
+      setExecutionHistory(records.reverse().map(record => ({
+        id: record.id,
+        recordId: (record.kind || 'script') === 'script' ? record.id : undefined,
+        // Session code and package installs have no file of their own
+        fileName: record.file_path.split('/').pop()
+          || (record.kind === 'package' ? `${record.interpreter} ${record.args.join(' ')}` : 'Python session'),
+        duration: record.duration_ms,
+        success: record.success,
+        error: record.error,
+        timestamp: new Date(record.started_at),
+        stdout: record.stdout,
+        stderr: record.stderr,
+        exitCode: record.exit_code ?? undefined,
+        createdFiles: record.created_files || [],
+      })));
+    } catch (error) {
+      console.error('❌ Failed to load execution history:', error);
+    }
+  }, []);
+
+  useEffect(() => {
+    loadHistory();
+  }, [loadHistory]);
+
+  // Reattach to a job that is still queued or running in the backend
+  useEffect(() => {
+    invoke('get_execution_queue')
+      .then(jobs => {
+        const activeJob = jobs.find(job => job.status === 'queued' || job.status === 'running');
+        if (activeJob) {
+          setActiveJobId(activeJob.id);
+          setRunning(true);
+          appendOutput(`🔗 Reattached to running queue job (${activeJob.items.length} files)\n`);
+        }
+      })
+      .catch(error => console.error('❌ Failed to load execution queue:', error));
+  }, [appendOutput]);
+
+  // Keep the latest handlers in a ref so the event listener is registered once
+  const queueHandlers = useRef({});
+  queueHandlers.current = { activeJobId, appendOutput, autoSyncFiles, toast };
+
+  useEffect(() => {
+    const unlistenPromise = listen('execution-queue-progress', async ({ payload }) => {
+      const { activeJobId, appendOutput, autoSyncFiles, toast } = queueHandlers.current;
+      if (payload.job_id !== activeJobId) return;
+
+      const jobs = await invoke('get_execution_queue');
+      const job = jobs.find(j => j.id === payload.job_id);
+      if (!job) return;
+
+      const item = payload.item_index !== null ? job.items[payload.item_index] : null;
+
+      if (item && item.status === 'running') {
+        appendOutput(`\n📋 Queue Progress: ${payload.completed + 1}/${payload.total}\n`);
+        appendOutput(`🚀 Executing: ${item.name}\n${'='.repeat(50)}\n`);
+      } else if (item && (item.status === 'success' || item.status === 'failed')) {
+        const result = item.result;
+        if (result?.stdout?.trim()) {
+          appendOutput(`📤 Output (${item.name}):\n${result.stdout}\n`);
+        }
+        if (result?.stderr?.trim()) {
+          appendOutput(`⚠️ Errors/Warnings (${item.name}):\n${result.stderr}\n`);
+        }
+        if (item.status === 'success') {
+          appendOutput(`✅ ${item.name} completed successfully in ${result.duration_ms}ms\n`);
+        } else {
+          appendOutput(`❌ ${item.name} failed: ${item.error || `exit code ${result?.exit_code ?? 'unknown'}`}\n`);
+        }
+        appendOutput(`${'='.repeat(50)}\n`);
+
+        setExecutionHistory(prev => [...prev, {
+          id: Date.now(),
+          fileName: item.name,
+          duration: result?.duration_ms || 0,
+          success: item.status === 'success',
+          error: item.error,
+          timestamp: new Date(),
+          stdout: result?.stdout,
+          stderr: result?.stderr,
+          exitCode: result?.exit_code,
+        }]);
+      } else if (item && item.status === 'skipped') {
+        appendOutput(`⏭️ Skipped: ${item.name}\n`);
+      }
+
+      if (payload.item_index === null && job.finished_at) {
+        const successCount = job.items.filter(i => i.status === 'success').length;
+        const failureCount = job.items.filter(i => i.status === 'failed').length;
+
+        appendOutput(`\n🏁 Queue execution ${payload.job_status}!\n`);
+        appendOutput(`✅ Successful: ${successCount}\n`);
+        appendOutput(`❌ Failed: ${failureCount}\n`);
+        appendOutput(`⏱️ Total files: ${job.items.length}\n\n`);
+
+        setActiveJobId(null);
+        setRunning(false);
+
+        const projectIds = [...new Set(job.items.map(i => i.project_id))];
+        for (const projectId of projectIds) {
+          await autoSyncFiles(projectId);
+        }
+
+        toast({
+          title: `Queue execution ${payload.job_status}`,
+          description: `${successCount} successful, ${failureCount} failed`,
+          status: successCount > 0 && failureCount === 0 ? 'success' : 'error',
+          duration: 3000,
+          isClosable: true,
+        });
+      }
+    });
+
+    return () => {
+      unlistenPromise.then(unlisten => unlisten());
+    };
+  }, []);
 
   const runQueue = useCallback(async () => {
     if (!pythonInstalled) {
@@ -211,47 +406,31 @@ export const useCodeExecution = ({
       if (clearOnRun) {
         setOutput('');
       }
-      
-      appendOutput(`🎯 Starting execution queue (${executionQueue.length} files)\n`);
-      appendOutput(`📅 ${new Date().toLocaleString()}\n\n`);
 
-      let successCount = 0;
-      let failureCount = 0;
-
-      for (let i = 0; i < executionQueue.length; i++) {
-        const nodeId = executionQueue[i];
-        appendOutput(`\n📋 Queue Progress: ${i + 1}/${executionQueue.length}\n`);
-        
-        const result = await runSingleFile(nodeId);
-        
-        if (result.success) {
-          successCount++;
-        } else {
-          failureCount++;
-        }
-        
-        // Small delay between executions to prevent overwhelming the system
-        if (i < executionQueue.length - 1) {
-          await new Promise(resolve => setTimeout(resolve, 500));
-        }
-      }
+      const items = executionQueue
+        .map(nodeId => nodes.find(n => n.id === nodeId))
+        .filter(Boolean)
+        .map(node => ({
+          node_id: node.id,
+          project_id: node.project_id || node.projectId,
+          file_path: node.file_path || node.name,
+          name: node.name,
+        }));
 
-      appendOutput(`\n🏁 Queue execution completed!\n`);
-      appendOutput(`✅ Successful: ${successCount}\n`);
-      appendOutput(`❌ Failed: ${failureCount}\n`);
-      appendOutput(`⏱️ Total files: ${executionQueue.length}\n\n`);
+      appendOutput(`🎯 Starting execution queue (${items.length} files, ${queueMode})\n`);
+      appendOutput(`📅 ${new Date().toLocaleString()}\n\n`);
 
-      toast({
-        title: 'Queue execution completed',
-        description: `${successCount} successful, ${failureCount} failed`,
-        status: successCount > 0 ? 'success' : 'error',
-        duration: 3000,
-        isClosable: true,
+      // The backend owns the queue, so it keeps running if this panel is closed
+      const job = await invoke('enqueue_execution_job', {
+        items,
+        mode: queueMode,
+        stopOnFailure,
       });
-      
+      setActiveJobId(job.id);
     } catch (error) {
       console.error('❌ Queue execution failed:', error);
       appendOutput(`\n❌ Queue execution failed: ${error}\n`);
+      setRunning(false);
       
       toast({
         title: 'Queue execution failed',
@@ -260,16 +439,26 @@ export const useCodeExecution = ({
         duration: 5000,
         isClosable: true,
       });
-    } finally {
-      setRunning(false);
     }
-  }, [pythonInstalled, executionQueue, clearOnRun, appendOutput, runSingleFile, toast]);
+  }, [pythonInstalled, executionQueue, nodes, clearOnRun, queueMode, stopOnFailure, appendOutput, toast]);
+
+  const cancelQueue = useCallback(async () => {
+    if (!activeJobId) return;
+
+    try {
+      await invoke('cancel_execution_job', { jobId: activeJobId });
+      appendOutput(`\n⏹️ Cancelling queue after the running file finishes...\n`);
+    } catch (error) {
+      console.error('❌ Failed to cancel queue:', error);
+      appendOutput(`⚠️ Failed to cancel queue: ${error}\n`);
+    }
+  }, [activeJobId, appendOutput]);
 
   const runSelectedFile = useCallback(async () => {
     if (!selectedNode) {
       toast({
         title: 'No file selected',
-        description: 'Please select a Python file to run',
+        description: 'Please select a file to run',
         status: 'warning',
         duration: 3000,
         isClosable: true,
@@ -278,10 +467,11 @@ export const useCodeExecution = ({
     }
 
     const node = nodes.find(n => n.id === selectedNode);
-    if (!node || node.type !== 'file' || !['py', 'ipynb'].includes(node.extension?.toLowerCase())) {
+    const runner = getRunnerForNode(node);
+    if (!runner) {
       toast({
         title: 'Invalid file type',
-        description: 'Please select a .py or .ipynb file',
+        description: 'No runner is configured for this file type',
         status: 'warning',
         duration: 3000,
         isClosable: true,
@@ -289,7 +479,7 @@ export const useCodeExecution = ({
       return;
     }
 
-    if (!pythonInstalled) {
+    if (['python', 'jupyter'].includes(runner.id) && !pythonInstalled) {
       toast({
         title: 'Python not found',
         description: 'Please install Python to run files',
@@ -314,7 +504,7 @@ export const useCodeExecution = ({
     } finally {
       setRunning(false);
     }
-  }, [selectedNode, nodes, pythonInstalled, clearOnRun, runSingleFile, toast]);
+  }, [selectedNode, nodes, pythonInstalled, clearOnRun, getRunnerForNode, runSingleFile, toast]);
 
   // Manual sync function
   const manualSyncFiles = useCallback(async () => {
@@ -362,10 +552,38 @@ export const useCodeExecution = ({
     setOutput('');
   }, []);
 
-  const clearHistory = useCallback(() => {
-    setExecutionHistory([]);
+  const clearHistory = useCallback(async () => {
+    try {
+      await invoke('prune_execution_history', { keepLast: 0 });
+      setExecutionHistory([]);
+    } catch (error) {
+      console.error('❌ Failed to clear execution history:', error);
+    }
   }, []);
 
+  const rerunExecution = useCallback(async (recordId) => {
+    try {
+      setRunning(true);
+      appendOutput(`\n🔁 Re-running previous execution...\n`);
+      const result = await invoke('rerun_execution', { recordId });
+
+      if (result.stdout && result.stdout.trim()) {
+        appendOutput(`📤 Output:\n${result.stdout}\n`);
+      }
+      if (result.stderr && result.stderr.trim()) {
+        appendOutput(`⚠️ Errors/Warnings:\n${result.stderr}\n`);
+      }
+      appendOutput(result.success
+        ? `✅ Completed successfully in ${result.duration_ms}ms\n`
+        : `❌ Execution failed (Exit code: ${result.exit_code || 'unknown'})\n`);
+    } catch (error) {
+      appendOutput(`\n❌ Error: ${error}\n`);
+    } finally {
+      setRunning(false);
+      await loadHistory();
+    }
+  }, [appendOutput, loadHistory]);
+
   return {
     // State
     running,
@@ -382,10 +600,19 @@ export const useCodeExecution = ({
     setClearOnRun,
     autoSyncAfterRun,
     setAutoSyncAfterRun,
+    queueMode,
+    setQueueMode,
+    stopOnFailure,
+    setStopOnFailure,
+    activeRunId,
+    database,
+    setDatabase,
     
     // Actions
     runSingleFile,
+    cancelExecution,
     runQueue,
+    cancelQueue,
     runSelectedFile,
     manualSyncFiles,
     addToQueue,
@@ -393,6 +620,7 @@ export const useCodeExecution = ({
     clearQueue,
     clearOutput,
     clearHistory,
+    rerunExecution,
     appendOutput,
   };
 };
\ No newline at end of file
@@ -0,0 +1,72 @@
+// src/components/CodeRunner/hooks/useProjectEnvironment.js - Project env vars and secrets hook
+import { useState, useEffect, useCallback } from 'react';
+import { useToast } from '@chakra-ui/react';
+import { invoke } from '@tauri-apps/api/tauri';
+
+const EMPTY_ENVIRONMENT = { variables: {}, secrets: [], dotenv: {} };
+
+export const useProjectEnvironment = (projectId) => {
+  const [environment, setEnvironment] = useState(EMPTY_ENVIRONMENT);
+  const [loading, setLoading] = useState(false);
+  const toast = useToast();
+
+  const loadEnvironment = useCallback(async () => {
+    if (!projectId) {
+      setEnvironment(EMPTY_ENVIRONMENT);
+      return;
+    }
+
+    try {
+      setLoading(true);
+      setEnvironment(await invoke('get_project_environment', { projectId }));
+    } catch (error) {
+      console.error('❌ Failed to load project environment:', error);
+    } finally {
+      setLoading(false);
+    }
+  }, [projectId]);
+
+  useEffect(() => {
+    loadEnvironment();
+  }, [loadEnvironment]);
+
+  const setVariable = useCallback(async (key, value, secret = false) => {
+    try {
+      await invoke('set_project_env_var', { projectId, key, value, secret });
+      await loadEnvironment();
+      return true;
+    } catch (error) {
+      toast({
+        title: 'Failed to save variable',
+        description: error.toString(),
+        status: 'error',
+        duration: 5000,
+        isClosable: true,
+      });
+      return false;
+    }
+  }, [projectId, loadEnvironment, toast]);
+
+  const deleteVariable = useCallback(async (key) => {
+    try {
+      await invoke('delete_project_env_var', { projectId, key });
+      await loadEnvironment();
+    } catch (error) {
+      toast({
+        title: 'Failed to delete variable',
+        description: error.toString(),
+        status: 'error',
+        duration: 5000,
+        isClosable: true,
+      });
+    }
+  }, [projectId, loadEnvironment, toast]);
+
+  return {
+    environment,
+    loading,
+    loadEnvironment,
+    setVariable,
+    deleteVariable,
+  };
+};
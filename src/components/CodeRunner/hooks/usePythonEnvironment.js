@@ -37,13 +37,14 @@ export const usePythonEnvironment = () => {
     }
   };
 
-  const installPackage = async (packageName, pythonCmd = null) => {
+  const installPackage = async (packageName, pythonCmd = null, projectId = null) => {
     try {
       console.log('📦 Installing Python package:', packageName);
       
       const result = await invoke('install_python_package', {
         packageName,
         pythonCmd,
+        projectId,
       });
       
       console.log('✅ Package installation result:', result);
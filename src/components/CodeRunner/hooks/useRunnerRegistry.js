@@ -0,0 +1,80 @@
+// src/components/CodeRunner/hooks/useRunnerRegistry.js - Language runner settings hook
+import { useState, useEffect, useCallback, useMemo } from 'react';
+import { invoke } from '@tauri-apps/api/tauri';
+
+export const useRunnerRegistry = () => {
+  const [runnerSettings, setRunnerSettings] = useState(null);
+  const [availability, setAvailability] = useState({});
+  const [saving, setSaving] = useState(false);
+
+  const checkAvailability = useCallback(async () => {
+    try {
+      const results = await invoke('check_runner_availability');
+      setAvailability(Object.fromEntries(results.map(result => [result.id, result])));
+    } catch (error) {
+      console.error('❌ Failed to check runner availability:', error);
+    }
+  }, []);
+
+  const loadRunnerSettings = useCallback(async () => {
+    try {
+      setRunnerSettings(await invoke('get_runner_settings'));
+    } catch (error) {
+      console.error('❌ Failed to load runner settings:', error);
+    }
+  }, []);
+
+  useEffect(() => {
+    loadRunnerSettings();
+    checkAvailability();
+  }, [loadRunnerSettings, checkAvailability]);
+
+  const saveRunnerSettings = useCallback(async (settings) => {
+    try {
+      setSaving(true);
+      const saved = await invoke('save_runner_settings', { settings });
+      setRunnerSettings(saved);
+      await checkAvailability();
+      return saved;
+    } finally {
+      setSaving(false);
+    }
+  }, [checkAvailability]);
+
+  const resetRunnerSettings = useCallback(async () => {
+    const defaults = await invoke('reset_runner_settings');
+    setRunnerSettings(defaults);
+    await checkAvailability();
+    return defaults;
+  }, [checkAvailability]);
+
+  // Extension (lowercase, no dot) -> enabled runner
+  const runnersByExtension = useMemo(() => {
+    const map = {};
+    (runnerSettings?.runners || [])
+      .filter(runner => runner.enabled)
+      .forEach(runner => {
+        runner.extensions.forEach(ext => {
+          map[ext.replace(/^\./, '').toLowerCase()] = runner;
+        });
+      });
+    return map;
+  }, [runnerSettings]);
+
+  const getRunnerForNode = useCallback((node) => {
+    if (!node || node.type !== 'file') return null;
+    return runnersByExtension[node.extension?.toLowerCase()] || null;
+  }, [runnersByExtension]);
+
+  return {
+    runnerSettings,
+    availability,
+    saving,
+    runnersByExtension,
+    getRunnerForNode,
+    loadRunnerSettings,
+    saveRunnerSettings,
+    resetRunnerSettings,
+    checkAvailability,
+  };
+};
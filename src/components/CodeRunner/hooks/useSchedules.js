@@ -0,0 +1,92 @@
+// src/components/CodeRunner/hooks/useSchedules.js - Scheduled runs hook
+import { useState, useEffect, useCallback } from 'react';
+import { useToast } from '@chakra-ui/react';
+import { invoke } from '@tauri-apps/api/tauri';
+import { listen } from '@tauri-apps/api/event';
+
+export const useSchedules = () => {
+  const [schedules, setSchedules] = useState([]);
+  const [runningSchedules, setRunningSchedules] = useState({});
+  const toast = useToast();
+
+  const loadSchedules = useCallback(async () => {
+    try {
+      setSchedules(await invoke('list_schedules'));
+    } catch (error) {
+      console.error('❌ Failed to load schedules:', error);
+    }
+  }, []);
+
+  useEffect(() => {
+    loadSchedules();
+
+    const unlistenPromise = listen('schedule-run', ({ payload }) => {
+      setRunningSchedules(prev => ({ ...prev, [payload.schedule_id]: payload.status === 'running' }));
+      if (payload.status !== 'running') {
+        loadSchedules();
+      }
+    });
+
+    return () => {
+      unlistenPromise.then(unlisten => unlisten());
+    };
+  }, [loadSchedules]);
+
+  // Wraps a schedule command with a toast on failure and a list refresh
+  const withRefresh = useCallback(async (command, args, errorTitle) => {
+    try {
+      const result = await invoke(command, args);
+      await loadSchedules();
+      return result;
+    } catch (error) {
+      toast({
+        title: errorTitle,
+        description: error.toString(),
+        status: 'error',
+        duration: 5000,
+        isClosable: true,
+      });
+      return null;
+    }
+  }, [loadSchedules, toast]);
+
+  const createSchedule = useCallback((schedule) => (
+    withRefresh('create_schedule', schedule, 'Failed to create schedule')
+  ), [withRefresh]);
+
+  const pauseSchedule = useCallback((scheduleId) => (
+    withRefresh('pause_schedule', { scheduleId }, 'Failed to pause schedule')
+  ), [withRefresh]);
+
+  const resumeSchedule = useCallback((scheduleId) => (
+    withRefresh('resume_schedule', { scheduleId }, 'Failed to resume schedule')
+  ), [withRefresh]);
+
+  const deleteSchedule = useCallback((scheduleId) => (
+    withRefresh('delete_schedule', { scheduleId }, 'Failed to delete schedule')
+  ), [withRefresh]);
+
+  const runScheduleNow = useCallback((scheduleId) => (
+    withRefresh('run_schedule_now', { scheduleId }, 'Failed to start schedule')
+  ), [withRefresh]);
+
+  const previewSchedule = useCallback(async (cron) => {
+    try {
+      return await invoke('preview_schedule', { cron, count: 3 });
+    } catch (error) {
+      return null;
+    }
+  }, []);
+
+  return {
+    schedules,
+    runningSchedules,
+    loadSchedules,
+    createSchedule,
+    pauseSchedule,
+    resumeSchedule,
+    deleteSchedule,
+    runScheduleNow,
+    previewSchedule,
+  };
+};
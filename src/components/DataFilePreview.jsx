@@ -13,6 +13,7 @@ import {
   Icon,
   Button,
   Flex,
+  Image,
   useColorModeValue,
 } from '@chakra-ui/react';
 import { 
@@ -30,12 +31,25 @@ import {
 } from 'react-icons/fi';
 import { invoke } from '@tauri-apps/api/tauri';
 
+const IMAGE_MIME_TYPES = {
+  png: 'image/png',
+  jpg: 'image/jpeg',
+  jpeg: 'image/jpeg',
+  gif: 'image/gif',
+  bmp: 'image/bmp',
+  webp: 'image/webp',
+  ico: 'image/x-icon',
+  svg: 'image/svg+xml',
+};
+const MAX_IMAGE_PREVIEW_BYTES = 16 * 1024 * 1024;
+
 const DataFilePreview = ({ node, projectId }) => {
   const [fileContent, setFileContent] = useState('');
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState('');
   const [fileStats, setFileStats] = useState(null);
   const [showRawContent, setShowRawContent] = useState(false);
+  const [imageUrl, setImageUrl] = useState('');
 
   const bgColor = useColorModeValue('gray.50', 'gray.800');
   const borderColor = useColorModeValue('gray.200', 'gray.600');
@@ -54,18 +68,45 @@ const DataFilePreview = ({ node, projectId }) => {
       });
       setFileStats(stats);
       
-      // Only try to load content for non-binary files or if user wants to see raw content
-      if (!stats.is_binary || showRawContent) {
-        const content = await invoke('get_file_content', {
-          nodeId: node.id,
-          filePath: node.file_path || node.name,
-          projectId,
+      const fileRequest = {
+        project_id: projectId,
+        node_id: node.id,
+        file_path: node.file_path || node.name,
+      };
+      
+      // Images are shown inline from their bytes
+      const imageType = IMAGE_MIME_TYPES[node.extension?.toLowerCase()];
+      if (imageType && stats.size <= MAX_IMAGE_PREVIEW_BYTES) {
+        const bytes = await invoke('read_file_bytes', { request: fileRequest });
+        setImageUrl(`data:${imageType};base64,${bytes.data}`);
+      } else {
+        setImageUrl('');
+      }
+      
+      if (stats.is_binary && showRawContent) {
+        // Raw view of a binary file: printable ASCII of the first bytes, dots elsewhere
+        const bytes = await invoke('read_file_bytes', {
+          request: { ...fileRequest, offset: 0, length: 16384 },
+        });
+        const raw = Array.from(atob(bytes.data), (char) => {
+          const code = char.charCodeAt(0);
+          return code === 10 || (code >= 32 && code < 127) ? char : '.';
+        }).join('');
+        setFileContent(bytes.eof ? raw : raw + `\n\n... (showing first ${formatFileSize(bytes.length)} of ${formatFileSize(bytes.total_size)})`);
+      } else if (!stats.is_binary) {
+        // Only the start of the file is read, so huge files preview instantly
+        const range = await invoke('read_file_range', {
+          request: {
+            ...fileRequest,
+            mode: 'bytes',
+            offset: 0,
+            length: 16384,
+          },
         });
         
-        // Truncate large files for preview
-        const truncatedContent = content.length > 5000 
-          ? content.substring(0, 5000) + '\n\n... (file truncated for preview - showing first 5000 characters)'
-          : content;
+        const truncatedContent = range.eof
+          ? range.content
+          : range.content + `\n\n... (file truncated for preview - showing first ${formatFileSize(range.length)} of ${formatFileSize(range.total_size)}, ~${range.total_lines.toLocaleString()} lines)`;
           
         setFileContent(truncatedContent);
       } else {
@@ -259,7 +300,16 @@ const DataFilePreview = ({ node, projectId }) => {
               <VStack align="start" spacing={1}>
                 <Text fontSize="xs" color="gray.500" fontWeight="medium">TYPE</Text>
                 <Text fontSize="sm">{fileStats.file_type}</Text>
+                {fileStats.mime_type && (
+                  <Text fontSize="xs" color="gray.500">{fileStats.mime_type}</Text>
+                )}
               </VStack>
+              {fileStats.encoding && (
+                <VStack align="start" spacing={1}>
+                  <Text fontSize="xs" color="gray.500" fontWeight="medium">ENCODING</Text>
+                  <Text fontSize="sm">{fileStats.encoding} • {fileStats.line_ending}</Text>
+                </VStack>
+              )}
             </HStack>
           </Box>
         )}
@@ -274,13 +324,19 @@ const DataFilePreview = ({ node, projectId }) => {
             borderColor={borderColor}
             textAlign="center"
           >
-            <Icon as={getFileIcon()} boxSize={16} color={getFileIconColor()} mb={4} />
-            <Text fontSize="lg" fontWeight="medium" mb={2}>
-              Binary File
-            </Text>
-            <Text fontSize="sm" color="gray.600" mb={4}>
-              This is a binary file that cannot be displayed as text.
-            </Text>
+            {imageUrl ? (
+              <Image src={imageUrl} alt={node.name} maxH="400px" mx="auto" mb={4} objectFit="contain" />
+            ) : (
+              <>
+                <Icon as={getFileIcon()} boxSize={16} color={getFileIconColor()} mb={4} />
+                <Text fontSize="lg" fontWeight="medium" mb={2}>
+                  Binary File
+                </Text>
+                <Text fontSize="sm" color="gray.600" mb={4}>
+                  This is a binary file that cannot be displayed as text.
+                </Text>
+              </>
+            )}
             <Text fontSize="xs" color="gray.500" mb={4}>
               File type: {fileStats.file_type} • Size: {formatFileSize(fileStats.size)}
             </Text>
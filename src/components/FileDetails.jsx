@@ -25,16 +25,15 @@ const FileDetails = ({ node }) => {
     if (!node || node.type !== 'file') return;
     
     try {
-      // FIXED: Use the correct parameters for get_file_content
-      const content = await invoke('get_file_content', { 
-        nodeId: node.id,
+      // Stats come from disk; reading the whole file just for its size froze on big files
+      const stats = await invoke('get_file_stats', {
         filePath: node.file_path || node.name,
         projectId: node.project_id || node.projectId
       });
       
       setFileStats({
-        size: new Blob([content]).size,
-        modified: new Date(),
+        size: stats.size,
+        modified: new Date(stats.modified * 1000),
       });
     } catch (error) {
       console.error('Failed to get file stats:', error);
@@ -1,6 +1,6 @@
 // src/components/FileEditor.jsx - Fixed version with proper API calls
 import { useState, useEffect } from 'react';
-import { Box, Textarea, Button, Flex, useToast, Alert, AlertIcon } from '@chakra-ui/react';
+import { Box, Textarea, Button, Flex, useToast, Alert, AlertIcon, AlertDescription, HStack } from '@chakra-ui/react';
 import { invoke } from '@tauri-apps/api/tauri';
 
 const FileEditor = ({ nodeId, nodes }) => {
@@ -8,6 +8,8 @@ const FileEditor = ({ nodeId, nodes }) => {
   const [isSaving, setIsSaving] = useState(false);
   const [isLoading, setIsLoading] = useState(false);
   const [isBinary, setIsBinary] = useState(false);
+  const [version, setVersion] = useState(null);
+  const [conflict, setConflict] = useState(null);
   const toast = useToast();
   
   // Find the current node to get file path and project info
@@ -24,19 +26,20 @@ const FileEditor = ({ nodeId, nodes }) => {
     
     try {
       setIsLoading(true);
-      const fileContent = await invoke('get_file_content', { 
+      const file = await invoke('get_file_content_versioned', { 
         nodeId: nodeId,
         filePath: currentNode.file_path || currentNode.name,
         projectId: currentNode.project_id || currentNode.projectId
       });
       
-      // Check if it's a binary file
-      if (fileContent === '[Binary file - content not displayable]') {
+      setConflict(null);
+      setVersion(file.version);
+      if (file.is_binary) {
         setIsBinary(true);
         setContent('');
       } else {
         setIsBinary(false);
-        setContent(fileContent);
+        setContent(file.content);
       }
     } catch (error) {
       console.error('Failed to load file content:', error);
@@ -52,18 +55,31 @@ const FileEditor = ({ nodeId, nodes }) => {
     }
   };
   
-  const saveContent = async () => {
+  // Saves report changes made on disk since loading as a JSON conflict error
+  const parseConflict = (error) => {
+    try {
+      const parsed = JSON.parse(error);
+      return parsed.error === 'conflict' ? parsed : null;
+    } catch {
+      return null;
+    }
+  };
+  
+  const saveContent = async (overwrite = false) => {
     if (!currentNode || isBinary) return;
     
     try {
       setIsSaving(true);
-      await invoke('save_file_content', { 
+      const savedVersion = await invoke('save_file_content', { 
         nodeId: nodeId,
         content: content,
         filePath: currentNode.file_path || currentNode.name,
-        projectId: currentNode.project_id || currentNode.projectId
+        projectId: currentNode.project_id || currentNode.projectId,
+        expectedVersion: overwrite ? null : version
       });
       
+      setVersion(savedVersion);
+      setConflict(null);
       toast({
         title: 'File saved',
         status: 'success',
@@ -71,6 +87,11 @@ const FileEditor = ({ nodeId, nodes }) => {
         isClosable: true,
       });
     } catch (error) {
+      const saveConflict = parseConflict(error);
+      if (saveConflict) {
+        setConflict(saveConflict);
+        return;
+      }
       console.error('Failed to save file content:', error);
       toast({
         title: 'Error saving file',
@@ -84,6 +105,19 @@ const FileEditor = ({ nodeId, nodes }) => {
     }
   };
 
+  const takeDiskVersion = () => {
+    setContent(conflict.current_content ?? '');
+    setVersion(conflict.current_version);
+    setConflict(null);
+  };
+  
+  // Keeps both texts so nothing is lost; the user resolves the markers by hand
+  const mergeWithDiskVersion = () => {
+    setContent(`<<<<<<< Your changes\n${content}\n=======\n${conflict.current_content ?? ''}\n>>>>>>> On disk\n`);
+    setVersion(conflict.current_version);
+    setConflict(null);
+  };
+
   if (!currentNode) {
     return (
       <Box p={4}>
@@ -119,6 +153,21 @@ const FileEditor = ({ nodeId, nodes }) => {
   
   return (
     <Box h="100%" display="flex" flexDirection="column">
+      {conflict && (
+        <Alert status="warning" mb={4}>
+          <AlertIcon />
+          <AlertDescription flex="1">{conflict.message}</AlertDescription>
+          <HStack spacing={2}>
+            {conflict.current_content !== null && conflict.current_content !== undefined && (
+              <>
+                <Button size="sm" onClick={mergeWithDiskVersion}>Merge</Button>
+                <Button size="sm" onClick={takeDiskVersion}>Use disk version</Button>
+              </>
+            )}
+            <Button size="sm" colorScheme="red" onClick={() => saveContent(true)}>Overwrite</Button>
+          </HStack>
+        </Alert>
+      )}
       <Textarea
         value={content}
         onChange={(e) => setContent(e.target.value)}
@@ -131,7 +180,7 @@ const FileEditor = ({ nodeId, nodes }) => {
       <Flex justify="flex-end">
         <Button 
           colorScheme="blue" 
-          onClick={saveContent}
+          onClick={() => saveContent()}
           isLoading={isSaving}
           loadingText="Saving..."
           isDisabled={isBinary}